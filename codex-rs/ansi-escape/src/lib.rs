@@ -2,21 +2,77 @@ use ansi_to_tui::Error;
 use ansi_to_tui::IntoText;
 use ratatui::text::Line;
 use ratatui::text::Text;
+use unicode_width::UnicodeWidthChar;
 
-// Expand tabs in a best-effort way for transcript rendering.
+/// Tab width used by [`ansi_escape_line`].
+const DEFAULT_TAB_WIDTH: usize = 4;
+
+// Expand tabs for transcript rendering.
 // Tabs can interact poorly with left-gutter prefixes in our TUI and CLI
 // transcript views (e.g., `nl` separates line numbers from content with a tab).
-// Replacing tabs with spaces avoids odd visual artifacts without changing
-// semantics for our use cases.
-fn expand_tabs(s: &str) -> std::borrow::Cow<'_, str> {
-    if s.contains('\t') {
-        // Keep it simple: replace each tab with 4 spaces.
-        // We do not try to align to tab stops since most usages (like `nl`)
-        // look acceptable with a fixed substitution and this avoids stateful math
-        // across spans.
-        std::borrow::Cow::Owned(s.replace('\t', "    "))
-    } else {
-        std::borrow::Cow::Borrowed(s)
+// Each tab advances to the next multiple of `width` columns, counting only
+// visible characters (escape sequences take no columns).
+fn expand_tabs(s: &str, width: usize) -> std::borrow::Cow<'_, str> {
+    if !s.contains('\t') {
+        return std::borrow::Cow::Borrowed(s);
+    }
+    let width = width.max(1);
+    let mut out = String::with_capacity(s.len());
+    let mut column = 0;
+    let mut chars = s.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\t' => {
+                let pad = width - column % width;
+                out.extend(std::iter::repeat_n(' ', pad));
+                column += pad;
+            }
+            '\n' | '\r' => {
+                out.push(ch);
+                column = 0;
+            }
+            '\u{1b}' => {
+                out.push(ch);
+                copy_escape_sequence(&mut chars, &mut out);
+            }
+            _ => {
+                out.push(ch);
+                column += UnicodeWidthChar::width(ch).unwrap_or(0);
+            }
+        }
+    }
+    std::borrow::Cow::Owned(out)
+}
+
+// Copy the remainder of an escape sequence (after ESC) to `out` unchanged.
+fn copy_escape_sequence(chars: &mut std::iter::Peekable<std::str::Chars<'_>>, out: &mut String) {
+    match chars.next() {
+        // CSI: parameters and intermediates, then a final byte in '@'..='~'.
+        Some('[') => {
+            out.push('[');
+            for ch in chars.by_ref() {
+                out.push(ch);
+                if ('@'..='~').contains(&ch) {
+                    break;
+                }
+            }
+        }
+        // OSC: terminated by BEL or ST (ESC \).
+        Some(']') => {
+            out.push(']');
+            while let Some(ch) = chars.next() {
+                out.push(ch);
+                if ch == '\u{7}' {
+                    break;
+                }
+                if ch == '\u{1b}' && chars.peek() == Some(&'\\') {
+                    out.extend(chars.next());
+                    break;
+                }
+            }
+        }
+        Some(ch) => out.push(ch),
+        None => {}
     }
 }
 
@@ -24,8 +80,14 @@ fn expand_tabs(s: &str) -> std::borrow::Cow<'_, str> {
 /// a single line. If multiple lines are found, a warning is logged and only the
 /// first line is returned.
 pub fn ansi_escape_line(s: &str) -> Line<'static> {
+    ansi_escape_line_with_tab_width(s, DEFAULT_TAB_WIDTH)
+}
+
+/// Like [`ansi_escape_line`], but tabs advance to the next multiple of `width`
+/// columns.
+pub fn ansi_escape_line_with_tab_width(s: &str, width: usize) -> Line<'static> {
     // Normalize tabs to spaces to avoid odd gutter collisions in transcript mode.
-    let s = expand_tabs(s);
+    let s = expand_tabs(s, width);
     let text = ansi_escape(&s);
     match text.lines.as_slice() {
         [] => "".into(),
@@ -38,6 +100,93 @@ pub fn ansi_escape_line(s: &str) -> Line<'static> {
 }
 
 pub fn ansi_escape(s: &str) -> Text<'static> {
+    let (s, _) = extract_hyperlinks(s);
+    parse_ansi(&s)
+}
+
+/// A terminal hyperlink (OSC 8) found by [`ansi_escape_with_links`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hyperlink {
+    /// Index of the line in the returned [`Text`] where the label starts.
+    pub line: usize,
+    /// Visible text of the link.
+    pub label: String,
+    pub url: String,
+}
+
+/// Like [`ansi_escape`], but also returns the OSC 8 hyperlinks found in `s`.
+/// Link labels stay in the text (underlined); the URLs are only available
+/// through the returned list since ratatui spans cannot carry them.
+pub fn ansi_escape_with_links(s: &str) -> (Text<'static>, Vec<Hyperlink>) {
+    let (s, links) = extract_hyperlinks(s);
+    (parse_ansi(&s), links)
+}
+
+const OSC8_PREFIX: &str = "\u{1b}]8;";
+const UNDERLINE_ON: &str = "\u{1b}[4m";
+const UNDERLINE_OFF: &str = "\u{1b}[24m";
+
+// ansi_to_tui drops OSC 8 sequences together with the text that follows them,
+// so replace each link with its underlined label before parsing.
+fn extract_hyperlinks(s: &str) -> (std::borrow::Cow<'_, str>, Vec<Hyperlink>) {
+    if !s.contains(OSC8_PREFIX) {
+        return (std::borrow::Cow::Borrowed(s), Vec::new());
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut links = Vec::new();
+    // URL of the link being read and where its label starts in `out`.
+    let mut open: Option<(String, usize)> = None;
+    let mut rest = s;
+    while let Some(idx) = rest.find(OSC8_PREFIX) {
+        out.push_str(&rest[..idx]);
+        let body = &rest[idx + OSC8_PREFIX.len()..];
+        let Some((body_len, terminator_len)) = find_osc_terminator(body) else {
+            // Unterminated sequence: leave the rest for the parser.
+            out.push_str(&rest[idx..]);
+            rest = "";
+            break;
+        };
+        let url = body[..body_len]
+            .split_once(';')
+            .map(|(_, url)| url)
+            .unwrap_or_default();
+        if let Some((url, start)) = open.take() {
+            links.push(hyperlink(&out, start, url));
+            out.push_str(UNDERLINE_OFF);
+        }
+        if !url.is_empty() {
+            out.push_str(UNDERLINE_ON);
+            open = Some((url.to_string(), out.len()));
+        }
+        rest = &body[body_len + terminator_len..];
+    }
+    out.push_str(rest);
+    if let Some((url, start)) = open {
+        links.push(hyperlink(&out, start, url));
+    }
+    (std::borrow::Cow::Owned(out), links)
+}
+
+// Parameters and URL run until BEL or ST (ESC \). Returns the body length
+// and the terminator length.
+fn find_osc_terminator(body: &str) -> Option<(usize, usize)> {
+    let end = body.find(['\u{7}', '\u{1b}'])?;
+    match &body[end..] {
+        tail if tail.starts_with('\u{7}') => Some((end, 1)),
+        tail if tail.starts_with("\u{1b}\\") => Some((end, 2)),
+        _ => None,
+    }
+}
+
+fn hyperlink(out: &str, start: usize, url: String) -> Hyperlink {
+    Hyperlink {
+        line: out[..start].matches('\n').count(),
+        label: strip_ansi(&out[start..]),
+        url,
+    }
+}
+
+fn parse_ansi(s: &str) -> Text<'static> {
     // to_text() claims to be faster, but introduces complex lifetime issues
     // such that it's not worth it.
     match s.into_text() {
@@ -56,3 +205,120 @@ pub fn ansi_escape(s: &str) -> Text<'static> {
         },
     }
 }
+
+/// Remove ANSI escape sequences (SGR colors, cursor movement, ...) from `s` and
+/// return the plain text. Uses the same parser as [`ansi_escape`], so the
+/// result matches what is rendered.
+pub fn strip_ansi(s: &str) -> String {
+    ansi_escape(s)
+        .lines
+        .iter()
+        .map(|line| {
+            line.spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use ratatui::style::Modifier;
+
+    #[test]
+    fn strip_ansi_removes_sgr_colors() {
+        assert_eq!(
+            strip_ansi("\u{1b}[1;31merror\u{1b}[0m: \u{1b}[32mok\u{1b}[0m"),
+            "error: ok"
+        );
+    }
+
+    #[test]
+    fn strip_ansi_removes_cursor_movement() {
+        assert_eq!(strip_ansi("\u{1b}[2Aup\u{1b}[10Cright\u{1b}[K"), "upright");
+    }
+
+    #[test]
+    fn strip_ansi_passes_plain_text_through() {
+        assert_eq!(
+            strip_ansi("plain text\nsecond line"),
+            "plain text\nsecond line"
+        );
+    }
+
+    fn plain(line: &Line<'_>) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn tabs_expand_to_the_next_tab_stop() {
+        let line = "ab\tc\tdefgh\ti";
+
+        assert_eq!(
+            (
+                plain(&ansi_escape_line(line)),
+                plain(&ansi_escape_line_with_tab_width(line, 8)),
+                line.replace('\t', "    "),
+            ),
+            (
+                "ab  c   defgh   i".to_string(),
+                "ab      c       defgh   i".to_string(),
+                "ab    c    defgh    i".to_string(),
+            )
+        );
+    }
+
+    #[test]
+    fn tab_stops_ignore_escape_sequences() {
+        let line = "\u{1b}[31mab\u{1b}[0m\tc";
+
+        assert_eq!(plain(&ansi_escape_line(line)), "ab  c");
+    }
+
+    #[test]
+    fn hyperlinks_keep_their_label_and_url() {
+        let input = "see \u{1b}]8;;https://example.com\u{1b}\\the docs\u{1b}]8;;\u{1b}\\ or\n\u{1b}]8;id=1;http://x.test\u{7}x\u{1b}]8;;\u{7}!";
+
+        let (text, links) = ansi_escape_with_links(input);
+
+        assert_eq!(
+            links,
+            vec![
+                Hyperlink {
+                    line: 0,
+                    label: "the docs".to_string(),
+                    url: "https://example.com".to_string(),
+                },
+                Hyperlink {
+                    line: 1,
+                    label: "x".to_string(),
+                    url: "http://x.test".to_string(),
+                },
+            ]
+        );
+        let label = text.lines[0]
+            .spans
+            .iter()
+            .find(|span| span.content == "the docs")
+            .expect("label span");
+        assert!(label.style.add_modifier.contains(Modifier::UNDERLINED));
+        assert_eq!(strip_ansi(input), "see the docs or\nx!");
+    }
+
+    #[test]
+    fn text_without_hyperlinks_is_unchanged() {
+        let input = "\u{1b}[32mok\u{1b}[0m plain";
+
+        assert_eq!(
+            ansi_escape_with_links(input),
+            (parse_ansi(input), Vec::new())
+        );
+    }
+}
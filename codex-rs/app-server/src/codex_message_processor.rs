@@ -2797,7 +2797,10 @@ impl CodexMessageProcessor {
             include_logs,
         } = params;
 
-        let snapshot = self.feedback.snapshot(conversation_id);
+        let snapshot = self
+            .feedback
+            .snapshot(conversation_id)
+            .with_offline_queue(&self.config.codex_home);
         let thread_id = snapshot.thread_id.clone();
 
         let validated_rollout_path = if include_logs {
@@ -2816,6 +2819,7 @@ impl CodexMessageProcessor {
                 reason.as_deref(),
                 include_logs,
                 rollout_path_ref,
+                serde_json::Value::Null,
             )
         })
         .await;
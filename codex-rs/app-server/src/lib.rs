@@ -13,15 +13,21 @@ use crate::outgoing_message::OutgoingMessage;
 use crate::outgoing_message::OutgoingMessageSender;
 use codex_app_server_protocol::JSONRPCMessage;
 use codex_feedback::CodexFeedback;
+use codex_feedback::CodexLogSnapshot;
+use tokio::io::AsyncBufRead;
 use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
 use tokio::io::AsyncWriteExt;
 use tokio::io::BufReader;
 use tokio::io::{self};
 use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 use tracing::Level;
 use tracing::debug;
 use tracing::error;
 use tracing::info;
+use tracing::warn;
 use tracing_subscriber::EnvFilter;
 use tracing_subscriber::Layer;
 use tracing_subscriber::filter::Targets;
@@ -41,36 +47,68 @@ mod outgoing_message;
 /// plenty for an interactive CLI.
 const CHANNEL_CAPACITY: usize = 128;
 
+/// Overrides [`CHANNEL_CAPACITY`] for clients that stream a lot of messages.
+pub const CHANNEL_CAPACITY_ENV_VAR: &str = "CODEX_APP_SERVER_CHANNEL_CAPACITY";
+
+/// Longest JSON-RPC message (one line of input) the server accepts. Longer
+/// lines are skipped rather than buffered without bound.
+const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Overrides [`MAX_MESSAGE_BYTES`].
+pub const MAX_MESSAGE_BYTES_ENV_VAR: &str = "CODEX_APP_SERVER_MAX_MESSAGE_BYTES";
+
+/// Parse a positive size override read from `env_var`; unset means `default`.
+fn parse_size_override(
+    env_var: &str,
+    value: Option<&str>,
+    default: usize,
+) -> Result<usize, String> {
+    let Some(value) = value else {
+        return Ok(default);
+    };
+    match value.trim().parse::<usize>() {
+        Ok(0) => Err(format!("{env_var} must be non-zero")),
+        Ok(size) => Ok(size),
+        Err(e) => Err(format!("invalid {env_var} {value:?}: {e}")),
+    }
+}
+
+fn size_override(env_var: &str, default: usize) -> Result<usize, String> {
+    parse_size_override(env_var, std::env::var(env_var).ok().as_deref(), default)
+}
+
+/// Bounded channels between the stdin reader, the processor, and the stdout
+/// writer, each holding up to `capacity` messages.
+#[allow(clippy::type_complexity)]
+fn message_channels(
+    capacity: usize,
+) -> (
+    (mpsc::Sender<JSONRPCMessage>, mpsc::Receiver<JSONRPCMessage>),
+    (
+        mpsc::Sender<OutgoingMessage>,
+        mpsc::Receiver<OutgoingMessage>,
+    ),
+) {
+    (mpsc::channel(capacity), mpsc::channel(capacity))
+}
+
 pub async fn run_main(
     codex_linux_sandbox_exe: Option<PathBuf>,
     cli_config_overrides: CliConfigOverrides,
 ) -> IoResult<()> {
-    // Set up channels.
-    let (incoming_tx, mut incoming_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
-    let (outgoing_tx, mut outgoing_rx) = mpsc::channel::<OutgoingMessage>(CHANNEL_CAPACITY);
+    // Set up channels. Invalid overrides are reported once logging is up.
+    let configured_capacity = size_override(CHANNEL_CAPACITY_ENV_VAR, CHANNEL_CAPACITY);
+    let channel_capacity = *configured_capacity.as_ref().unwrap_or(&CHANNEL_CAPACITY);
+    let configured_max_message = size_override(MAX_MESSAGE_BYTES_ENV_VAR, MAX_MESSAGE_BYTES);
+    let max_message_bytes = *configured_max_message
+        .as_ref()
+        .unwrap_or(&MAX_MESSAGE_BYTES);
+    let ((incoming_tx, incoming_rx), (outgoing_tx, outgoing_rx)) =
+        message_channels(channel_capacity);
 
     // Task: read from stdin, push to `incoming_tx`.
-    let stdin_reader_handle = tokio::spawn({
-        async move {
-            let stdin = io::stdin();
-            let reader = BufReader::new(stdin);
-            let mut lines = reader.lines();
-
-            while let Some(line) = lines.next_line().await.unwrap_or_default() {
-                match serde_json::from_str::<JSONRPCMessage>(&line) {
-                    Ok(msg) => {
-                        if incoming_tx.send(msg).await.is_err() {
-                            // Receiver gone – nothing left to do.
-                            break;
-                        }
-                    }
-                    Err(e) => error!("Failed to deserialize JSONRPCMessage: {e}"),
-                }
-            }
-
-            debug!("stdin reader finished (EOF)");
-        }
-    });
+    let stdin_reader_handle =
+        tokio::spawn(read_incoming(io::stdin(), incoming_tx, max_message_bytes));
 
     // Parse CLI overrides once and derive the base Config eagerly so later
     // components do not need to work with raw TOML values.
@@ -118,56 +156,363 @@ pub async fn run_main(
         }))
         .try_init();
 
+    // Best effort: deliver feedback queued by earlier offline uploads.
+    CodexLogSnapshot::flush_queued_feedback_in_background(config.codex_home.clone());
+
+    if let Err(message) = &configured_capacity {
+        warn!("{message}; using the default of {CHANNEL_CAPACITY}");
+    }
+    if let Err(message) = &configured_max_message {
+        warn!("{message}; using the default of {MAX_MESSAGE_BYTES}");
+    }
+
     // Task: process incoming messages.
     let processor_handle = tokio::spawn({
         let outgoing_message_sender = OutgoingMessageSender::new(outgoing_tx);
-        let mut processor = MessageProcessor::new(
+        let processor = MessageProcessor::new(
             outgoing_message_sender,
             codex_linux_sandbox_exe,
             std::sync::Arc::new(config),
             feedback.clone(),
         );
-        async move {
-            while let Some(msg) = incoming_rx.recv().await {
-                match msg {
-                    JSONRPCMessage::Request(r) => processor.process_request(r).await,
-                    JSONRPCMessage::Response(r) => processor.process_response(r).await,
-                    JSONRPCMessage::Notification(n) => processor.process_notification(n).await,
-                    JSONRPCMessage::Error(e) => processor.process_error(e),
+        process_incoming(processor, incoming_rx)
+    });
+
+    // Task: write outgoing messages to stdout.
+    let stdout_writer_handle = tokio::spawn(write_outgoing(outgoing_rx, io::stdout()));
+
+    wait_for_shutdown(stdin_reader_handle, processor_handle, stdout_writer_handle).await
+}
+
+/// Read JSONL messages from `input` until EOF. Returning drops `incoming_tx`,
+/// which is what starts a clean shutdown. Lines longer than
+/// `max_message_bytes` are skipped without being buffered.
+async fn read_incoming<R: AsyncRead + Unpin>(
+    input: R,
+    incoming_tx: mpsc::Sender<JSONRPCMessage>,
+    max_message_bytes: usize,
+) {
+    let mut reader = BufReader::new(input);
+
+    loop {
+        let line = match next_line(&mut reader, max_message_bytes).await {
+            Ok(Some(InputLine::Line(line))) => line,
+            Ok(Some(InputLine::TooLong(len))) => {
+                error!(
+                    "Skipping {len}-byte message: exceeds the {max_message_bytes}-byte limit ({MAX_MESSAGE_BYTES_ENV_VAR})"
+                );
+                continue;
+            }
+            Ok(None) => break,
+            Err(e) => {
+                error!("Failed to read from stdin: {e}");
+                break;
+            }
+        };
+        match serde_json::from_slice::<JSONRPCMessage>(&line) {
+            Ok(msg) => {
+                if incoming_tx.send(msg).await.is_err() {
+                    // Receiver gone – nothing left to do.
+                    break;
                 }
             }
+            Err(e) => error!("Failed to deserialize JSONRPCMessage: {e}"),
+        }
+    }
+
+    debug!("stdin reader finished (EOF)");
+}
 
-            info!("processor task exited (channel closed)");
+#[derive(Debug, PartialEq, Eq)]
+enum InputLine {
+    Line(Vec<u8>),
+    /// A line over the limit, already discarded; holds its length in bytes.
+    TooLong(usize),
+}
+
+/// Next newline-terminated line without its line ending, or `None` at EOF.
+/// Once a line exceeds `max_bytes` the rest of it is consumed and dropped.
+async fn next_line<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    max_bytes: usize,
+) -> IoResult<Option<InputLine>> {
+    let mut line = Vec::new();
+    let mut skipped = 0;
+    loop {
+        let available = reader.fill_buf().await?;
+        if available.is_empty() {
+            return Ok(match (line.is_empty(), skipped) {
+                (true, 0) => None,
+                (false, 0) => Some(InputLine::Line(line)),
+                _ => Some(InputLine::TooLong(skipped)),
+            });
         }
-    });
+        let newline = available.iter().position(|&b| b == b'\n');
+        let chunk = &available[..newline.unwrap_or(available.len())];
+        if skipped > 0 || line.len() + chunk.len() > max_bytes {
+            skipped += std::mem::take(&mut line).len() + chunk.len();
+        } else {
+            line.extend_from_slice(chunk);
+        }
+        let used = chunk.len() + usize::from(newline.is_some());
+        reader.consume(used);
 
-    // Task: write outgoing messages to stdout.
-    let stdout_writer_handle = tokio::spawn(async move {
-        let mut stdout = io::stdout();
-        while let Some(outgoing_message) = outgoing_rx.recv().await {
-            let Ok(value) = serde_json::to_value(outgoing_message) else {
-                error!("Failed to convert OutgoingMessage to JSON value");
-                continue;
-            };
-            match serde_json::to_string(&value) {
-                Ok(mut json) => {
-                    json.push('\n');
-                    if let Err(e) = stdout.write_all(json.as_bytes()).await {
-                        error!("Failed to write to stdout: {e}");
-                        break;
-                    }
+        if newline.is_some() {
+            if skipped > 0 {
+                return Ok(Some(InputLine::TooLong(skipped)));
+            }
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            return Ok(Some(InputLine::Line(line)));
+        }
+    }
+}
+
+/// Write outgoing messages as JSONL until every sender is gone, then flush.
+async fn write_outgoing<W: AsyncWrite + Unpin>(
+    mut outgoing_rx: mpsc::Receiver<OutgoingMessage>,
+    mut output: W,
+) {
+    while let Some(outgoing_message) = outgoing_rx.recv().await {
+        let Ok(value) = serde_json::to_value(outgoing_message) else {
+            error!("Failed to convert OutgoingMessage to JSON value");
+            continue;
+        };
+        match serde_json::to_string(&value) {
+            Ok(mut json) => {
+                json.push('\n');
+                if let Err(e) = output.write_all(json.as_bytes()).await {
+                    error!("Failed to write to stdout: {e}");
+                    break;
                 }
-                Err(e) => error!("Failed to serialize JSONRPCMessage: {e}"),
             }
+            Err(e) => error!("Failed to serialize JSONRPCMessage: {e}"),
         }
+    }
+    if let Err(e) = output.flush().await {
+        error!("Failed to flush stdout: {e}");
+    }
 
-        info!("stdout writer exited (channel closed)");
-    });
+    info!("stdout writer exited (channel closed)");
+}
+
+/// Shut down in order so no queued response is lost:
+/// 1. input ends: EOF makes the reader drop `incoming_tx`;
+/// 2. the processor handles every message already queued, finishing the
+///    request in flight, then drops its `OutgoingMessageSender`;
+/// 3. with the outgoing channel closed, the writer drains what is left and
+///    flushes.
+///
+/// A fatal processor error skips the rest of the input instead and is
+/// returned once the writer has drained.
+async fn wait_for_shutdown(
+    reader_handle: JoinHandle<()>,
+    processor_handle: JoinHandle<IoResult<()>>,
+    writer_handle: JoinHandle<()>,
+) -> IoResult<()> {
+    let processor_result = processor_handle.await.map_err(std::io::Error::other)?;
+    if processor_result.is_err() {
+        reader_handle.abort();
+    }
+    let _ = reader_handle.await;
+    writer_handle.await.map_err(std::io::Error::other)?;
 
-    // Wait for all tasks to finish.  The typical exit path is the stdin reader
-    // hitting EOF which, once it drops `incoming_tx`, propagates shutdown to
-    // the processor and then to the stdout task.
-    let _ = tokio::join!(stdin_reader_handle, processor_handle, stdout_writer_handle);
+    processor_result
+}
 
+/// Feed incoming messages to `processor` until the channel closes (clean
+/// shutdown) or the processor reports a fatal error.
+async fn process_incoming(
+    mut processor: MessageProcessor,
+    mut incoming_rx: mpsc::Receiver<JSONRPCMessage>,
+) -> IoResult<()> {
+    while let Some(msg) = incoming_rx.recv().await {
+        match msg {
+            JSONRPCMessage::Request(r) => processor.process_request(r).await,
+            JSONRPCMessage::Response(r) => processor.process_response(r).await,
+            JSONRPCMessage::Notification(n) => processor.process_notification(n).await,
+            JSONRPCMessage::Error(e) => processor.process_error(e),
+        }
+        if let Some(message) = processor.take_fatal_error() {
+            return Err(std::io::Error::other(message));
+        }
+    }
+
+    info!("processor task exited (channel closed)");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_app_server_protocol::JSONRPCNotification;
+    use codex_core::config::ConfigToml;
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+    use tokio::io::AsyncReadExt;
+
+    fn processor(
+        codex_home: &TempDir,
+        outgoing_tx: mpsc::Sender<OutgoingMessage>,
+    ) -> IoResult<MessageProcessor> {
+        let config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        Ok(MessageProcessor::new(
+            OutgoingMessageSender::new(outgoing_tx),
+            None,
+            Arc::new(config),
+            CodexFeedback::new(),
+        ))
+    }
+
+    fn initialized_notification_with_padding(len: usize) -> JSONRPCMessage {
+        JSONRPCMessage::Notification(JSONRPCNotification {
+            method: "initialized".to_string(),
+            params: Some(serde_json::json!({ "padding": "x".repeat(len) })),
+        })
+    }
+
+    fn initialized_notification() -> JSONRPCMessage {
+        JSONRPCMessage::Notification(JSONRPCNotification {
+            method: "initialized".to_string(),
+            params: None,
+        })
+    }
+
+    #[test]
+    fn channel_capacity_override_is_validated() {
+        let parse = |value| parse_size_override(CHANNEL_CAPACITY_ENV_VAR, value, CHANNEL_CAPACITY);
+        assert_eq!(parse(None), Ok(CHANNEL_CAPACITY));
+        assert_eq!(parse(Some("1024")), Ok(1024));
+        assert_eq!(
+            parse(Some("0")),
+            Err("CODEX_APP_SERVER_CHANNEL_CAPACITY must be non-zero".to_string())
+        );
+        assert!(parse(Some("lots")).is_err());
+    }
+
+    #[test]
+    fn configured_capacity_is_applied_to_both_channels() {
+        let ((incoming_tx, _incoming_rx), (outgoing_tx, _outgoing_rx)) = message_channels(512);
+        assert_eq!(
+            (incoming_tx.max_capacity(), outgoing_tx.max_capacity()),
+            (512, 512)
+        );
+    }
+
+    #[tokio::test]
+    async fn response_is_written_when_input_ends_right_after_request() -> IoResult<()> {
+        let codex_home = TempDir::new()?;
+        let ((incoming_tx, incoming_rx), (outgoing_tx, outgoing_rx)) =
+            message_channels(CHANNEL_CAPACITY);
+        let input = concat!(
+            r#"{"id":1,"method":"initialize","params":{"clientInfo":{"name":"test","version":"0.0.0"}}}"#,
+            "\n"
+        );
+        let (output, mut client) = tokio::io::duplex(64 * 1024);
+
+        let reader_handle = tokio::spawn(read_incoming(
+            input.as_bytes(),
+            incoming_tx,
+            MAX_MESSAGE_BYTES,
+        ));
+        let processor_handle = tokio::spawn(process_incoming(
+            processor(&codex_home, outgoing_tx)?,
+            incoming_rx,
+        ));
+        let writer_handle = tokio::spawn(write_outgoing(outgoing_rx, output));
+        wait_for_shutdown(reader_handle, processor_handle, writer_handle).await?;
+
+        let mut written = String::new();
+        client.read_to_string(&mut written).await?;
+        let lines: Vec<serde_json::Value> = written
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+        assert_eq!(
+            lines,
+            vec![serde_json::json!({
+                "id": 1,
+                "result": { "userAgent": codex_core::default_client::get_codex_user_agent() },
+            })]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn over_limit_line_is_skipped() {
+        let large =
+            serde_json::to_string(&initialized_notification_with_padding(100)).expect("serialize");
+        let limit = large.len();
+        let input = format!(
+            "{}\n{large}\n",
+            serde_json::to_string(&initialized_notification_with_padding(200)).expect("serialize")
+        );
+        let (incoming_tx, mut incoming_rx) = mpsc::channel(CHANNEL_CAPACITY);
+
+        // A tiny read buffer makes every message arrive split across reads.
+        let reader = BufReader::with_capacity(16, input.as_bytes());
+        read_incoming(reader, incoming_tx, limit).await;
+
+        let mut received = Vec::new();
+        while let Some(msg) = incoming_rx.recv().await {
+            received.push(msg);
+        }
+        assert_eq!(received, vec![initialized_notification_with_padding(100)]);
+    }
+
+    #[tokio::test]
+    async fn next_line_reports_over_limit_length() -> IoResult<()> {
+        let mut reader = BufReader::with_capacity(4, &b"0123456789\nabc"[..]);
+        assert_eq!(
+            next_line(&mut reader, 8).await?,
+            Some(InputLine::TooLong(10))
+        );
+        assert_eq!(
+            next_line(&mut reader, 8).await?,
+            Some(InputLine::Line(b"abc".to_vec()))
+        );
+        assert_eq!(next_line(&mut reader, 8).await?, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn clean_shutdown_returns_ok() -> IoResult<()> {
+        let codex_home = TempDir::new()?;
+        let (outgoing_tx, _outgoing_rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let (incoming_tx, incoming_rx) = mpsc::channel(CHANNEL_CAPACITY);
+        incoming_tx
+            .send(initialized_notification())
+            .await
+            .map_err(std::io::Error::other)?;
+        drop(incoming_tx);
+
+        process_incoming(processor(&codex_home, outgoing_tx)?, incoming_rx).await
+    }
+
+    #[tokio::test]
+    async fn fatal_processor_error_is_returned() -> IoResult<()> {
+        let codex_home = TempDir::new()?;
+        // Simulate the stdout writer dying: the processor can no longer reach
+        // the client.
+        let (outgoing_tx, outgoing_rx) = mpsc::channel(CHANNEL_CAPACITY);
+        drop(outgoing_rx);
+        let (incoming_tx, incoming_rx) = mpsc::channel(CHANNEL_CAPACITY);
+        incoming_tx
+            .send(initialized_notification())
+            .await
+            .map_err(std::io::Error::other)?;
+
+        // `incoming_tx` stays open: the error must end processing on its own.
+        let err = process_incoming(processor(&codex_home, outgoing_tx)?, incoming_rx)
+            .await
+            .expect_err("fatal error");
+        assert_eq!(err.to_string(), "client output channel closed");
+        Ok(())
+    }
+}
@@ -24,6 +24,7 @@ pub(crate) struct MessageProcessor {
     outgoing: Arc<OutgoingMessageSender>,
     codex_message_processor: CodexMessageProcessor,
     initialized: bool,
+    fatal_error: Option<String>,
 }
 
 impl MessageProcessor {
@@ -58,6 +59,7 @@ impl MessageProcessor {
             outgoing,
             codex_message_processor,
             initialized: false,
+            fatal_error: None,
         }
     }
 
@@ -139,6 +141,23 @@ impl MessageProcessor {
             .await;
     }
 
+    /// Record an unrecoverable condition. The server stops processing
+    /// messages and `run_main` returns the error, so the process exits
+    /// non-zero instead of looking like a clean shutdown.
+    pub(crate) fn set_fatal_error(&mut self, message: String) {
+        tracing::error!("fatal: {message}");
+        self.fatal_error.get_or_insert(message);
+    }
+
+    /// The recorded fatal error, if any. Losing the output channel is always
+    /// fatal since no further request can be answered.
+    pub(crate) fn take_fatal_error(&mut self) -> Option<String> {
+        if self.fatal_error.is_none() && self.outgoing.is_closed() {
+            self.set_fatal_error("client output channel closed".to_string());
+        }
+        self.fatal_error.take()
+    }
+
     pub(crate) async fn process_notification(&self, notification: JSONRPCNotification) {
         // Currently, we do not expect to receive any notifications from the
         // client, so we just log them.
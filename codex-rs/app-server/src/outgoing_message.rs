@@ -32,6 +32,12 @@ impl OutgoingMessageSender {
         }
     }
 
+    /// True once the stdout writer has gone away; nothing sent after that
+    /// reaches the client.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
     pub(crate) async fn send_request(
         &self,
         request: ServerRequestPayload,
@@ -426,6 +426,7 @@ async fn test_send_user_turn_updates_sandbox_and_cwd_between_turns() -> Result<(
                 network_access: false,
                 exclude_tmpdir_env_var: false,
                 exclude_slash_tmp: false,
+                allow_hosts: Vec::new(),
             },
             model: model.clone(),
             effort: Some(ReasoningEffort::Medium),
@@ -84,6 +84,7 @@ async fn get_config_toml_parses_all_fields() -> Result<()> {
                 network_access: Some(true),
                 exclude_tmpdir_env_var: Some(true),
                 exclude_slash_tmp: Some(true),
+                allow_hosts: Vec::new(),
             }),
             forced_chatgpt_workspace_id: Some("12345678-0000-0000-0000-000000000000".into()),
             forced_login_method: Some(ForcedLoginMethod::Chatgpt),
@@ -17,6 +17,7 @@ use reqwest::header::HeaderName;
 use reqwest::header::HeaderValue;
 use reqwest::header::USER_AGENT;
 use serde::de::DeserializeOwned;
+use std::time::Duration;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PathStyle {
@@ -36,6 +37,40 @@ impl PathStyle {
     }
 }
 
+/// Non-success HTTP response from a [`Client`] request. Carried inside the
+/// returned `anyhow::Error`; see [`is_transient_error`].
+#[derive(Debug)]
+pub struct RequestError {
+    pub method: String,
+    pub url: String,
+    pub status: reqwest::StatusCode,
+    pub content_type: String,
+    pub body: String,
+}
+
+impl std::fmt::Display for RequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} failed: {}; content-type={}; body={}",
+            self.method, self.url, self.status, self.content_type, self.body
+        )
+    }
+}
+
+impl std::error::Error for RequestError {}
+
+/// Whether an error returned by a [`Client`] call is likely to go away on
+/// retry: a timeout, a connection failure, or a 5xx / 429 response.
+pub fn is_transient_error(err: &anyhow::Error) -> bool {
+    if let Some(err) = err.downcast_ref::<RequestError>() {
+        return err.status.is_server_error()
+            || err.status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+    }
+    err.downcast_ref::<reqwest::Error>()
+        .is_some_and(|err| err.is_timeout() || err.is_connect())
+}
+
 #[derive(Clone, Debug)]
 pub struct Client {
     base_url: String,
@@ -44,6 +79,7 @@ pub struct Client {
     user_agent: Option<HeaderValue>,
     chatgpt_account_id: Option<String>,
     path_style: PathStyle,
+    timeout: Option<Duration>,
 }
 
 impl Client {
@@ -69,6 +105,7 @@ impl Client {
             user_agent: None,
             chatgpt_account_id: None,
             path_style,
+            timeout: None,
         })
     }
 
@@ -105,6 +142,22 @@ impl Client {
         self
     }
 
+    /// Give up on each request after `timeout`; a timed-out request fails with
+    /// an error that [`is_transient_error`] reports as transient. `None` (the
+    /// default) waits indefinitely.
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+        let req = self.http.request(method, url).headers(self.headers());
+        match self.timeout {
+            Some(timeout) => req.timeout(timeout),
+            None => req,
+        }
+    }
+
     fn headers(&self) -> HeaderMap {
         let mut h = HeaderMap::new();
         if let Some(ua) = &self.user_agent {
@@ -143,7 +196,14 @@ impl Client {
             .to_string();
         let body = res.text().await.unwrap_or_default();
         if !status.is_success() {
-            anyhow::bail!("{method} {url} failed: {status}; content-type={ct}; body={body}");
+            return Err(RequestError {
+                method: method.to_string(),
+                url: url.to_string(),
+                status,
+                content_type: ct,
+                body,
+            }
+            .into());
         }
         Ok((body, ct))
     }
@@ -162,7 +222,7 @@ impl Client {
             PathStyle::CodexApi => format!("{}/api/codex/usage", self.base_url),
             PathStyle::ChatGptApi => format!("{}/wham/usage", self.base_url),
         };
-        let req = self.http.get(&url).headers(self.headers());
+        let req = self.request(reqwest::Method::GET, &url);
         let (body, ct) = self.exec_request(req, "GET", &url).await?;
         let payload: RateLimitStatusPayload = self.decode_json(&url, &ct, &body)?;
         Ok(Self::rate_limit_snapshot_from_payload(payload))
@@ -173,12 +233,25 @@ impl Client {
         limit: Option<i32>,
         task_filter: Option<&str>,
         environment_id: Option<&str>,
+    ) -> Result<PaginatedListTaskListItem> {
+        self.list_tasks_page(limit, task_filter, environment_id, None)
+            .await
+    }
+
+    /// Like [`Self::list_tasks`], resuming from the `cursor` returned by a
+    /// previous page.
+    pub async fn list_tasks_page(
+        &self,
+        limit: Option<i32>,
+        task_filter: Option<&str>,
+        environment_id: Option<&str>,
+        cursor: Option<&str>,
     ) -> Result<PaginatedListTaskListItem> {
         let url = match self.path_style {
             PathStyle::CodexApi => format!("{}/api/codex/tasks/list", self.base_url),
             PathStyle::ChatGptApi => format!("{}/wham/tasks/list", self.base_url),
         };
-        let req = self.http.get(&url).headers(self.headers());
+        let req = self.request(reqwest::Method::GET, &url);
         let req = if let Some(lim) = limit {
             req.query(&[("limit", lim)])
         } else {
@@ -194,6 +267,11 @@ impl Client {
         } else {
             req
         };
+        let req = if let Some(cursor) = cursor {
+            req.query(&[("cursor", cursor)])
+        } else {
+            req
+        };
         let (body, ct) = self.exec_request(req, "GET", &url).await?;
         self.decode_json::<PaginatedListTaskListItem>(&url, &ct, &body)
     }
@@ -211,7 +289,7 @@ impl Client {
             PathStyle::CodexApi => format!("{}/api/codex/tasks/{}", self.base_url, task_id),
             PathStyle::ChatGptApi => format!("{}/wham/tasks/{}", self.base_url, task_id),
         };
-        let req = self.http.get(&url).headers(self.headers());
+        let req = self.request(reqwest::Method::GET, &url);
         let (body, ct) = self.exec_request(req, "GET", &url).await?;
         let parsed: CodeTaskDetailsResponse = self.decode_json(&url, &ct, &body)?;
         Ok((parsed, body, ct))
@@ -232,7 +310,7 @@ impl Client {
                 self.base_url, task_id, turn_id
             ),
         };
-        let req = self.http.get(&url).headers(self.headers());
+        let req = self.request(reqwest::Method::GET, &url);
         let (body, ct) = self.exec_request(req, "GET", &url).await?;
         self.decode_json::<TurnAttemptsSiblingTurnsResponse>(&url, &ct, &body)
     }
@@ -245,9 +323,7 @@ impl Client {
             PathStyle::ChatGptApi => format!("{}/wham/tasks", self.base_url),
         };
         let req = self
-            .http
-            .post(&url)
-            .headers(self.headers())
+            .request(reqwest::Method::POST, &url)
             .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
             .json(&request_body);
         let (body, ct) = self.exec_request(req, "POST", &url).await?;
@@ -272,6 +348,26 @@ impl Client {
         }
     }
 
+    /// Ask the backend to stop an in-progress task. Returns `Ok(false)` when
+    /// the backend reports a conflict because the task already finished.
+    pub async fn cancel_task(&self, task_id: &str) -> Result<bool> {
+        let url = match self.path_style {
+            PathStyle::CodexApi => format!("{}/api/codex/tasks/{}/cancel", self.base_url, task_id),
+            PathStyle::ChatGptApi => format!("{}/wham/tasks/{}/cancel", self.base_url, task_id),
+        };
+        let req = self.request(reqwest::Method::POST, &url);
+        let res = req.send().await?;
+        let status = res.status();
+        if status == reqwest::StatusCode::CONFLICT {
+            return Ok(false);
+        }
+        if !status.is_success() {
+            let body = res.text().await.unwrap_or_default();
+            anyhow::bail!("POST {url} failed: {status}; body={body}");
+        }
+        Ok(true)
+    }
+
     // rate limit helpers
     fn rate_limit_snapshot_from_payload(payload: RateLimitStatusPayload) -> RateLimitSnapshot {
         let rate_limit_details = payload
@@ -2,6 +2,8 @@ mod client;
 pub mod types;
 
 pub use client::Client;
+pub use client::RequestError;
+pub use client::is_transient_error;
 pub use types::CodeTaskDetailsResponse;
 pub use types::CodeTaskDetailsResponseExt;
 pub use types::PaginatedListTaskListItem;
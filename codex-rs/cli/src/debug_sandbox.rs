@@ -136,6 +136,7 @@ async fn run_command_under_sandbox(
     if let SandboxType::Windows = sandbox_type {
         #[cfg(target_os = "windows")]
         {
+            use codex_windows_sandbox::SandboxRunOptions;
             use codex_windows_sandbox::run_windows_sandbox_capture;
 
             let policy_str = serde_json::to_string(&config.sandbox_policy)?;
@@ -150,12 +151,14 @@ async fn run_command_under_sandbox(
             let res = tokio::task::spawn_blocking(move || {
                 run_windows_sandbox_capture(
                     policy_str.as_str(),
-                    &sandbox_cwd,
                     base_dir.as_path(),
                     command_vec,
                     &cwd_clone,
                     env_map,
-                    None,
+                    SandboxRunOptions {
+                        sandbox_policy_cwd: Some(sandbox_cwd),
+                        ..Default::default()
+                    },
                 )
             })
             .await;
@@ -2,6 +2,7 @@ use chrono::DateTime;
 use chrono::Utc;
 use serde::Deserialize;
 use serde::Serialize;
+use std::path::PathBuf;
 
 pub type Result<T> = std::result::Result<T, CloudTaskError>;
 
@@ -11,6 +12,8 @@ pub enum CloudTaskError {
     Unimplemented(&'static str),
     #[error("http error: {0}")]
     Http(String),
+    #[error("task {0} has already finished")]
+    AlreadyFinished(String),
     #[error("io error: {0}")]
     Io(String),
     #[error("{0}")]
@@ -21,6 +24,12 @@ pub enum CloudTaskError {
 #[serde(transparent)]
 pub struct TaskId(pub String);
 
+/// Opaque position in a paginated task listing, as handed back by
+/// [`CloudBackend::list_tasks_page`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Cursor(pub String);
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum TaskStatus {
@@ -127,6 +136,23 @@ impl Default for TaskText {
 #[async_trait::async_trait]
 pub trait CloudBackend: Send + Sync {
     async fn list_tasks(&self, env: Option<&str>) -> Result<Vec<TaskSummary>>;
+    /// Return up to `limit` tasks starting at `cursor` (the first page when
+    /// `None`), plus the cursor for the next page when more remain.
+    ///
+    /// Backends without pagination return everything from [`Self::list_tasks`]
+    /// as a single page.
+    async fn list_tasks_page(
+        &self,
+        env: Option<&str>,
+        cursor: Option<Cursor>,
+        limit: usize,
+    ) -> Result<(Vec<TaskSummary>, Option<Cursor>)> {
+        let _ = limit;
+        if cursor.is_some() {
+            return Ok((Vec::new(), None));
+        }
+        Ok((self.list_tasks(env).await?, None))
+    }
     async fn get_task_diff(&self, id: TaskId) -> Result<Option<String>>;
     /// Return assistant output messages (no diff) when available.
     async fn get_task_messages(&self, id: TaskId) -> Result<Vec<String>>;
@@ -141,12 +167,23 @@ pub trait CloudBackend: Send + Sync {
     /// Dry-run apply (preflight) that validates whether the patch would apply cleanly.
     /// Never modifies the working tree. When `diff_override` is supplied, the provided diff is
     /// used instead of re-fetching the task details so callers can apply alternate attempts.
+    /// `target_dir` selects the working tree to check against; `None` means the current directory.
     async fn apply_task_preflight(
         &self,
         id: TaskId,
         diff_override: Option<String>,
+        target_dir: Option<PathBuf>,
     ) -> Result<ApplyOutcome>;
-    async fn apply_task(&self, id: TaskId, diff_override: Option<String>) -> Result<ApplyOutcome>;
+    /// Apply the task diff to `target_dir`, or to the current directory when `None`.
+    async fn apply_task(
+        &self,
+        id: TaskId,
+        diff_override: Option<String>,
+        target_dir: Option<PathBuf>,
+    ) -> Result<ApplyOutcome>;
+    /// Stop an in-progress task. Fails with [`CloudTaskError::AlreadyFinished`]
+    /// when the task has already completed and there is nothing to cancel.
+    async fn cancel_task(&self, id: TaskId) -> Result<()>;
     async fn create_task(
         &self,
         env_id: &str,
@@ -155,4 +192,11 @@ pub trait CloudBackend: Send + Sync {
         qa_mode: bool,
         best_of_n: usize,
     ) -> Result<CreatedTask>;
+    /// Cheap request that succeeds when the backend is reachable and accepts
+    /// our credentials, so callers can report a precise error up front.
+    ///
+    /// Defaults to fetching a single task.
+    async fn healthcheck(&self) -> Result<()> {
+        self.list_tasks_page(None, None, 1).await.map(|_| ())
+    }
 }
@@ -0,0 +1,198 @@
+use crate::ApplyOutcome;
+use crate::AttemptStatus;
+use crate::CloudBackend;
+use crate::CloudTaskError;
+use crate::Result;
+use crate::TaskId;
+use crate::TaskStatus;
+use crate::TaskSummary;
+use crate::TurnAttempt;
+use crate::api::TaskText;
+use crate::mock::mock_apply_location;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// Mock backend that serves tasks from a directory of JSON fixtures, so UI
+/// states (empty diff, huge diff, failed turn) can be reproduced exactly.
+///
+/// Layout:
+/// - `tasks.json`: array of [`TaskSummary`] objects returned by `list_tasks`;
+///   filtering by environment matches `environment_id`.
+/// - `tasks/<task id>.json` (optional): details for one task, an object with
+///   optional `diff`, `prompt`, `messages`, `status` (`completed`, `failed`,
+///   ...) and `error` fields. When `error` is set, loading the task's diff or
+///   conversation fails with that message.
+#[derive(Clone, Debug)]
+pub struct FixtureClient {
+    dir: PathBuf,
+    tasks: Vec<TaskSummary>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct TaskFixture {
+    diff: Option<String>,
+    prompt: Option<String>,
+    messages: Vec<String>,
+    status: FixtureStatus,
+    error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FixtureStatus {
+    Pending,
+    InProgress,
+    #[default]
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl From<FixtureStatus> for AttemptStatus {
+    fn from(status: FixtureStatus) -> Self {
+        match status {
+            FixtureStatus::Pending => AttemptStatus::Pending,
+            FixtureStatus::InProgress => AttemptStatus::InProgress,
+            FixtureStatus::Completed => AttemptStatus::Completed,
+            FixtureStatus::Failed => AttemptStatus::Failed,
+            FixtureStatus::Cancelled => AttemptStatus::Cancelled,
+        }
+    }
+}
+
+impl FixtureClient {
+    /// Read `tasks.json` from `dir`. Per-task details are read on demand, so
+    /// they can be edited while the UI is running.
+    pub fn load(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        let path = dir.join("tasks.json");
+        let bytes = std::fs::read(&path)
+            .map_err(|e| CloudTaskError::Io(format!("failed to read {}: {e}", path.display())))?;
+        let tasks = serde_json::from_slice(&bytes)
+            .map_err(|e| CloudTaskError::Msg(format!("invalid {}: {e}", path.display())))?;
+        Ok(Self { dir, tasks })
+    }
+
+    fn task(&self, id: &TaskId) -> Result<&TaskSummary> {
+        self.tasks
+            .iter()
+            .find(|task| task.id == *id)
+            .ok_or_else(|| CloudTaskError::Msg(format!("unknown task {}", id.0)))
+    }
+
+    fn details(&self, id: &TaskId) -> Result<TaskFixture> {
+        self.task(id)?;
+        let path = self.dir.join("tasks").join(format!("{}.json", id.0));
+        let fixture: TaskFixture = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| CloudTaskError::Msg(format!("invalid {}: {e}", path.display())))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => TaskFixture::default(),
+            Err(e) => {
+                return Err(CloudTaskError::Io(format!(
+                    "failed to read {}: {e}",
+                    path.display()
+                )));
+            }
+        };
+        match fixture.error {
+            Some(error) => Err(CloudTaskError::Http(error)),
+            None => Ok(fixture),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CloudBackend for FixtureClient {
+    async fn list_tasks(&self, env: Option<&str>) -> Result<Vec<TaskSummary>> {
+        Ok(self
+            .tasks
+            .iter()
+            .filter(|task| env.is_none() || task.environment_id.as_deref() == env)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_task_diff(&self, id: TaskId) -> Result<Option<String>> {
+        Ok(self.details(&id)?.diff)
+    }
+
+    async fn get_task_messages(&self, id: TaskId) -> Result<Vec<String>> {
+        Ok(self.details(&id)?.messages)
+    }
+
+    async fn get_task_text(&self, id: TaskId) -> Result<TaskText> {
+        let fixture = self.details(&id)?;
+        Ok(TaskText {
+            prompt: fixture.prompt,
+            messages: fixture.messages,
+            turn_id: Some(format!("{}-turn", id.0)),
+            sibling_turn_ids: Vec::new(),
+            attempt_placement: Some(0),
+            attempt_status: fixture.status.into(),
+        })
+    }
+
+    async fn list_sibling_attempts(
+        &self,
+        _task: TaskId,
+        _turn_id: String,
+    ) -> Result<Vec<TurnAttempt>> {
+        Ok(Vec::new())
+    }
+
+    async fn apply_task_preflight(
+        &self,
+        id: TaskId,
+        _diff_override: Option<String>,
+        target_dir: Option<PathBuf>,
+    ) -> Result<ApplyOutcome> {
+        let location = mock_apply_location(target_dir.as_deref())?;
+        Ok(ApplyOutcome {
+            applied: false,
+            status: crate::ApplyStatus::Success,
+            message: format!(
+                "Preflight passed for task {} against {location} (fixtures)",
+                id.0
+            ),
+            skipped_paths: Vec::new(),
+            conflict_paths: Vec::new(),
+        })
+    }
+
+    async fn apply_task(
+        &self,
+        id: TaskId,
+        _diff_override: Option<String>,
+        target_dir: Option<PathBuf>,
+    ) -> Result<ApplyOutcome> {
+        let location = mock_apply_location(target_dir.as_deref())?;
+        Ok(ApplyOutcome {
+            applied: true,
+            status: crate::ApplyStatus::Success,
+            message: format!("Applied task {} to {location} (fixtures)", id.0),
+            skipped_paths: Vec::new(),
+            conflict_paths: Vec::new(),
+        })
+    }
+
+    async fn cancel_task(&self, id: TaskId) -> Result<()> {
+        match self.task(&id)?.status {
+            TaskStatus::Pending => Ok(()),
+            _ => Err(CloudTaskError::AlreadyFinished(id.0)),
+        }
+    }
+
+    async fn create_task(
+        &self,
+        _env_id: &str,
+        _prompt: &str,
+        _git_ref: &str,
+        _qa_mode: bool,
+        _best_of_n: usize,
+    ) -> Result<crate::CreatedTask> {
+        Err(CloudTaskError::Unimplemented(
+            "creating tasks is not supported with fixtures",
+        ))
+    }
+}
@@ -3,6 +3,7 @@ use crate::ApplyStatus;
 use crate::AttemptStatus;
 use crate::CloudBackend;
 use crate::CloudTaskError;
+use crate::Cursor;
 use crate::DiffSummary;
 use crate::Result;
 use crate::TaskId;
@@ -12,21 +13,97 @@ use crate::TurnAttempt;
 use crate::api::TaskText;
 use chrono::DateTime;
 use chrono::Utc;
+use rand::Rng;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Duration;
 
 use codex_backend_client as backend;
 use codex_backend_client::CodeTaskDetailsResponseExt;
 
+/// Retries for idempotent GETs that fail transiently (timeouts, connection
+/// errors, 5xx/429 responses).
+#[derive(Clone, Copy, Debug)]
+struct GetRetries {
+    max_retries: i64,
+    base_delay: Duration,
+}
+
+/// Per-request timeout used unless overridden with [`HttpClient::with_timeout`].
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+const DEFAULT_GET_RETRIES: GetRetries = GetRetries {
+    max_retries: 2,
+    base_delay: Duration::from_millis(200),
+};
+
+impl GetRetries {
+    /// Run `request`, retrying transient failures with jittered exponential
+    /// backoff. Only use this for idempotent requests.
+    async fn run<T, F, Fut>(self, mut request: F) -> anyhow::Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match request().await {
+                Err(err) if attempt < self.max_retries && backend::is_transient_error(&err) => {
+                    attempt += 1;
+                    tokio::time::sleep(self.delay(attempt)).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn delay(self, attempt: i64) -> Duration {
+        let exp = 2_u32.saturating_pow(u32::try_from(attempt - 1).unwrap_or(u32::MAX));
+        let jitter = rand::rng().random_range(0.8..1.2);
+        self.base_delay.saturating_mul(exp).mul_f64(jitter)
+    }
+}
+
 #[derive(Clone)]
 pub struct HttpClient {
     pub base_url: String,
     backend: backend::Client,
+    get_retries: GetRetries,
 }
 
 impl HttpClient {
     pub fn new(base_url: impl Into<String>) -> anyhow::Result<Self> {
         let base_url = base_url.into();
-        let backend = backend::Client::new(base_url.clone())?;
-        Ok(Self { base_url, backend })
+        let backend =
+            backend::Client::new(base_url.clone())?.with_timeout(Some(DEFAULT_REQUEST_TIMEOUT));
+        Ok(Self {
+            base_url,
+            backend,
+            get_retries: DEFAULT_GET_RETRIES,
+        })
+    }
+
+    /// Fail each backend request that takes longer than `timeout` (30s by
+    /// default) instead of waiting forever on a stalled backend. Timed-out
+    /// GETs are retried like other transient failures (see
+    /// [`HttpClient::with_get_retries`]). None of the task endpoints stream
+    /// or long-poll, so one timeout covers them all.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.backend = self.backend.clone().with_timeout(Some(timeout));
+        self
+    }
+
+    /// Retry idempotent GETs (task details, diffs, sibling attempts) up to
+    /// `max_retries` extra times on timeouts, connection errors, and 5xx/429
+    /// responses. The wait before retry `n` is about `base_delay * 2^(n-1)`,
+    /// jittered by ±20%. Task creation and apply are never retried. Defaults to
+    /// 2 retries starting at 200ms; pass 0 (or a negative value) to disable.
+    pub fn with_get_retries(mut self, max_retries: i64, base_delay: Duration) -> Self {
+        self.get_retries = GetRetries {
+            max_retries,
+            base_delay,
+        };
+        self
     }
 
     pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
@@ -63,6 +140,15 @@ impl CloudBackend for HttpClient {
         self.tasks_api().list(env).await
     }
 
+    async fn list_tasks_page(
+        &self,
+        env: Option<&str>,
+        cursor: Option<Cursor>,
+        limit: usize,
+    ) -> Result<(Vec<TaskSummary>, Option<Cursor>)> {
+        self.tasks_api().list_page(env, cursor, limit).await
+    }
+
     async fn get_task_diff(&self, id: TaskId) -> Result<Option<String>> {
         self.tasks_api().diff(id).await
     }
@@ -83,16 +169,30 @@ impl CloudBackend for HttpClient {
         self.attempts_api().list(task, turn_id).await
     }
 
-    async fn apply_task(&self, id: TaskId, diff_override: Option<String>) -> Result<ApplyOutcome> {
-        self.apply_api().run(id, diff_override, false).await
+    async fn apply_task(
+        &self,
+        id: TaskId,
+        diff_override: Option<String>,
+        target_dir: Option<PathBuf>,
+    ) -> Result<ApplyOutcome> {
+        self.apply_api()
+            .run(id, diff_override, target_dir, false)
+            .await
     }
 
     async fn apply_task_preflight(
         &self,
         id: TaskId,
         diff_override: Option<String>,
+        target_dir: Option<PathBuf>,
     ) -> Result<ApplyOutcome> {
-        self.apply_api().run(id, diff_override, true).await
+        self.apply_api()
+            .run(id, diff_override, target_dir, true)
+            .await
+    }
+
+    async fn cancel_task(&self, id: TaskId) -> Result<()> {
+        self.tasks_api().cancel(id).await
     }
 
     async fn create_task(
@@ -107,6 +207,10 @@ impl CloudBackend for HttpClient {
             .create(env_id, prompt, git_ref, qa_mode, best_of_n)
             .await
     }
+
+    async fn healthcheck(&self) -> Result<()> {
+        self.tasks_api().healthcheck().await
+    }
 }
 
 mod api {
@@ -118,6 +222,7 @@ mod api {
     pub(crate) struct Tasks<'a> {
         base_url: &'a str,
         backend: &'a backend::Client,
+        get_retries: GetRetries,
     }
 
     impl<'a> Tasks<'a> {
@@ -125,15 +230,40 @@ mod api {
             Self {
                 base_url: &client.base_url,
                 backend: &client.backend,
+                get_retries: client.get_retries,
             }
         }
 
         pub(crate) async fn list(&self, env: Option<&str>) -> Result<Vec<TaskSummary>> {
+            let (tasks, _) = self.list_page(env, None, 20).await?;
+            Ok(tasks)
+        }
+
+        pub(crate) async fn healthcheck(&self) -> Result<()> {
+            self.backend
+                .list_tasks(Some(1), Some("current"), None)
+                .await
+                .map(|_| ())
+                .map_err(|e| CloudTaskError::Http(format!("healthcheck failed: {e:#}")))
+        }
+
+        pub(crate) async fn list_page(
+            &self,
+            env: Option<&str>,
+            cursor: Option<Cursor>,
+            limit: usize,
+        ) -> Result<(Vec<TaskSummary>, Option<Cursor>)> {
+            let limit = i32::try_from(limit).unwrap_or(i32::MAX);
             let resp = self
                 .backend
-                .list_tasks(Some(20), Some("current"), env)
+                .list_tasks_page(
+                    Some(limit),
+                    Some("current"),
+                    env,
+                    cursor.as_ref().map(|c| c.0.as_str()),
+                )
                 .await
-                .map_err(|e| CloudTaskError::Http(format!("list_tasks failed: {e}")))?;
+                .map_err(|e| CloudTaskError::Http(format!("list_tasks failed: {e:#}")))?;
 
             let tasks: Vec<TaskSummary> = resp
                 .items
@@ -142,18 +272,19 @@ mod api {
                 .collect();
 
             append_error_log(&format!(
-                "http.list_tasks: env={} items={}",
+                "http.list_tasks: env={} items={} more={}",
                 env.unwrap_or("<all>"),
-                tasks.len()
+                tasks.len(),
+                resp.cursor.is_some()
             ));
-            Ok(tasks)
+            Ok((tasks, resp.cursor.map(Cursor)))
         }
 
         pub(crate) async fn diff(&self, id: TaskId) -> Result<Option<String>> {
             let (details, body, ct) = self
                 .details_with_body(&id.0)
                 .await
-                .map_err(|e| CloudTaskError::Http(format!("get_task_details failed: {e}")))?;
+                .map_err(|e| CloudTaskError::Http(format!("get_task_details failed: {e:#}")))?;
             if let Some(diff) = details.unified_diff() {
                 return Ok(Some(diff));
             }
@@ -165,7 +296,7 @@ mod api {
             let (details, body, ct) = self
                 .details_with_body(&id.0)
                 .await
-                .map_err(|e| CloudTaskError::Http(format!("get_task_details failed: {e}")))?;
+                .map_err(|e| CloudTaskError::Http(format!("get_task_details failed: {e:#}")))?;
 
             let mut msgs = details.assistant_text_messages();
             if msgs.is_empty() {
@@ -191,7 +322,7 @@ mod api {
             let (details, body, _ct) = self
                 .details_with_body(&id.0)
                 .await
-                .map_err(|e| CloudTaskError::Http(format!("get_task_details failed: {e}")))?;
+                .map_err(|e| CloudTaskError::Http(format!("get_task_details failed: {e:#}")))?;
             let prompt = details.user_text_prompt();
             let mut messages = details.assistant_text_messages();
             if messages.is_empty() {
@@ -274,37 +405,59 @@ mod api {
                         prompt.chars().count(),
                         e
                     ));
-                    Err(CloudTaskError::Http(format!("create_task failed: {e}")))
+                    Err(CloudTaskError::Http(format!("create_task failed: {e:#}")))
                 }
             }
         }
 
+        pub(crate) async fn cancel(&self, id: TaskId) -> Result<()> {
+            let cancelled = self
+                .backend
+                .cancel_task(&id.0)
+                .await
+                .map_err(|e| CloudTaskError::Http(format!("cancel_task failed: {e:#}")))?;
+            append_error_log(&format!(
+                "http.cancel_task: id={} cancelled={cancelled}",
+                id.0
+            ));
+            if cancelled {
+                Ok(())
+            } else {
+                Err(CloudTaskError::AlreadyFinished(id.0))
+            }
+        }
+
         async fn details_with_body(
             &self,
             id: &str,
         ) -> anyhow::Result<(backend::CodeTaskDetailsResponse, String, String)> {
-            let (parsed, body, ct) = self.backend.get_task_details_with_body(id).await?;
+            let (parsed, body, ct) = self
+                .get_retries
+                .run(|| self.backend.get_task_details_with_body(id))
+                .await?;
             Ok((parsed, body, ct))
         }
     }
 
     pub(crate) struct Attempts<'a> {
         backend: &'a backend::Client,
+        get_retries: GetRetries,
     }
 
     impl<'a> Attempts<'a> {
         pub(crate) fn new(client: &'a HttpClient) -> Self {
             Self {
                 backend: &client.backend,
+                get_retries: client.get_retries,
             }
         }
 
         pub(crate) async fn list(&self, task: TaskId, turn_id: String) -> Result<Vec<TurnAttempt>> {
             let resp = self
-                .backend
-                .list_sibling_turns(&task.0, &turn_id)
+                .get_retries
+                .run(|| self.backend.list_sibling_turns(&task.0, &turn_id))
                 .await
-                .map_err(|e| CloudTaskError::Http(format!("list_sibling_turns failed: {e}")))?;
+                .map_err(|e| CloudTaskError::Http(format!("list_sibling_turns failed: {e:#}")))?;
 
             let mut attempts: Vec<TurnAttempt> = resp
                 .sibling_turns
@@ -331,6 +484,7 @@ mod api {
             &self,
             task_id: TaskId,
             diff_override: Option<String>,
+            target_dir: Option<PathBuf>,
             preflight: bool,
         ) -> Result<ApplyOutcome> {
             let id = task_id.0.clone();
@@ -338,7 +492,7 @@ mod api {
                 Some(diff) => diff,
                 None => {
                     let details = self.backend.get_task_details(&id).await.map_err(|e| {
-                        CloudTaskError::Http(format!("get_task_details failed: {e}"))
+                        CloudTaskError::Http(format!("get_task_details failed: {e:#}"))
                     })?;
                     details.unified_diff().ok_or_else(|| {
                         CloudTaskError::Msg(format!("No diff available for task {id}"))
@@ -363,7 +517,9 @@ mod api {
             }
 
             let req = codex_git::ApplyGitRequest {
-                cwd: std::env::current_dir().unwrap_or_else(|_| std::env::temp_dir()),
+                cwd: target_dir.unwrap_or_else(|| {
+                    std::env::current_dir().unwrap_or_else(|_| std::env::temp_dir())
+                }),
                 diff: diff.clone(),
                 revert: false,
                 preflight,
@@ -6,6 +6,7 @@ pub use api::AttemptStatus;
 pub use api::CloudBackend;
 pub use api::CloudTaskError;
 pub use api::CreatedTask;
+pub use api::Cursor;
 pub use api::DiffSummary;
 pub use api::Result;
 pub use api::TaskId;
@@ -14,12 +15,16 @@ pub use api::TaskSummary;
 pub use api::TaskText;
 pub use api::TurnAttempt;
 
+#[cfg(feature = "mock")]
+mod fixtures;
 #[cfg(feature = "mock")]
 mod mock;
 
 #[cfg(feature = "online")]
 mod http;
 
+#[cfg(feature = "mock")]
+pub use fixtures::FixtureClient;
 #[cfg(feature = "mock")]
 pub use mock::MockClient;
 
@@ -1,6 +1,8 @@
 use crate::ApplyOutcome;
 use crate::AttemptStatus;
 use crate::CloudBackend;
+use crate::CloudTaskError;
+use crate::Cursor;
 use crate::DiffSummary;
 use crate::Result;
 use crate::TaskId;
@@ -9,6 +11,8 @@ use crate::TaskSummary;
 use crate::TurnAttempt;
 use crate::api::TaskText;
 use chrono::Utc;
+use std::path::Path;
+use std::path::PathBuf;
 
 #[derive(Clone, Default)]
 pub struct MockClient;
@@ -60,6 +64,27 @@ impl CloudBackend for MockClient {
         Ok(out)
     }
 
+    async fn list_tasks_page(
+        &self,
+        env: Option<&str>,
+        cursor: Option<Cursor>,
+        limit: usize,
+    ) -> Result<(Vec<TaskSummary>, Option<Cursor>)> {
+        // The mock cursor is simply the offset of the next row.
+        let start = match cursor {
+            Some(Cursor(offset)) => offset
+                .parse::<usize>()
+                .map_err(|_| CloudTaskError::Msg(format!("invalid mock cursor: {offset}")))?,
+            None => 0,
+        };
+        let tasks = self.list_tasks(env).await?;
+        let total = tasks.len();
+        let page: Vec<TaskSummary> = tasks.into_iter().skip(start).take(limit.max(1)).collect();
+        let end = start.saturating_add(page.len());
+        let next = (end < total).then(|| Cursor(end.to_string()));
+        Ok((page, next))
+    }
+
     async fn get_task_diff(&self, id: TaskId) -> Result<Option<String>> {
         Ok(Some(mock_diff_for(&id)))
     }
@@ -81,25 +106,49 @@ impl CloudBackend for MockClient {
         })
     }
 
-    async fn apply_task(&self, id: TaskId, _diff_override: Option<String>) -> Result<ApplyOutcome> {
+    async fn apply_task(
+        &self,
+        id: TaskId,
+        _diff_override: Option<String>,
+        target_dir: Option<PathBuf>,
+    ) -> Result<ApplyOutcome> {
+        let location = mock_apply_location(target_dir.as_deref())?;
         Ok(ApplyOutcome {
             applied: true,
             status: crate::ApplyStatus::Success,
-            message: format!("Applied task {} locally (mock)", id.0),
+            message: format!("Applied task {} to {location} (mock)", id.0),
             skipped_paths: Vec::new(),
             conflict_paths: Vec::new(),
         })
     }
 
+    async fn cancel_task(&self, id: TaskId) -> Result<()> {
+        for env in [None, Some("env-A"), Some("env-B")] {
+            let tasks = self.list_tasks(env).await?;
+            if let Some(task) = tasks.into_iter().find(|t| t.id == id) {
+                return match task.status {
+                    TaskStatus::Pending => Ok(()),
+                    _ => Err(CloudTaskError::AlreadyFinished(id.0)),
+                };
+            }
+        }
+        Err(CloudTaskError::Msg(format!("unknown task {}", id.0)))
+    }
+
     async fn apply_task_preflight(
         &self,
         id: TaskId,
         _diff_override: Option<String>,
+        target_dir: Option<PathBuf>,
     ) -> Result<ApplyOutcome> {
+        let location = mock_apply_location(target_dir.as_deref())?;
         Ok(ApplyOutcome {
             applied: false,
             status: crate::ApplyStatus::Success,
-            message: format!("Preflight passed for task {} (mock)", id.0),
+            message: format!(
+                "Preflight passed for task {} against {location} (mock)",
+                id.0
+            ),
             skipped_paths: Vec::new(),
             conflict_paths: Vec::new(),
         })
@@ -137,6 +186,18 @@ impl CloudBackend for MockClient {
     }
 }
 
+/// Describe where a mock apply lands, rejecting target directories that do not exist.
+pub(crate) fn mock_apply_location(target_dir: Option<&Path>) -> Result<String> {
+    match target_dir {
+        Some(dir) if !dir.is_dir() => Err(CloudTaskError::Io(format!(
+            "target directory {} does not exist",
+            dir.display()
+        ))),
+        Some(dir) => Ok(dir.display().to_string()),
+        None => Ok("the current directory".to_string()),
+    }
+}
+
 fn mock_diff_for(id: &TaskId) -> String {
     match id.0.as_str() {
         "T-1000" => {
@@ -0,0 +1,56 @@
+#![cfg(feature = "online")]
+
+use codex_cloud_tasks_client::CloudBackend;
+use codex_cloud_tasks_client::CloudTaskError;
+use codex_cloud_tasks_client::HttpClient;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+use wiremock::matchers::query_param;
+
+#[tokio::test]
+async fn healthcheck_lists_a_single_task() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/codex/tasks/list"))
+        .and(query_param("limit", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "items": [] })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let client = HttpClient::new(server.uri()).expect("client");
+
+    client.healthcheck().await.expect("healthy backend");
+}
+
+#[tokio::test]
+async fn healthcheck_reports_rejected_credentials() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/codex/tasks/list"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("bad token"))
+        .mount(&server)
+        .await;
+    let client = HttpClient::new(server.uri()).expect("client");
+
+    let err = client.healthcheck().await.expect_err("unauthorized");
+
+    let CloudTaskError::Http(message) = err else {
+        panic!("expected an http error, got {err:?}");
+    };
+    assert!(
+        message.starts_with("healthcheck failed:") && message.contains("401"),
+        "unexpected message: {message}"
+    );
+}
+
+#[cfg(feature = "mock")]
+#[tokio::test]
+async fn mock_backend_is_healthy() {
+    codex_cloud_tasks_client::MockClient
+        .healthcheck()
+        .await
+        .expect("mock backend is always healthy");
+}
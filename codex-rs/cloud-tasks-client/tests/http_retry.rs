@@ -0,0 +1,96 @@
+#![cfg(feature = "online")]
+
+use std::time::Duration;
+
+use codex_cloud_tasks_client::CloudBackend;
+use codex_cloud_tasks_client::HttpClient;
+use codex_cloud_tasks_client::TaskId;
+use pretty_assertions::assert_eq;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+const DIFF: &str =
+    "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n@@ -1 +1 @@\n-old\n+new\n";
+
+async fn server_failing_then_serving_diff(failures: u64) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/codex/tasks/T1"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("try again"))
+        .up_to_n_times(failures)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/codex/tasks/T1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "current_diff_task_turn": {
+                "output_items": [{ "type": "output_diff", "diff": DIFF }]
+            }
+        })))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn get_task_diff_retries_transient_failures() {
+    let server = server_failing_then_serving_diff(2).await;
+    let client = HttpClient::new(server.uri())
+        .expect("client")
+        .with_get_retries(2, Duration::from_millis(1));
+
+    let diff = client
+        .get_task_diff(TaskId("T1".to_string()))
+        .await
+        .expect("diff after retries");
+
+    let requests = server.received_requests().await.expect("recorded requests");
+    assert_eq!((diff, requests.len()), (Some(DIFF.to_string()), 3));
+}
+
+#[tokio::test]
+async fn get_task_diff_gives_up_when_retries_are_exhausted() {
+    let server = server_failing_then_serving_diff(2).await;
+    let client = HttpClient::new(server.uri())
+        .expect("client")
+        .with_get_retries(1, Duration::from_millis(1));
+
+    let err = client
+        .get_task_diff(TaskId("T1".to_string()))
+        .await
+        .expect_err("second failure should surface");
+
+    let requests = server.received_requests().await.expect("recorded requests");
+    assert!(err.to_string().contains("503"), "unexpected error: {err}");
+    assert_eq!(requests.len(), 2);
+}
+
+#[tokio::test]
+async fn stalled_backend_times_out() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/codex/tasks/T1"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(30)))
+        .mount(&server)
+        .await;
+    let client = HttpClient::new(server.uri())
+        .expect("client")
+        .with_timeout(Duration::from_millis(50))
+        .with_get_retries(0, Duration::from_millis(1));
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(5),
+        client.get_task_diff(TaskId("T1".to_string())),
+    )
+    .await
+    .expect("request should time out instead of hanging");
+
+    let err = result.expect_err("stalled request should fail");
+    assert!(
+        err.to_string().contains("timed out"),
+        "unexpected error: {err}"
+    );
+}
@@ -0,0 +1,139 @@
+#![cfg(feature = "mock")]
+
+use codex_cloud_tasks_client::AttemptStatus;
+use codex_cloud_tasks_client::CloudBackend;
+use codex_cloud_tasks_client::CloudTaskError;
+use codex_cloud_tasks_client::FixtureClient;
+use codex_cloud_tasks_client::TaskId;
+use pretty_assertions::assert_eq;
+use std::path::Path;
+
+const DIFF: &str =
+    "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n@@ -1 +1 @@\n-old\n+new\n";
+
+fn write_fixtures(dir: &Path) -> std::io::Result<()> {
+    let summary = serde_json::json!({
+        "files_changed": 0,
+        "lines_added": 0,
+        "lines_removed": 0,
+    });
+    let tasks = serde_json::json!([
+        {
+            "id": "T-1",
+            "title": "Has a diff",
+            "status": "ready",
+            "updated_at": "2025-01-01T00:00:00Z",
+            "environment_id": "env-A",
+            "environment_label": "Env A",
+            "summary": summary,
+        },
+        {
+            "id": "T-2",
+            "title": "Empty diff",
+            "status": "ready",
+            "updated_at": "2025-01-01T00:00:00Z",
+            "environment_id": "env-B",
+            "environment_label": null,
+            "summary": summary,
+        },
+        {
+            "id": "T-3",
+            "title": "Error turn",
+            "status": "error",
+            "updated_at": "2025-01-01T00:00:00Z",
+            "environment_id": "env-A",
+            "environment_label": "Env A",
+            "summary": summary,
+        },
+    ]);
+    std::fs::write(dir.join("tasks.json"), tasks.to_string())?;
+    std::fs::create_dir(dir.join("tasks"))?;
+    std::fs::write(
+        dir.join("tasks/T-1.json"),
+        serde_json::json!({
+            "diff": DIFF,
+            "prompt": "Fix a.txt",
+            "messages": ["Updated a.txt."],
+        })
+        .to_string(),
+    )?;
+    std::fs::write(
+        dir.join("tasks/T-3.json"),
+        serde_json::json!({ "error": "task failed to start" }).to_string(),
+    )?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn fixture_tasks_are_listed_and_filtered_by_env() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    write_fixtures(dir.path()).expect("write fixtures");
+    let client = FixtureClient::load(dir.path()).expect("load fixtures");
+
+    let titles = |tasks: Vec<codex_cloud_tasks_client::TaskSummary>| {
+        tasks.into_iter().map(|t| t.title).collect::<Vec<_>>()
+    };
+    assert_eq!(
+        titles(client.list_tasks(None).await.expect("request")),
+        vec!["Has a diff", "Empty diff", "Error turn"]
+    );
+    assert_eq!(
+        titles(client.list_tasks(Some("env-A")).await.expect("request")),
+        vec!["Has a diff", "Error turn"]
+    );
+}
+
+#[tokio::test]
+async fn fixture_details_drive_diff_and_conversation() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    write_fixtures(dir.path()).expect("write fixtures");
+    let client = FixtureClient::load(dir.path()).expect("load fixtures");
+
+    assert_eq!(
+        client
+            .get_task_diff(TaskId("T-1".to_string()))
+            .await
+            .unwrap(),
+        Some(DIFF.to_string())
+    );
+    let text = client
+        .get_task_text(TaskId("T-1".to_string()))
+        .await
+        .expect("request");
+    assert_eq!(
+        (text.prompt, text.messages, text.attempt_status),
+        (
+            Some("Fix a.txt".to_string()),
+            vec!["Updated a.txt.".to_string()],
+            AttemptStatus::Completed
+        )
+    );
+
+    // No details file: the task simply has no diff.
+    assert_eq!(
+        client
+            .get_task_diff(TaskId("T-2".to_string()))
+            .await
+            .unwrap(),
+        None
+    );
+
+    let err = client
+        .get_task_diff(TaskId("T-3".to_string()))
+        .await
+        .expect_err("should fail");
+    assert!(
+        matches!(&err, CloudTaskError::Http(message) if message == "task failed to start"),
+        "unexpected error: {err:?}"
+    );
+}
+
+#[test]
+fn missing_fixture_directory_is_an_error() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let err = FixtureClient::load(dir.path().join("missing")).expect_err("should fail");
+    assert!(
+        matches!(&err, CloudTaskError::Io(message) if message.contains("tasks.json")),
+        "unexpected error: {err:?}"
+    );
+}
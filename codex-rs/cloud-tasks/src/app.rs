@@ -1,3 +1,6 @@
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -39,14 +42,120 @@ pub struct ApplyModalState {
     pub diff_override: Option<String>,
 }
 
+/// An apply request: the task plus the attempt diff to use instead of the
+/// task's default, if one was selected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApplyJob {
+    pub task_id: TaskId,
+    pub diff_override: Option<String>,
+    // Working tree to apply to; `None` applies to the current directory.
+    pub target_dir: Option<PathBuf>,
+}
+
+/// Client-side filter over [`TaskStatus`] for the task list.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StatusFilter {
+    #[default]
+    All,
+    InProgress,
+    Ready,
+    Applied,
+    Failed,
+}
+
+impl StatusFilter {
+    pub fn matches(self, status: &TaskStatus) -> bool {
+        match self {
+            StatusFilter::All => true,
+            StatusFilter::InProgress => *status == TaskStatus::Pending,
+            StatusFilter::Ready => *status == TaskStatus::Ready,
+            StatusFilter::Applied => *status == TaskStatus::Applied,
+            StatusFilter::Failed => *status == TaskStatus::Error,
+        }
+    }
+
+    /// The filter selected by the next press of the toggle key.
+    pub fn next(self) -> Self {
+        match self {
+            StatusFilter::All => StatusFilter::InProgress,
+            StatusFilter::InProgress => StatusFilter::Failed,
+            StatusFilter::Failed => StatusFilter::Ready,
+            StatusFilter::Ready => StatusFilter::Applied,
+            StatusFilter::Applied => StatusFilter::All,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StatusFilter::All => "All statuses",
+            StatusFilter::InProgress => "In progress",
+            StatusFilter::Ready => "Ready",
+            StatusFilter::Applied => "Applied",
+            StatusFilter::Failed => "Failed",
+        }
+    }
+}
+
+/// The tasks that pass `filter`, in list order.
+pub fn filter_tasks(tasks: &[TaskSummary], filter: StatusFilter) -> Vec<&TaskSummary> {
+    tasks.iter().filter(|t| filter.matches(&t.status)).collect()
+}
+
+/// Rank `tasks` by a case-insensitive fuzzy match of `query` against the
+/// title, best match first. Tasks that do not match are dropped; ties keep
+/// their list order.
+pub fn search_tasks<'a>(tasks: Vec<&'a TaskSummary>, query: &str) -> Vec<&'a TaskSummary> {
+    if query.is_empty() {
+        return tasks;
+    }
+    let mut scored: Vec<(i32, &TaskSummary)> = tasks
+        .into_iter()
+        .filter_map(|t| fuzzy_match(&t.title, query).map(|(_, score)| (score, t)))
+        .collect();
+    scored.sort_by_key(|(score, _)| *score);
+    scored.into_iter().map(|(_, t)| t).collect()
+}
+
+/// Title search box opened with `/`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchState {
+    pub query: String,
+    // Keys go to the search box until Enter.
+    pub editing: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CancelModalState {
+    pub task_id: TaskId,
+    pub title: String,
+}
+
+use crate::details_cache::file_name_for_task;
 use crate::scrollable_diff::ScrollableDiff;
 use codex_cloud_tasks_client::CloudBackend;
+use codex_cloud_tasks_client::Cursor;
 use codex_cloud_tasks_client::TaskId;
+use codex_cloud_tasks_client::TaskStatus;
 use codex_cloud_tasks_client::TaskSummary;
+use codex_common::fuzzy_match::fuzzy_match;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::time::MissedTickBehavior;
+
+/// Number of tasks requested per page.
+pub const TASK_PAGE_SIZE: usize = 20;
+/// Start fetching the next page once the selection is this close to the end.
+const LOAD_MORE_THRESHOLD: usize = 5;
+
 #[derive(Default)]
 pub struct App {
     pub tasks: Vec<TaskSummary>,
+    // Index into the tasks visible under `status_filter`.
     pub selected: usize,
+    pub status_filter: StatusFilter,
+    pub search: Option<SearchState>,
+    // Cursor for the next page of tasks, if the backend has more.
+    pub next_cursor: Option<Cursor>,
+    pub page_inflight: bool,
     pub status: String,
     pub diff_overlay: Option<DiffOverlay>,
     pub spinner_start: Option<Instant>,
@@ -57,6 +166,7 @@ pub struct App {
     pub env_modal: Option<EnvModalState>,
     pub apply_modal: Option<ApplyModalState>,
     pub best_of_modal: Option<BestOfModalState>,
+    pub cancel_modal: Option<CancelModalState>,
     pub environments: Vec<EnvironmentRow>,
     pub env_last_loaded: Option<std::time::Instant>,
     pub env_loading: bool,
@@ -68,6 +178,13 @@ pub struct App {
     pub apply_preflight_inflight: bool,
     // Apply action spinner state
     pub apply_inflight: bool,
+    // Most recent apply and its error, kept so a failed apply can be retried
+    pub last_apply_job: Option<ApplyJob>,
+    pub apply_error: Option<String>,
+    // Directory diffs are applied to (from `--dir`); defaults to the cwd
+    pub apply_dir: Option<PathBuf>,
+    // Cancel request spinner state
+    pub cancel_inflight: bool,
     // Background enrichment coordination
     pub list_generation: u64,
     pub in_flight: std::collections::HashSet<String>,
@@ -79,6 +196,10 @@ impl App {
         Self {
             tasks: Vec::new(),
             selected: 0,
+            status_filter: StatusFilter::All,
+            search: None,
+            next_cursor: None,
+            page_inflight: false,
             status: "Press r to refresh".to_string(),
             diff_overlay: None,
             spinner_start: None,
@@ -88,6 +209,7 @@ impl App {
             env_modal: None,
             apply_modal: None,
             best_of_modal: None,
+            cancel_modal: None,
             environments: Vec::new(),
             env_last_loaded: None,
             env_loading: false,
@@ -96,37 +218,251 @@ impl App {
             best_of_n: 1,
             apply_preflight_inflight: false,
             apply_inflight: false,
+            last_apply_job: None,
+            apply_error: None,
+            apply_dir: None,
+            cancel_inflight: false,
             list_generation: 0,
             in_flight: std::collections::HashSet::new(),
         }
     }
 
+    /// Tasks shown in the list under the current status filter and search.
+    pub fn visible_tasks(&self) -> Vec<&TaskSummary> {
+        let filtered = filter_tasks(&self.tasks, self.status_filter);
+        match &self.search {
+            Some(search) => search_tasks(filtered, &search.query),
+            None => filtered,
+        }
+    }
+
+    pub fn selected_task(&self) -> Option<&TaskSummary> {
+        self.visible_tasks().get(self.selected).copied()
+    }
+
+    /// Keep the selection inside the visible list after it shrinks.
+    pub fn clamp_selection(&mut self) {
+        let visible = self.visible_tasks().len();
+        if self.selected >= visible {
+            self.selected = visible.saturating_sub(1);
+        }
+    }
+
+    pub fn open_search(&mut self) {
+        let search = self.search.get_or_insert_with(SearchState::default);
+        search.editing = true;
+    }
+
+    /// Replace the search query; the selection jumps to the best match.
+    pub fn set_search_query(&mut self, query: String) {
+        if let Some(search) = self.search.as_mut() {
+            search.query = query;
+            self.selected = 0;
+        }
+    }
+
+    pub fn clear_search(&mut self) {
+        self.search = None;
+        self.clamp_selection();
+    }
+
+    pub fn cycle_status_filter(&mut self) {
+        self.status_filter = self.status_filter.next();
+        self.clamp_selection();
+        self.status = format!("Showing: {}", self.status_filter.label());
+    }
+
     pub fn next(&mut self) {
-        if self.tasks.is_empty() {
+        let visible = self.visible_tasks().len();
+        if visible == 0 {
             return;
         }
-        self.selected = (self.selected + 1).min(self.tasks.len().saturating_sub(1));
+        self.selected = (self.selected + 1).min(visible - 1);
     }
 
     pub fn prev(&mut self) {
-        if self.tasks.is_empty() {
+        if self.visible_tasks().is_empty() {
             return;
         }
         if self.selected > 0 {
             self.selected -= 1;
         }
     }
+
+    /// Record a failed apply; `last_apply_job` is kept for [`App::take_retry_job`].
+    pub fn record_apply_failure(&mut self, error: String) {
+        self.apply_inflight = false;
+        self.status = format!("Apply failed: {error} (press r to retry)");
+        if let Some(m) = self.apply_modal.as_mut() {
+            m.result_message = Some(format!("Apply failed: {error}"));
+            m.result_level = Some(ApplyResultLevel::Error);
+        }
+        self.apply_error = Some(error);
+    }
+
+    /// The job to re-issue after a failed apply, clearing the error. Returns
+    /// `None` when nothing failed or while an apply/preflight is running.
+    pub fn take_retry_job(&mut self) -> Option<ApplyJob> {
+        if self.apply_inflight || self.apply_preflight_inflight || self.apply_error.is_none() {
+            return None;
+        }
+        let job = self.last_apply_job.clone()?;
+        self.apply_error = None;
+        if let Some(m) = self.apply_modal.as_mut() {
+            m.result_message = None;
+            m.result_level = None;
+        }
+        Some(job)
+    }
+
+    /// Whether a scheduled refresh should be skipped: a modal, the task
+    /// overlay or the new-task page has focus, or a refresh is already running.
+    pub fn auto_refresh_paused(&self) -> bool {
+        self.diff_overlay.is_some()
+            || self.env_modal.is_some()
+            || self.apply_modal.is_some()
+            || self.best_of_modal.is_some()
+            || self.cancel_modal.is_some()
+            || self.new_task.is_some()
+            || self.refresh_inflight
+    }
+
+    /// Ask to confirm cancelling the selected task. Only in-progress tasks
+    /// can be cancelled; for anything else the status explains why and no
+    /// modal opens. Returns whether the modal was opened.
+    pub fn open_cancel_modal(&mut self) -> bool {
+        let Some(task) = self.selected_task() else {
+            return false;
+        };
+        if task.status != TaskStatus::Pending {
+            self.status = format!("'{}' has already finished; nothing to cancel", task.title);
+            return false;
+        }
+        self.cancel_modal = Some(CancelModalState {
+            task_id: task.id.clone(),
+            title: task.title.clone(),
+        });
+        true
+    }
+
+    /// Whether the selection is near enough to the end of the list that the
+    /// next page should be fetched now.
+    pub fn should_load_more(&self) -> bool {
+        self.next_cursor.is_some()
+            && !self.page_inflight
+            && !self.refresh_inflight
+            && self.selected + LOAD_MORE_THRESHOLD >= self.visible_tasks().len()
+    }
+
+    /// Append a page of tasks, skipping any already listed (pages can shift
+    /// when tasks are created between fetches). Existing rows keep their
+    /// position, so the selection stays on the same task.
+    pub fn append_page(&mut self, tasks: Vec<TaskSummary>, next_cursor: Option<Cursor>) {
+        for task in tasks {
+            if !self.tasks.iter().any(|t| t.id == task.id) {
+                self.tasks.push(task);
+            }
+        }
+        self.next_cursor = next_cursor;
+    }
+
+    /// Replace the list with a freshly loaded first page, keeping the
+    /// selection on the same task while it is still listed.
+    pub fn replace_tasks(&mut self, tasks: Vec<TaskSummary>, next_cursor: Option<Cursor>) {
+        let selected_id = self.selected_task().map(|t| t.id.clone());
+        self.tasks = tasks;
+        self.next_cursor = next_cursor;
+        self.reselect(selected_id);
+    }
+
+    /// Merge a re-fetched first page into the loaded list: listed tasks are
+    /// updated in place and new ones go on top, while rows from further pages
+    /// and the cursor for the next page are kept. The selection stays on the
+    /// same task.
+    pub fn merge_first_page(&mut self, tasks: Vec<TaskSummary>) {
+        let selected_id = self.selected_task().map(|t| t.id.clone());
+        let mut new_tasks = Vec::new();
+        for task in tasks {
+            match self.tasks.iter_mut().find(|t| t.id == task.id) {
+                Some(existing) => *existing = task,
+                None => new_tasks.push(task),
+            }
+        }
+        self.tasks.splice(0..0, new_tasks);
+        self.reselect(selected_id);
+    }
+
+    /// Select the task with `id` if it is visible, else clamp the selection.
+    fn reselect(&mut self, id: Option<TaskId>) {
+        let index = id.and_then(|id| self.visible_tasks().iter().position(|t| t.id == id));
+        match index {
+            Some(index) => self.selected = index,
+            None => self.clamp_selection(),
+        }
+    }
 }
 
+/// Load the first page of tasks for `env`, returning the cursor for the rest.
 pub async fn load_tasks(
     backend: &dyn CloudBackend,
     env: Option<&str>,
-) -> anyhow::Result<Vec<TaskSummary>> {
+) -> anyhow::Result<(Vec<TaskSummary>, Option<Cursor>)> {
+    load_tasks_page(backend, env, None).await
+}
+
+/// Load the page of tasks starting at `cursor`.
+pub async fn load_tasks_page(
+    backend: &dyn CloudBackend,
+    env: Option<&str>,
+    cursor: Option<Cursor>,
+) -> anyhow::Result<(Vec<TaskSummary>, Option<Cursor>)> {
     // In later milestones, add a small debounce, spinner, and error display.
-    let tasks = tokio::time::timeout(Duration::from_secs(5), backend.list_tasks(env)).await??;
+    let (tasks, next_cursor) = tokio::time::timeout(
+        Duration::from_secs(5),
+        backend.list_tasks_page(env, cursor, TASK_PAGE_SIZE),
+    )
+    .await??;
     // Hide review-only tasks from the main list.
     let filtered: Vec<TaskSummary> = tasks.into_iter().filter(|t| !t.is_review).collect();
-    Ok(filtered)
+    Ok((filtered, next_cursor))
+}
+
+/// Probe the backend at startup. Returns the status line to show when it is
+/// unreachable or rejects our credentials.
+pub async fn backend_health_status(backend: &dyn CloudBackend) -> Option<String> {
+    backend
+        .healthcheck()
+        .await
+        .err()
+        .map(|e| format!("Codex Cloud is unavailable: {e}"))
+}
+
+/// Write `diff` to a new patch file under `dir` and return its path. The file
+/// is named after `task_id`, escaped like the details cache does, with a
+/// counter (`codex-task-<id>-1.patch`, ...) appended when the plain name is
+/// taken. Existing files are never overwritten.
+pub fn export_diff(dir: &Path, task_id: &TaskId, diff: &str) -> std::io::Result<PathBuf> {
+    let name = file_name_for_task(task_id);
+    let mut n = 0;
+    loop {
+        let path = if n == 0 {
+            dir.join(format!("codex-task-{name}.patch"))
+        } else {
+            dir.join(format!("codex-task-{name}-{n}.patch"))
+        };
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(mut file) => {
+                file.write_all(diff.as_bytes())?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => n += 1,
+            Err(e) => return Err(e),
+        }
+    }
 }
 
 pub struct DiffOverlay {
@@ -265,6 +601,8 @@ impl DiffOverlay {
         self.text_lines = text_lines.clone();
         self.prompt = prompt;
 
+        self.sd
+            .set_diff_gutter(matches!(self.current_view, DetailView::Diff));
         match self.current_view {
             DetailView::Diff => {
                 if diff_lines.is_empty() {
@@ -294,15 +632,32 @@ pub enum DetailView {
 /// These let the UI event loop remain responsive and keep the spinner animating.
 #[derive(Debug)]
 pub enum AppEvent {
+    /// The first page of the task list requested under `generation`.
     TasksLoaded {
         env: Option<String>,
-        result: anyhow::Result<Vec<TaskSummary>>,
+        generation: u64,
+        result: anyhow::Result<(Vec<TaskSummary>, Option<Cursor>)>,
+    },
+    /// The first page re-fetched by an auto-refresh under `generation`; merged
+    /// into the list instead of replacing it.
+    TasksRefreshed {
+        env: Option<String>,
+        generation: u64,
+        result: anyhow::Result<(Vec<TaskSummary>, Option<Cursor>)>,
     },
+    /// A further page of tasks for the list loaded under `generation`.
+    TasksPageLoaded {
+        env: Option<String>,
+        generation: u64,
+        result: anyhow::Result<(Vec<TaskSummary>, Option<Cursor>)>,
+    },
+    /// The startup healthcheck failed; carries the status line to show.
+    BackendUnavailable(String),
     // Background diff summary events were planned; removed for now to keep code minimal.
     /// Autodetection of a likely environment id finished
     EnvironmentAutodetected(anyhow::Result<crate::env_detect::AutodetectSelection>),
     /// Background completion of environment list fetch
-    EnvironmentsLoaded(anyhow::Result<Vec<EnvironmentRow>>),
+    EnvironmentsLoaded(Result<Vec<EnvironmentRow>, crate::env_detect::EnvironmentsError>),
     DetailsDiffLoaded {
         id: TaskId,
         title: String,
@@ -343,6 +698,33 @@ pub enum AppEvent {
         id: TaskId,
         result: std::result::Result<codex_cloud_tasks_client::ApplyOutcome, String>,
     },
+    /// Background completion of a cancel request
+    CancelFinished {
+        id: TaskId,
+        title: String,
+        result: codex_cloud_tasks_client::Result<()>,
+    },
+    /// Periodic tick from `--auto-refresh`
+    AutoRefreshDue,
+}
+
+/// Send [`AppEvent::AutoRefreshDue`] every `interval`, starting one interval
+/// from now. The task stops once the receiving side is dropped.
+pub fn spawn_auto_refresh(
+    interval: Duration,
+    tx: UnboundedSender<AppEvent>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+        // A slow frame should not trigger a burst of catch-up refreshes.
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            if tx.send(AppEvent::AutoRefreshDue).is_err() {
+                break;
+            }
+        }
+    })
 }
 
 // Convenience aliases; currently unused.
@@ -354,6 +736,7 @@ mod tests {
     struct FakeBackend {
         // maps env key to titles
         by_env: std::collections::HashMap<Option<String>, Vec<&'static str>>,
+        healthcheck_error: Option<&'static str>,
     }
 
     #[async_trait::async_trait]
@@ -426,6 +809,7 @@ mod tests {
             &self,
             _id: TaskId,
             _diff_override: Option<String>,
+            _target_dir: Option<PathBuf>,
         ) -> codex_cloud_tasks_client::Result<codex_cloud_tasks_client::ApplyOutcome> {
             Err(codex_cloud_tasks_client::CloudTaskError::Unimplemented(
                 "not used in test",
@@ -436,12 +820,19 @@ mod tests {
             &self,
             _id: TaskId,
             _diff_override: Option<String>,
+            _target_dir: Option<PathBuf>,
         ) -> codex_cloud_tasks_client::Result<codex_cloud_tasks_client::ApplyOutcome> {
             Err(codex_cloud_tasks_client::CloudTaskError::Unimplemented(
                 "not used in test",
             ))
         }
 
+        async fn cancel_task(&self, _id: TaskId) -> codex_cloud_tasks_client::Result<()> {
+            Err(codex_cloud_tasks_client::CloudTaskError::Unimplemented(
+                "not used in test",
+            ))
+        }
+
         async fn create_task(
             &self,
             _env_id: &str,
@@ -454,6 +845,36 @@ mod tests {
                 "not used in test",
             ))
         }
+
+        async fn healthcheck(&self) -> codex_cloud_tasks_client::Result<()> {
+            match self.healthcheck_error {
+                Some(error) => Err(codex_cloud_tasks_client::CloudTaskError::Http(
+                    error.to_string(),
+                )),
+                None => Ok(()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn backend_health_status_reports_unhealthy_backend() {
+        let healthy = FakeBackend {
+            by_env: std::collections::HashMap::new(),
+            healthcheck_error: None,
+        };
+        assert_eq!(backend_health_status(&healthy).await, None);
+
+        let unhealthy = FakeBackend {
+            by_env: std::collections::HashMap::new(),
+            healthcheck_error: Some("healthcheck failed: 401 Unauthorized"),
+        };
+        assert_eq!(
+            backend_health_status(&unhealthy).await,
+            Some(
+                "Codex Cloud is unavailable: http error: healthcheck failed: 401 Unauthorized"
+                    .to_string()
+            )
+        );
     }
 
     #[tokio::test]
@@ -463,19 +884,353 @@ mod tests {
         by_env.insert(None, vec!["root-1", "root-2"]);
         by_env.insert(Some("env-A".to_string()), vec!["A-1"]);
         by_env.insert(Some("env-B".to_string()), vec!["B-1", "B-2", "B-3"]);
-        let backend = FakeBackend { by_env };
+        let backend = FakeBackend {
+            by_env,
+            healthcheck_error: None,
+        };
 
         // Act + Assert
-        let root = load_tasks(&backend, None).await.unwrap();
+        let (root, _) = load_tasks(&backend, None).await.unwrap();
         assert_eq!(root.len(), 2);
         assert_eq!(root[0].title, "root-1");
 
-        let a = load_tasks(&backend, Some("env-A")).await.unwrap();
+        let (a, _) = load_tasks(&backend, Some("env-A")).await.unwrap();
         assert_eq!(a.len(), 1);
         assert_eq!(a[0].title, "A-1");
 
-        let b = load_tasks(&backend, Some("env-B")).await.unwrap();
+        let (b, _) = load_tasks(&backend, Some("env-B")).await.unwrap();
         assert_eq!(b.len(), 3);
         assert_eq!(b[2].title, "B-3");
     }
+
+    fn summary(id: &str) -> TaskSummary {
+        TaskSummary {
+            id: TaskId(id.to_string()),
+            title: id.to_string(),
+            status: codex_cloud_tasks_client::TaskStatus::Ready,
+            updated_at: Utc::now(),
+            environment_id: None,
+            environment_label: None,
+            summary: codex_cloud_tasks_client::DiffSummary::default(),
+            is_review: false,
+            attempt_total: Some(1),
+        }
+    }
+
+    #[test]
+    fn append_page_skips_rows_already_listed() {
+        let mut app = App::new();
+        app.tasks = vec![summary("T-1"), summary("T-2"), summary("T-3")];
+        app.selected = 2;
+        app.next_cursor = Some(Cursor("3".to_string()));
+
+        // The page overlaps the selected row because a task was created
+        // between fetches.
+        app.append_page(vec![summary("T-3"), summary("T-4")], None);
+
+        let ids: Vec<&str> = app.tasks.iter().map(|t| t.id.0.as_str()).collect();
+        assert_eq!(
+            (ids, app.tasks[app.selected].id.0.as_str(), app.next_cursor),
+            (vec!["T-1", "T-2", "T-3", "T-4"], "T-3", None)
+        );
+    }
+
+    #[test]
+    fn merge_first_page_keeps_loaded_pages_and_selection() {
+        let mut app = App::new();
+        app.tasks = vec![summary("T-1"), summary("T-2"), summary("T-3")];
+        app.selected = 1;
+        app.next_cursor = Some(Cursor("3".to_string()));
+        let mut updated = summary("T-1");
+        updated.title = "Updated".to_string();
+
+        app.merge_first_page(vec![summary("T-0"), updated]);
+
+        let rows: Vec<(&str, &str)> = app
+            .tasks
+            .iter()
+            .map(|t| (t.id.0.as_str(), t.title.as_str()))
+            .collect();
+        assert_eq!(
+            (
+                rows,
+                app.selected_task().map(|t| t.id.0.as_str()),
+                app.next_cursor.clone()
+            ),
+            (
+                vec![
+                    ("T-0", "T-0"),
+                    ("T-1", "Updated"),
+                    ("T-2", "T-2"),
+                    ("T-3", "T-3"),
+                ],
+                Some("T-2"),
+                Some(Cursor("3".to_string()))
+            )
+        );
+    }
+
+    #[test]
+    fn replace_tasks_keeps_selected_task() {
+        let mut app = App::new();
+        app.tasks = vec![summary("T-1"), summary("T-2")];
+        app.selected = 1;
+
+        app.replace_tasks(vec![summary("T-0"), summary("T-1"), summary("T-2")], None);
+
+        assert_eq!(app.selected_task().map(|t| t.id.0.as_str()), Some("T-2"));
+    }
+
+    #[test]
+    fn cancel_modal_opens_for_in_progress_task() {
+        let mut app = App::new();
+        let mut running = summary("T-1");
+        running.status = TaskStatus::Pending;
+        app.tasks = vec![summary("T-0"), running];
+        app.selected = 1;
+
+        let opened = app.open_cancel_modal();
+
+        assert_eq!(
+            (opened, app.cancel_modal),
+            (
+                true,
+                Some(CancelModalState {
+                    task_id: TaskId("T-1".to_string()),
+                    title: "T-1".to_string(),
+                })
+            )
+        );
+    }
+
+    #[test]
+    fn cancel_modal_refuses_finished_task() {
+        let mut app = App::new();
+        app.tasks = vec![summary("T-0")];
+
+        let opened = app.open_cancel_modal();
+
+        assert_eq!(
+            (opened, app.cancel_modal, app.status.as_str()),
+            (false, None, "'T-0' has already finished; nothing to cancel")
+        );
+    }
+
+    #[test]
+    fn filter_tasks_keeps_matching_statuses_in_order() {
+        let with_status = |id: &str, status: TaskStatus| TaskSummary {
+            status,
+            ..summary(id)
+        };
+        let tasks = vec![
+            with_status("T-0", TaskStatus::Ready),
+            with_status("T-1", TaskStatus::Error),
+            with_status("T-2", TaskStatus::Pending),
+            with_status("T-3", TaskStatus::Error),
+            with_status("T-4", TaskStatus::Applied),
+        ];
+        let ids = |filter| -> Vec<&str> {
+            filter_tasks(&tasks, filter)
+                .into_iter()
+                .map(|t| t.id.0.as_str())
+                .collect()
+        };
+
+        assert_eq!(
+            (
+                ids(StatusFilter::All),
+                ids(StatusFilter::Failed),
+                ids(StatusFilter::InProgress),
+                ids(StatusFilter::Ready),
+                ids(StatusFilter::Applied),
+            ),
+            (
+                vec!["T-0", "T-1", "T-2", "T-3", "T-4"],
+                vec!["T-1", "T-3"],
+                vec!["T-2"],
+                vec!["T-0"],
+                vec!["T-4"],
+            )
+        );
+    }
+
+    #[test]
+    fn cycling_the_filter_clamps_the_selection() {
+        let mut app = App::new();
+        let mut running = summary("T-1");
+        running.status = TaskStatus::Pending;
+        app.tasks = vec![summary("T-0"), running, summary("T-2")];
+        app.selected = 2;
+
+        app.cycle_status_filter();
+
+        assert_eq!(
+            (
+                app.status_filter,
+                app.selected,
+                app.selected_task().map(|t| t.id.0.as_str()),
+            ),
+            (StatusFilter::InProgress, 0, Some("T-1"))
+        );
+    }
+
+    #[test]
+    fn should_load_more_only_near_the_end_with_a_cursor() {
+        let mut app = App::new();
+        app.tasks = (0..20).map(|i| summary(&format!("T-{i}"))).collect();
+        app.next_cursor = Some(Cursor("20".to_string()));
+
+        app.selected = 0;
+        let at_top = app.should_load_more();
+        app.selected = 16;
+        let near_end = app.should_load_more();
+        app.page_inflight = true;
+        let while_loading = app.should_load_more();
+
+        assert_eq!((at_top, near_end, while_loading), (false, true, false));
+    }
+
+    #[test]
+    fn export_diff_writes_patch_and_avoids_collisions() {
+        let dir = tempfile::tempdir().unwrap();
+        let id = TaskId("T-42".to_string());
+        let diff = "diff --git a/f b/f\n--- a/f\n+++ b/f\n@@ -1 +1 @@\n-old\n+new\n";
+
+        let first = export_diff(dir.path(), &id, diff).unwrap();
+        let second = export_diff(dir.path(), &id, diff).unwrap();
+
+        assert_eq!(
+            (first.clone(), second.clone()),
+            (
+                dir.path().join("codex-task-T-42.patch"),
+                dir.path().join("codex-task-T-42-1.patch"),
+            )
+        );
+        assert_eq!(std::fs::read_to_string(&first).unwrap(), diff);
+        assert_eq!(std::fs::read_to_string(&second).unwrap(), diff);
+    }
+
+    #[test]
+    fn export_diff_escapes_task_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let id = TaskId("../T/42".to_string());
+
+        let path = export_diff(dir.path(), &id, "diff").unwrap();
+
+        assert_eq!(path, dir.path().join("codex-task-%2E%2E%2FT%2F42.patch"));
+    }
+
+    #[tokio::test]
+    async fn auto_refresh_fires_once_per_interval() {
+        tokio::time::pause();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let handle = spawn_auto_refresh(Duration::from_secs(30), tx);
+
+        let mut counts = Vec::new();
+        for wait in [29, 2, 60] {
+            tokio::time::sleep(Duration::from_secs(wait)).await;
+            counts.push(
+                std::iter::from_fn(|| rx.try_recv().ok())
+                    .filter(|ev| matches!(ev, AppEvent::AutoRefreshDue))
+                    .count(),
+            );
+        }
+        handle.abort();
+
+        assert_eq!(counts, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn auto_refresh_pauses_while_a_modal_is_open() {
+        let mut app = App::new();
+        let idle = app.auto_refresh_paused();
+        app.cancel_modal = Some(CancelModalState {
+            task_id: TaskId("T-1".to_string()),
+            title: "t".to_string(),
+        });
+        let with_modal = app.auto_refresh_paused();
+
+        assert_eq!((idle, with_modal), (false, true));
+    }
+
+    #[test]
+    fn search_tasks_ranks_fuzzy_title_matches() {
+        let tasks: Vec<TaskSummary> = [
+            ("T-1", "Update README"),
+            ("T-2", "Fix flaky login test"),
+            ("T-3", "Refactor logger"),
+            ("T-4", "Add lint rule"),
+        ]
+        .into_iter()
+        .map(|(id, title)| TaskSummary {
+            title: title.to_string(),
+            ..summary(id)
+        })
+        .collect();
+
+        let ids = |query: &str| {
+            search_tasks(tasks.iter().collect(), query)
+                .into_iter()
+                .map(|t| t.id.0.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            (ids("LOG"), ids("ad"), ids(""), ids("zzz")),
+            (
+                vec!["T-3", "T-2"],
+                vec!["T-4", "T-1"],
+                vec!["T-1", "T-2", "T-3", "T-4"],
+                vec![],
+            )
+        );
+    }
+
+    #[test]
+    fn failed_apply_can_be_retried_once_idle() {
+        let job = ApplyJob {
+            task_id: TaskId("T-1".to_string()),
+            diff_override: Some("diff --git a/f b/f\n".to_string()),
+            target_dir: None,
+        };
+        let mut app = App::new();
+        app.apply_modal = Some(ApplyModalState {
+            task_id: job.task_id.clone(),
+            title: "t".to_string(),
+            result_message: None,
+            result_level: None,
+            skipped_paths: Vec::new(),
+            conflict_paths: Vec::new(),
+            diff_override: job.diff_override.clone(),
+        });
+        app.apply_inflight = true;
+        app.last_apply_job = Some(job.clone());
+        let while_running = app.take_retry_job();
+
+        app.record_apply_failure("boom".to_string());
+        let failed = (
+            app.apply_inflight,
+            app.apply_error.clone(),
+            app.apply_modal.as_ref().and_then(|m| m.result_level),
+        );
+        let retry = app.take_retry_job();
+
+        assert_eq!(while_running, None);
+        assert_eq!(
+            failed,
+            (
+                false,
+                Some("boom".to_string()),
+                Some(ApplyResultLevel::Error)
+            )
+        );
+        assert_eq!(retry, Some(job));
+        assert_eq!(
+            (
+                app.apply_error,
+                app.apply_modal.and_then(|m| m.result_message)
+            ),
+            (None, None)
+        );
+    }
 }
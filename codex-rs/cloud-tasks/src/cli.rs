@@ -1,6 +1,8 @@
 use clap::Args;
 use clap::Parser;
 use codex_common::CliConfigOverrides;
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser, Debug, Default)]
 #[command(version)]
@@ -8,6 +10,14 @@ pub struct Cli {
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 
+    /// Refresh the task list every SECONDS while no modal is open.
+    #[arg(long = "auto-refresh", value_name = "SECONDS", value_parser = parse_auto_refresh)]
+    pub auto_refresh: Option<Duration>,
+
+    /// Apply task diffs to the working tree at DIR instead of the current directory.
+    #[arg(long = "dir", value_name = "DIR")]
+    pub apply_dir: Option<PathBuf>,
+
     #[command(subcommand)]
     pub command: Option<Command>,
 }
@@ -16,6 +26,8 @@ pub struct Cli {
 pub enum Command {
     /// Submit a new Codex Cloud task without launching the TUI.
     Exec(ExecCommand),
+    /// List the Codex Cloud environments available to `exec --env`.
+    Environments(EnvironmentsCommand),
 }
 
 #[derive(Debug, Args)]
@@ -24,6 +36,10 @@ pub struct ExecCommand {
     #[arg(value_name = "QUERY")]
     pub query: Option<String>,
 
+    /// Read the task prompt from the file at PATH instead of QUERY or stdin.
+    #[arg(long = "query-file", value_name = "PATH", conflicts_with = "query")]
+    pub query_file: Option<PathBuf>,
+
     /// Target environment identifier (see `codex cloud` to browse).
     #[arg(long = "env", value_name = "ENV_ID")]
     pub environment: String,
@@ -35,6 +51,17 @@ pub struct ExecCommand {
         value_parser = parse_attempts
     )]
     pub attempts: usize,
+
+    /// Print the created task as JSON (`id`, `url`, `environment`) instead of a bare URL.
+    #[arg(long = "json", default_value_t = false)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct EnvironmentsCommand {
+    /// Print the environments as a JSON array instead of `ID<TAB>LABEL` lines.
+    #[arg(long = "json", default_value_t = false)]
+    pub json: bool,
 }
 
 fn parse_attempts(input: &str) -> Result<usize, String> {
@@ -47,3 +74,54 @@ fn parse_attempts(input: &str) -> Result<usize, String> {
         Err("attempts must be between 1 and 4".to_string())
     }
 }
+
+fn parse_auto_refresh(input: &str) -> Result<Duration, String> {
+    let secs: u64 = input
+        .parse()
+        .map_err(|_| "auto-refresh must be a whole number of seconds".to_string())?;
+    if secs == 0 {
+        return Err("auto-refresh must be at least 1 second".to_string());
+    }
+    Ok(Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::error::ErrorKind;
+
+    #[test]
+    fn query_file_conflicts_with_positional_query() {
+        for query in ["do the thing", "-"] {
+            let err = Cli::try_parse_from([
+                "cloud",
+                "exec",
+                "--env",
+                "env-1",
+                "--query-file",
+                "prompt.md",
+                query,
+            ])
+            .expect_err("query file and positional query should conflict");
+            assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+        }
+    }
+
+    #[test]
+    fn query_file_parses_without_positional_query() {
+        let cli = Cli::try_parse_from([
+            "cloud",
+            "exec",
+            "--env",
+            "env-1",
+            "--query-file",
+            "prompt.md",
+        ])
+        .expect("parse");
+        let Some(Command::Exec(exec)) = cli.command else {
+            panic!("expected exec subcommand");
+        };
+        assert_eq!(exec.query, None);
+        assert_eq!(exec.query_file, Some(PathBuf::from("prompt.md")));
+    }
+}
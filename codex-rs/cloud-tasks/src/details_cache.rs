@@ -0,0 +1,320 @@
+use chrono::DateTime;
+use chrono::Utc;
+use codex_cloud_tasks_client::AttemptStatus;
+use codex_cloud_tasks_client::TaskId;
+use codex_cloud_tasks_client::TaskText;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How long cached task details are shown without going back to the network.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Diff and conversation for a task as last fetched from the backend.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedDetails {
+    pub turn_id: Option<String>,
+    /// `None` only when the backend reported that the task has no diff.
+    pub diff: Option<String>,
+    pub prompt: Option<String>,
+    pub messages: Vec<String>,
+    pub sibling_turn_ids: Vec<String>,
+    pub attempt_placement: Option<i64>,
+    #[serde(with = "AttemptStatusDef")]
+    pub attempt_status: AttemptStatus,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "AttemptStatus")]
+enum AttemptStatusDef {
+    Pending,
+    InProgress,
+    Completed,
+    Failed,
+    Cancelled,
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    fetched_at: DateTime<Utc>,
+    /// The task's `updated_at` when the details were fetched; a new turn or
+    /// attempt bumps it and so invalidates the entry.
+    task_updated_at: DateTime<Utc>,
+    details: CachedDetails,
+}
+
+/// On-disk cache of task details under `codex_home/cloud-cache/`, one file per task.
+///
+/// An entry is written in one go once both the diff and the conversation of a
+/// turn have been fetched, so a diff is never shown next to messages from a
+/// different turn and a failed diff fetch never looks like "no diff". Entries
+/// are only served while the task's `updated_at` is unchanged, so a new turn
+/// or attempt is fetched right away instead of after the TTL.
+pub struct DetailsCache {
+    dir: PathBuf,
+    ttl: Duration,
+    // Two views of the same task may finish loading at once.
+    write_lock: Mutex<()>,
+}
+
+impl DetailsCache {
+    pub fn new(dir: PathBuf, ttl: Duration) -> Self {
+        Self {
+            dir,
+            ttl,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    pub fn in_codex_home(codex_home: &Path) -> Self {
+        Self::new(codex_home.join("cloud-cache"), DEFAULT_TTL)
+    }
+
+    /// Cached details for `task_id` if they were stored within the TTL while
+    /// the task was last updated at `task_updated_at`.
+    pub fn load(&self, task_id: &TaskId, task_updated_at: DateTime<Utc>) -> Option<CachedDetails> {
+        let entry = self.read_entry(task_id)?;
+        if entry.task_updated_at != task_updated_at {
+            return None;
+        }
+        let age = Utc::now()
+            .signed_duration_since(entry.fetched_at)
+            .to_std()
+            .unwrap_or_default();
+        (age < self.ttl).then_some(entry.details)
+    }
+
+    /// Stores the diff and conversation of a finished attempt, replacing any
+    /// previous entry. `task_updated_at` is the task's `updated_at` as listed
+    /// when the details were requested. `diff` must come from a successful
+    /// fetch, with `None` meaning the backend has no diff for the task. Running
+    /// attempts are still changing, so they are not cached.
+    pub fn store(
+        &self,
+        task_id: &TaskId,
+        task_updated_at: DateTime<Utc>,
+        diff: Option<&str>,
+        text: &TaskText,
+    ) {
+        if matches!(
+            text.attempt_status,
+            AttemptStatus::Pending | AttemptStatus::InProgress
+        ) {
+            return;
+        }
+        let entry = CacheEntry {
+            fetched_at: Utc::now(),
+            task_updated_at,
+            details: CachedDetails {
+                turn_id: text.turn_id.clone(),
+                diff: diff.map(str::to_string),
+                prompt: text.prompt.clone(),
+                messages: text.messages.clone(),
+                sibling_turn_ids: text.sibling_turn_ids.clone(),
+                attempt_placement: text.attempt_placement,
+                attempt_status: text.attempt_status,
+            },
+        };
+        let _guard = self
+            .write_lock
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let result = std::fs::create_dir_all(&self.dir).and_then(|()| {
+            let json = serde_json::to_vec(&entry).map_err(std::io::Error::other)?;
+            std::fs::write(self.path_for(task_id), json)
+        });
+        if let Err(e) = result {
+            crate::append_error_log(format!("details cache: failed to write {}: {e}", task_id.0));
+        }
+    }
+
+    fn read_entry(&self, task_id: &TaskId) -> Option<CacheEntry> {
+        let bytes = std::fs::read(self.path_for(task_id)).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    fn path_for(&self, task_id: &TaskId) -> PathBuf {
+        self.dir
+            .join(format!("{}.json", file_name_for_task(task_id)))
+    }
+}
+
+/// Percent-encodes every byte of the id outside `[A-Za-z0-9_-]`, so distinct ids
+/// (e.g. `a/1` and `a_1`) never share a file.
+pub(crate) fn file_name_for_task(task_id: &TaskId) -> String {
+    let mut name = String::with_capacity(task_id.0.len());
+    for byte in task_id.0.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' {
+            name.push(char::from(byte));
+        } else {
+            name.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn updated_at() -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000, 0).expect("timestamp")
+    }
+
+    fn task_text(turn_id: &str, message: &str) -> TaskText {
+        TaskText {
+            prompt: Some("Fix the bug".to_string()),
+            messages: vec![message.to_string()],
+            turn_id: Some(turn_id.to_string()),
+            sibling_turn_ids: vec!["turn-2".to_string()],
+            attempt_placement: Some(0),
+            attempt_status: AttemptStatus::Completed,
+        }
+    }
+
+    #[test]
+    fn stores_diff_and_text_in_one_entry() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cache = DetailsCache::in_codex_home(dir.path());
+        let id = TaskId("task_e/1".to_string());
+
+        cache.store(
+            &id,
+            updated_at(),
+            Some("diff --git a/x b/x"),
+            &task_text("turn-1", "Done."),
+        );
+
+        assert!(dir.path().join("cloud-cache/task_e%2F1.json").is_file());
+        assert_eq!(
+            cache.load(&id, updated_at()),
+            Some(CachedDetails {
+                turn_id: Some("turn-1".to_string()),
+                diff: Some("diff --git a/x b/x".to_string()),
+                prompt: Some("Fix the bug".to_string()),
+                messages: vec!["Done.".to_string()],
+                sibling_turn_ids: vec!["turn-2".to_string()],
+                attempt_placement: Some(0),
+                attempt_status: AttemptStatus::Completed,
+            })
+        );
+    }
+
+    #[test]
+    fn hit_is_served_from_disk() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let id = TaskId("T-1000".to_string());
+        {
+            let writer = DetailsCache::in_codex_home(dir.path());
+            writer.store(&id, updated_at(), None, &task_text("turn-1", "Done."));
+        }
+
+        // A fresh cache over the same directory answers without any backend.
+        let reader = DetailsCache::in_codex_home(dir.path());
+        let details = reader.load(&id, updated_at()).expect("cache hit");
+        assert_eq!(details.messages, vec!["Done.".to_string()]);
+        assert_eq!(
+            reader.load(&TaskId("T-1001".to_string()), updated_at()),
+            None
+        );
+    }
+
+    #[test]
+    fn newer_turn_replaces_entry() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cache = DetailsCache::in_codex_home(dir.path());
+        let id = TaskId("T-1000".to_string());
+        cache.store(
+            &id,
+            updated_at(),
+            Some("old diff"),
+            &task_text("turn-1", "First."),
+        );
+
+        cache.store(&id, updated_at(), None, &task_text("turn-3", "Second."));
+
+        let details = cache.load(&id, updated_at()).expect("cache hit");
+        assert_eq!(details.turn_id.as_deref(), Some("turn-3"));
+        assert_eq!(details.diff, None);
+        assert_eq!(details.messages, vec!["Second.".to_string()]);
+    }
+
+    #[test]
+    fn task_update_invalidates_entry() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cache = DetailsCache::in_codex_home(dir.path());
+        let id = TaskId("T-1000".to_string());
+        cache.store(
+            &id,
+            updated_at(),
+            Some("old diff"),
+            &task_text("turn-1", "First."),
+        );
+
+        let newer = updated_at() + chrono::Duration::seconds(1);
+        assert_eq!(cache.load(&id, newer), None);
+    }
+
+    #[test]
+    fn similar_ids_use_distinct_files() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cache = DetailsCache::in_codex_home(dir.path());
+        let slash = TaskId("a/1".to_string());
+        let underscore = TaskId("a_1".to_string());
+        cache.store(
+            &slash,
+            updated_at(),
+            Some("slash diff"),
+            &task_text("turn-1", "Slash."),
+        );
+        cache.store(
+            &underscore,
+            updated_at(),
+            None,
+            &task_text("turn-1", "Underscore."),
+        );
+
+        assert_eq!(
+            (
+                cache
+                    .load(&slash, updated_at())
+                    .and_then(|details| details.diff),
+                cache
+                    .load(&underscore, updated_at())
+                    .map(|details| details.messages),
+            ),
+            (
+                Some("slash diff".to_string()),
+                Some(vec!["Underscore.".to_string()])
+            )
+        );
+    }
+
+    #[test]
+    fn running_attempts_are_not_cached() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cache = DetailsCache::in_codex_home(dir.path());
+        let id = TaskId("T-1001".to_string());
+        let text = TaskText {
+            attempt_status: AttemptStatus::InProgress,
+            ..task_text("turn-1", "Working…")
+        };
+        cache.store(&id, updated_at(), None, &text);
+
+        assert_eq!(cache.load(&id, updated_at()), None);
+    }
+
+    #[test]
+    fn expired_entries_are_ignored() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cache = DetailsCache::new(dir.path().join("cloud-cache"), Duration::ZERO);
+        let id = TaskId("T-1000".to_string());
+        cache.store(&id, updated_at(), None, &task_text("turn-1", "Done."));
+
+        assert_eq!(cache.load(&id, updated_at()), None);
+    }
+}
@@ -1,9 +1,49 @@
+use reqwest::StatusCode;
 use reqwest::header::CONTENT_TYPE;
 use reqwest::header::HeaderMap;
 use std::collections::HashMap;
 use tracing::info;
 use tracing::warn;
 
+/// Why [`list_environments`] could not produce any environments.
+#[derive(Debug, thiserror::Error)]
+pub enum EnvironmentsError {
+    /// The request failed to complete or the server returned an error status.
+    #[error("network error: {0}")]
+    Network(String),
+    /// The backend rejected our credentials (401/403).
+    #[error("not authorized: {0}")]
+    Auth(String),
+    /// The request succeeded but the workspace has no environments.
+    #[error("no cloud environments are available for this workspace")]
+    Empty,
+    /// The response body was not the expected environments JSON.
+    #[error("unexpected environments response: {0}")]
+    Parse(String),
+}
+
+impl EnvironmentsError {
+    /// Short, actionable message for the environment picker.
+    pub fn hint(&self) -> String {
+        match self {
+            Self::Network(_) => {
+                "Could not reach Codex Cloud; check your connection and try again.".to_string()
+            }
+            Self::Auth(_) => {
+                "Not signed in to Codex Cloud or the session expired; re-run `codex login`."
+                    .to_string()
+            }
+            Self::Empty => {
+                "No environments found for this workspace; create one in Codex Cloud first."
+                    .to_string()
+            }
+            Self::Parse(_) => {
+                "Codex Cloud sent an unexpected response; see error.log for details.".to_string()
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, serde::Deserialize)]
 struct CodeEnvironment {
     id: String,
@@ -146,9 +186,16 @@ fn pick_environment_row(
 async fn get_json<T: serde::de::DeserializeOwned>(
     url: &str,
     headers: &HeaderMap,
-) -> anyhow::Result<T> {
-    let http = reqwest::Client::builder().build()?;
-    let res = http.get(url).headers(headers.clone()).send().await?;
+) -> Result<T, EnvironmentsError> {
+    let http = reqwest::Client::builder()
+        .build()
+        .map_err(|e| EnvironmentsError::Network(format!("failed to build HTTP client: {e}")))?;
+    let res = http
+        .get(url)
+        .headers(headers.clone())
+        .send()
+        .await
+        .map_err(|e| EnvironmentsError::Network(format!("GET {url}: {e}")))?;
     let status = res.status();
     let ct = res
         .headers()
@@ -158,13 +205,32 @@ async fn get_json<T: serde::de::DeserializeOwned>(
         .to_string();
     let body = res.text().await.unwrap_or_default();
     crate::append_error_log(format!("env: status={status} content-type={ct}"));
+    decode_response(url, status, &ct, &body)
+}
+
+/// Classify an environments response: auth failures and other error statuses
+/// first, then the JSON body.
+fn decode_response<T: serde::de::DeserializeOwned>(
+    url: &str,
+    status: StatusCode,
+    ct: &str,
+    body: &str,
+) -> Result<T, EnvironmentsError> {
+    if matches!(status, StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) {
+        return Err(EnvironmentsError::Auth(format!(
+            "GET {url} failed: {status}"
+        )));
+    }
     if !status.is_success() {
-        anyhow::bail!("GET {url} failed: {status}; content-type={ct}; body={body}");
+        return Err(EnvironmentsError::Network(format!(
+            "GET {url} failed: {status}; content-type={ct}; body={body}"
+        )));
     }
-    let parsed = serde_json::from_str::<T>(&body).map_err(|e| {
-        anyhow::anyhow!("Decode error for {url}: {e}; content-type={ct}; body={body}")
-    })?;
-    Ok(parsed)
+    serde_json::from_str::<T>(body).map_err(|e| {
+        EnvironmentsError::Parse(format!(
+            "Decode error for {url}: {e}; content-type={ct}; body={body}"
+        ))
+    })
 }
 
 fn get_git_origins() -> Vec<String> {
@@ -255,7 +321,7 @@ fn parse_owner_repo(url: &str) -> Option<(String, String)> {
 pub async fn list_environments(
     base_url: &str,
     headers: &HeaderMap,
-) -> anyhow::Result<Vec<crate::app::EnvironmentRow>> {
+) -> Result<Vec<crate::app::EnvironmentRow>, EnvironmentsError> {
     let mut map: HashMap<String, crate::app::EnvironmentRow> = HashMap::new();
 
     // 1) By-repo lookup for each parsed GitHub origin
@@ -341,6 +407,9 @@ pub async fn list_environments(
         }
     }
 
+    if map.is_empty() {
+        return Err(EnvironmentsError::Empty);
+    }
     let mut rows: Vec<crate::app::EnvironmentRow> = map.into_values().collect();
     rows.sort_by(|a, b| {
         // pinned first
@@ -359,3 +428,78 @@ pub async fn list_environments(
     });
     Ok(rows)
 }
+
+/// Environments reported in mock mode (`CODEX_CLOUD_TASKS_MODE=mock`); they line up
+/// with the environments `MockClient` knows about.
+pub fn mock_environments() -> Vec<crate::app::EnvironmentRow> {
+    vec![
+        crate::app::EnvironmentRow {
+            id: "env-A".to_string(),
+            label: Some("Env A".to_string()),
+            is_pinned: true,
+            repo_hints: None,
+        },
+        crate::app::EnvironmentRow {
+            id: "env-B".to_string(),
+            label: Some("Env B".to_string()),
+            is_pinned: false,
+            repo_hints: None,
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://example.test/api/codex/environments";
+
+    fn decode(status: StatusCode, body: &str) -> Result<Vec<CodeEnvironment>, EnvironmentsError> {
+        decode_response(URL, status, "application/json", body)
+    }
+
+    #[test]
+    fn decodes_environment_list() {
+        let body = r#"[
+            {"id": "env-1", "label": "web", "is_pinned": true, "task_count": 3},
+            {"id": "env-2"}
+        ]"#;
+
+        let envs = decode(StatusCode::OK, body).expect("environments");
+        assert_eq!(
+            envs.iter()
+                .map(|e| (e.id.as_str(), e.label.as_deref(), e.is_pinned, e.task_count))
+                .collect::<Vec<_>>(),
+            vec![
+                ("env-1", Some("web"), Some(true), Some(3)),
+                ("env-2", None, None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn classifies_failed_responses() {
+        let errors = [
+            decode(StatusCode::UNAUTHORIZED, r#"{"detail":"token expired"}"#),
+            decode(StatusCode::FORBIDDEN, ""),
+            decode(StatusCode::BAD_GATEWAY, "<html>bad gateway</html>"),
+            decode(StatusCode::OK, "<html>login</html>"),
+            decode(StatusCode::OK, r#"{"items": []}"#),
+        ]
+        .map(|result| match result.expect_err("should fail") {
+            EnvironmentsError::Network(_) => "network",
+            EnvironmentsError::Auth(_) => "auth",
+            EnvironmentsError::Empty => "empty",
+            EnvironmentsError::Parse(_) => "parse",
+        });
+
+        assert_eq!(errors, ["auth", "auth", "network", "parse", "parse"]);
+    }
+
+    #[test]
+    fn auth_hint_points_to_login() {
+        let hint = EnvironmentsError::Auth(format!("GET {URL} failed: 401")).hint();
+
+        assert!(hint.contains("codex login"), "{hint}");
+    }
+}
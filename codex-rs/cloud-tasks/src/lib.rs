@@ -1,5 +1,6 @@
 mod app;
 mod cli;
+mod details_cache;
 pub mod env_detect;
 mod new_task;
 pub mod scrollable_diff;
@@ -8,7 +9,7 @@ pub mod util;
 pub use cli::Cli;
 
 use anyhow::anyhow;
-use codex_login::AuthManager;
+use app::ApplyJob;
 use std::io::IsTerminal;
 use std::io::Read;
 use std::path::PathBuf;
@@ -21,14 +22,10 @@ use tracing_subscriber::EnvFilter;
 use util::append_error_log;
 use util::set_user_agent_suffix;
 
-struct ApplyJob {
-    task_id: codex_cloud_tasks_client::TaskId,
-    diff_override: Option<String>,
-}
-
 struct BackendContext {
     backend: Arc<dyn codex_cloud_tasks_client::CloudBackend>,
     base_url: String,
+    use_mock: bool,
 }
 
 async fn init_backend(user_agent_suffix: &str) -> anyhow::Result<BackendContext> {
@@ -42,69 +39,69 @@ async fn init_backend(user_agent_suffix: &str) -> anyhow::Result<BackendContext>
     set_user_agent_suffix(user_agent_suffix);
 
     if use_mock {
+        // CODEX_CLOUD_TASKS_FIXTURES points mock mode at a directory of JSON fixtures.
+        let backend: Arc<dyn codex_cloud_tasks_client::CloudBackend> =
+            match std::env::var_os("CODEX_CLOUD_TASKS_FIXTURES") {
+                Some(dir) => Arc::new(codex_cloud_tasks_client::FixtureClient::load(dir)?),
+                None => Arc::new(codex_cloud_tasks_client::MockClient),
+            };
         return Ok(BackendContext {
-            backend: Arc::new(codex_cloud_tasks_client::MockClient),
+            backend,
             base_url,
+            use_mock,
         });
     }
 
     let ua = codex_core::default_client::get_codex_user_agent();
     let mut http = codex_cloud_tasks_client::HttpClient::new(base_url.clone())?.with_user_agent(ua);
-    let style = if base_url.contains("/backend-api") {
-        "wham"
-    } else {
+    let style = if util::uses_codex_api(&base_url) {
         "codex-api"
+    } else {
+        "wham"
     };
     append_error_log(format!("startup: base_url={base_url} path_style={style}"));
 
-    let auth_manager = util::load_auth_manager().await;
-    let auth = match auth_manager.as_ref().and_then(AuthManager::auth) {
-        Some(auth) => auth,
-        None => {
-            eprintln!(
-                "Not signed in. Please run 'codex login' to sign in with ChatGPT, then re-run 'codex cloud'."
-            );
+    let auth = match util::load_cloud_auth(&base_url).await {
+        Ok(auth) => auth,
+        Err(e) => {
+            eprintln!("{e}");
             std::process::exit(1);
         }
     };
-
-    if let Some(acc) = auth.get_account_id() {
-        append_error_log(format!("auth: mode=ChatGPT account_id={acc}"));
-    }
-
-    let token = match auth.get_token().await {
-        Ok(t) if !t.is_empty() => t,
-        _ => {
-            eprintln!(
-                "Not signed in. Please run 'codex login' to sign in with ChatGPT, then re-run 'codex cloud'."
-            );
-            std::process::exit(1);
+    match auth {
+        util::CloudAuth::ChatGpt { token, account_id } => {
+            http = http.with_bearer_token(token);
+            if let Some(acc) = account_id {
+                append_error_log(format!("auth: set ChatGPT-Account-Id header: {acc}"));
+                http = http.with_chatgpt_account_id(acc);
+            }
+        }
+        util::CloudAuth::ApiKey(key) => {
+            append_error_log("auth: mode=ApiKey");
+            http = http.with_bearer_token(key);
         }
-    };
-
-    http = http.with_bearer_token(token.clone());
-    if let Some(acc) = auth
-        .get_account_id()
-        .or_else(|| util::extract_chatgpt_account_id(&token))
-    {
-        append_error_log(format!("auth: set ChatGPT-Account-Id header: {acc}"));
-        http = http.with_chatgpt_account_id(acc);
     }
 
     Ok(BackendContext {
         backend: Arc::new(http),
         base_url,
+        use_mock,
     })
 }
 
 async fn run_exec_command(args: crate::cli::ExecCommand) -> anyhow::Result<()> {
     let crate::cli::ExecCommand {
         query,
+        query_file,
         environment,
         attempts,
+        json,
     } = args;
     let ctx = init_backend("codex_cloud_tasks_exec").await?;
-    let prompt = resolve_query_input(query)?;
+    let prompt = match query_file {
+        Some(path) => read_query_file(&path)?,
+        None => resolve_query_input(query)?,
+    };
     let env_id = resolve_environment_id(&ctx, &environment).await?;
     let created = codex_cloud_tasks_client::CloudBackend::create_task(
         &*ctx.backend,
@@ -116,23 +113,71 @@ async fn run_exec_command(args: crate::cli::ExecCommand) -> anyhow::Result<()> {
     )
     .await?;
     let url = util::task_url(&ctx.base_url, &created.id.0);
-    println!("{url}");
+    println!("{}", exec_output(&created.id.0, &url, &env_id, json));
+    Ok(())
+}
+
+fn exec_output(task_id: &str, url: &str, environment: &str, json: bool) -> String {
+    if json {
+        serde_json::json!({
+            "id": task_id,
+            "url": url,
+            "environment": environment,
+        })
+        .to_string()
+    } else {
+        url.to_string()
+    }
+}
+
+async fn run_environments_command(args: crate::cli::EnvironmentsCommand) -> anyhow::Result<()> {
+    let ctx = init_backend("codex_cloud_tasks_environments").await?;
+    let environments = fetch_environments(&ctx).await?;
+    println!("{}", environments_output(&environments, args.json));
     Ok(())
 }
 
+fn environments_output(environments: &[app::EnvironmentRow], json: bool) -> String {
+    if json {
+        serde_json::Value::Array(
+            environments
+                .iter()
+                .map(|row| {
+                    serde_json::json!({
+                        "id": row.id,
+                        "label": row.label,
+                    })
+                })
+                .collect(),
+        )
+        .to_string()
+    } else {
+        environments
+            .iter()
+            .map(|row| match row.label.as_deref() {
+                Some(label) => format!("{}\t{label}", row.id),
+                None => row.id.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+async fn fetch_environments(ctx: &BackendContext) -> anyhow::Result<Vec<app::EnvironmentRow>> {
+    if ctx.use_mock {
+        return Ok(crate::env_detect::mock_environments());
+    }
+    let normalized = util::normalize_base_url(&ctx.base_url);
+    let headers = util::build_chatgpt_headers().await;
+    Ok(crate::env_detect::list_environments(&normalized, &headers).await?)
+}
+
 async fn resolve_environment_id(ctx: &BackendContext, requested: &str) -> anyhow::Result<String> {
     let trimmed = requested.trim();
     if trimmed.is_empty() {
         return Err(anyhow!("environment id must not be empty"));
     }
-    let normalized = util::normalize_base_url(&ctx.base_url);
-    let headers = util::build_chatgpt_headers().await;
-    let environments = crate::env_detect::list_environments(&normalized, &headers).await?;
-    if environments.is_empty() {
-        return Err(anyhow!(
-            "no cloud environments are available for this workspace"
-        ));
-    }
+    let environments = fetch_environments(ctx).await?;
 
     if let Some(row) = environments.iter().find(|row| row.id == trimmed) {
         return Ok(row.id.clone());
@@ -192,6 +237,19 @@ fn resolve_query_input(query_arg: Option<String>) -> anyhow::Result<String> {
     }
 }
 
+fn read_query_file(path: &std::path::Path) -> anyhow::Result<String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("failed to read query from {}: {e}", path.display()))?;
+    let query = contents.trim_end();
+    if query.trim().is_empty() {
+        return Err(anyhow!(
+            "no query provided in {} (file is empty).",
+            path.display()
+        ));
+    }
+    Ok(query.to_string())
+}
+
 fn level_from_status(status: codex_cloud_tasks_client::ApplyStatus) -> app::ApplyResultLevel {
     match status {
         codex_cloud_tasks_client::ApplyStatus::Success => app::ApplyResultLevel::Success,
@@ -226,11 +284,13 @@ fn spawn_preflight(
         let ApplyJob {
             task_id,
             diff_override,
+            target_dir,
         } = job;
         let result = codex_cloud_tasks_client::CloudBackend::apply_task_preflight(
             &*backend,
             task_id.clone(),
             diff_override,
+            target_dir,
         )
         .await;
 
@@ -279,6 +339,8 @@ fn spawn_apply(
     }
 
     app.apply_inflight = true;
+    app.last_apply_job = Some(job.clone());
+    app.apply_error = None;
     let _ = frame_tx.send(Instant::now() + Duration::from_millis(100));
 
     let backend = backend.clone();
@@ -287,11 +349,13 @@ fn spawn_apply(
         let ApplyJob {
             task_id,
             diff_override,
+            target_dir,
         } = job;
         let result = codex_cloud_tasks_client::CloudBackend::apply_task(
             &*backend,
             task_id.clone(),
             diff_override,
+            target_dir,
         )
         .await;
 
@@ -321,9 +385,19 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
     if let Some(command) = cli.command {
         return match command {
             crate::cli::Command::Exec(args) => run_exec_command(args).await,
+            crate::cli::Command::Environments(args) => run_environments_command(args).await,
         };
     }
-    let Cli { .. } = cli;
+    let Cli {
+        auto_refresh,
+        apply_dir,
+        ..
+    } = cli;
+    if let Some(dir) = &apply_dir
+        && !dir.is_dir()
+    {
+        return Err(anyhow!("--dir {} is not a directory", dir.display()));
+    }
 
     // Very minimal logging setup; mirrors other crates' pattern.
     let default_level = "error";
@@ -338,8 +412,21 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
         .try_init();
 
     info!("Launching Cloud Tasks list UI");
-    let BackendContext { backend, .. } = init_backend("codex_cloud_tasks_tui").await?;
+    let BackendContext {
+        backend,
+        base_url,
+        use_mock,
+    } = init_backend("codex_cloud_tasks_tui").await?;
     let backend = backend;
+    // Mock and fixture data must neither be served from nor written to the
+    // real cache.
+    let details_cache = if use_mock {
+        None
+    } else {
+        codex_core::config::find_codex_home()
+            .ok()
+            .map(|home| Arc::new(details_cache::DetailsCache::in_codex_home(&home)))
+    };
 
     // Terminal setup
     use crossterm::ExecutableCommand;
@@ -374,6 +461,7 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
 
     // App state
     let mut app = app::App::new();
+    app.apply_dir = apply_dir;
     // Initial load
     let force_internal = matches!(
         std::env::var("CODEX_CLOUD_TASKS_FORCE_INTERNAL")
@@ -406,18 +494,33 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
     // Channel for non-blocking background loads
     use tokio::sync::mpsc::unbounded_channel;
     let (tx, mut rx) = unbounded_channel::<app::AppEvent>();
+    // Probe the backend so an unreachable or unauthorized backend shows a precise error.
+    {
+        let backend = Arc::clone(&backend);
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            if let Some(status) = app::backend_health_status(&*backend).await {
+                let _ = tx.send(app::AppEvent::BackendUnavailable(status));
+            }
+        });
+    }
     // Kick off the initial load in background
     {
         let backend = Arc::clone(&backend);
         let tx = tx.clone();
+        let generation = app.list_generation;
         tokio::spawn(async move {
             let res = app::load_tasks(&*backend, None).await;
             let _ = tx.send(app::AppEvent::TasksLoaded {
                 env: None,
+                generation,
                 result: res,
             });
         });
     }
+    if let Some(interval) = auto_refresh {
+        app::spawn_auto_refresh(interval, tx.clone());
+    }
     // Fetch environment list in parallel so the header can show friendly names quickly.
     {
         let tx = tx.clone();
@@ -514,6 +617,8 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                 }
                 // Keep spinner pulsing only while loading.
                 if app.refresh_inflight
+                    || app.page_inflight
+                    || app.cancel_inflight
                     || app.details_inflight
                     || app.env_loading
                     || app.apply_preflight_inflight
@@ -532,8 +637,12 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
             maybe_app_event = rx.recv() => {
                 if let Some(ev) = maybe_app_event {
                     match ev {
-                        app::AppEvent::TasksLoaded { env, result } => {
-                            // Only apply results for the current filter to avoid races.
+                        app::AppEvent::TasksLoaded { env, generation, result } => {
+                            // Only apply the latest refresh for the current filter to avoid races.
+                            if generation != app.list_generation {
+                                append_error_log(format!("refresh.drop: generation={generation} current={}", app.list_generation));
+                                continue;
+                            }
                             if env.as_deref() != app.env_filter.as_deref() {
                                 append_error_log(format!(
                                     "refresh.drop: env={} current={}",
@@ -543,15 +652,17 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                 continue;
                             }
                             app.refresh_inflight = false;
+                            // Any page still in flight belongs to the previous list.
+                            app.page_inflight = false;
                             match result {
-                                Ok(tasks) => {
+                                Ok((tasks, next_cursor)) => {
                                     append_error_log(format!(
-                                        "refresh.apply: env={} count={}",
+                                        "refresh.apply: env={} count={} more={}",
                                         env.clone().unwrap_or_else(|| "<all>".to_string()),
-                                        tasks.len()
+                                        tasks.len(),
+                                        next_cursor.is_some()
                                     ));
-                                    app.tasks = tasks;
-                                    if app.selected >= app.tasks.len() { app.selected = app.tasks.len().saturating_sub(1); }
+                                    app.replace_tasks(tasks, next_cursor);
                                     app.status = "Loaded tasks".to_string();
                                 }
                                 Err(e) => {
@@ -562,6 +673,78 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                             needs_redraw = true;
                             let _ = frame_tx.send(Instant::now());
                         }
+                        app::AppEvent::TasksRefreshed { env, generation, result } => {
+                            if generation != app.list_generation || env.as_deref() != app.env_filter.as_deref() {
+                                append_error_log(format!("autorefresh.drop: generation={generation} current={}", app.list_generation));
+                                continue;
+                            }
+                            app.refresh_inflight = false;
+                            // Any page still in flight was dropped with the previous generation.
+                            app.page_inflight = false;
+                            match result {
+                                Ok((tasks, _)) => {
+                                    append_error_log(format!("autorefresh.apply: count={}", tasks.len()));
+                                    app.merge_first_page(tasks);
+                                }
+                                Err(e) => {
+                                    append_error_log(format!("autorefresh load_tasks failed: {e}"));
+                                    app.status = format!("Failed to refresh tasks: {e}");
+                                }
+                            }
+                            needs_redraw = true;
+                            let _ = frame_tx.send(Instant::now());
+                        }
+                        app::AppEvent::BackendUnavailable(status) => {
+                            append_error_log(format!("healthcheck: {status}"));
+                            app.refresh_inflight = false;
+                            app.status = status;
+                            needs_redraw = true;
+                            let _ = frame_tx.send(Instant::now());
+                        }
+                        app::AppEvent::TasksPageLoaded { env, generation, result } => {
+                            // Drop pages for a list that has since been refreshed or re-filtered.
+                            if generation != app.list_generation || env.as_deref() != app.env_filter.as_deref() {
+                                append_error_log(format!("page.drop: generation={generation} current={}", app.list_generation));
+                                continue;
+                            }
+                            app.page_inflight = false;
+                            match result {
+                                Ok((tasks, next_cursor)) => {
+                                    append_error_log(format!(
+                                        "page.apply: count={} more={}",
+                                        tasks.len(),
+                                        next_cursor.is_some()
+                                    ));
+                                    app.append_page(tasks, next_cursor);
+                                    app.status = "Loaded tasks".to_string();
+                                }
+                                Err(e) => {
+                                    append_error_log(format!("page load_tasks_page failed: {e}"));
+                                    app.status = format!("Failed to load more tasks: {e}");
+                                }
+                            }
+                            needs_redraw = true;
+                            let _ = frame_tx.send(Instant::now());
+                        }
+                        app::AppEvent::AutoRefreshDue => {
+                            // Leave the list alone while the user is working in a modal.
+                            if app.auto_refresh_paused() {
+                                continue;
+                            }
+                            app.refresh_inflight = true;
+                            app.list_generation = app.list_generation.saturating_add(1);
+                            app.in_flight.clear();
+                            let backend = Arc::clone(&backend);
+                            let tx = tx.clone();
+                            let env_sel = app.env_filter.clone();
+                            let generation = app.list_generation;
+                            tokio::spawn(async move {
+                                let res = app::load_tasks(&*backend, env_sel.as_deref()).await;
+                                let _ = tx.send(app::AppEvent::TasksRefreshed { env: env_sel, generation, result: res });
+                            });
+                            needs_redraw = true;
+                            let _ = frame_tx.send(Instant::now());
+                        }
                         app::AppEvent::NewTaskSubmitted(result) => {
                             match result {
                                 Ok(created) => {
@@ -576,9 +759,10 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                     let backend = Arc::clone(&backend);
                                     let tx = tx.clone();
                                     let env_sel = app.env_filter.clone();
+                                    let generation = app.list_generation;
                                     tokio::spawn(async move {
                                         let res = app::load_tasks(&*backend, env_sel.as_deref()).await;
-                                        let _ = tx.send(app::AppEvent::TasksLoaded { env: env_sel, result: res });
+                                        let _ = tx.send(app::AppEvent::TasksLoaded { env: env_sel, generation, result: res });
                                     });
                                     let _ = frame_tx.send(Instant::now());
                                 }
@@ -616,7 +800,8 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                     app.env_last_loaded = Some(std::time::Instant::now());
                                 }
                                 Err(e) => {
-                                    app.env_error = Some(e.to_string());
+                                    append_error_log(format!("env: list_environments failed: {e}"));
+                                    app.env_error = Some(e.hint());
                                 }
                             }
                             needs_redraw = true;
@@ -649,9 +834,10 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                         let backend = Arc::clone(&backend);
                                         let tx = tx.clone();
                                         let env_sel = app.env_filter.clone();
+                                        let generation = app.list_generation;
                                         tokio::spawn(async move {
                                             let res = app::load_tasks(&*backend, env_sel.as_deref()).await;
-                                            let _ = tx.send(app::AppEvent::TasksLoaded { env: env_sel, result: res });
+                                            let _ = tx.send(app::AppEvent::TasksLoaded { env: env_sel, generation, result: res });
                                         });
                                     }
                                     // Proactively fetch environments to resolve a friendly name for the header.
@@ -875,22 +1061,57 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                         app.apply_modal = None;
                                         app.diff_overlay = None;
                                         // Refresh tasks after successful apply
+                                        app.list_generation = app.list_generation.saturating_add(1);
                                         let backend = Arc::clone(&backend);
                                         let tx = tx.clone();
                                         let env_sel = app.env_filter.clone();
+                                        let generation = app.list_generation;
                                         tokio::spawn(async move {
                                             let res = app::load_tasks(&*backend, env_sel.as_deref()).await;
-                                            let _ = tx.send(app::AppEvent::TasksLoaded { env: env_sel, result: res });
+                                            let _ = tx.send(app::AppEvent::TasksLoaded { env: env_sel, generation, result: res });
                                         });
                                     }
                                 }
                                 Err(e) => {
                                     append_error_log(format!("apply_task failed for {}: {e}", id.0));
-                                    app.status = format!("Apply failed: {e}");
+                                    app.record_apply_failure(e);
                                 }
                             }
                             needs_redraw = true;
                         }
+                        app::AppEvent::CancelFinished { id, title, result } => {
+                            app.cancel_inflight = false;
+                            app.cancel_modal = None;
+                            let refresh = match result {
+                                Ok(()) => {
+                                    app.status = format!("Cancelled '{title}'");
+                                    true
+                                }
+                                Err(codex_cloud_tasks_client::CloudTaskError::AlreadyFinished(_)) => {
+                                    app.status = format!("'{title}' had already finished; nothing to cancel");
+                                    true
+                                }
+                                Err(e) => {
+                                    append_error_log(format!("cancel_task failed for {}: {e}", id.0));
+                                    app.status = format!("Cancel failed: {e}");
+                                    false
+                                }
+                            };
+                            if refresh {
+                                app.refresh_inflight = true;
+                                app.list_generation = app.list_generation.saturating_add(1);
+                                let backend = Arc::clone(&backend);
+                                let tx = tx.clone();
+                                let env_sel = app.env_filter.clone();
+                                let generation = app.list_generation;
+                                tokio::spawn(async move {
+                                    let res = app::load_tasks(&*backend, env_sel.as_deref()).await;
+                                    let _ = tx.send(app::AppEvent::TasksLoaded { env: env_sel, generation, result: res });
+                                });
+                            }
+                            needs_redraw = true;
+                            let _ = frame_tx.send(Instant::now());
+                        }
                     }
                 }
                 // Render immediately after processing app events.
@@ -935,6 +1156,9 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                 app.apply_modal = None;
                                 app.status = "Apply canceled".to_string();
                                 needs_redraw = true;
+                            } else if app.cancel_modal.is_some() {
+                                app.cancel_modal = None;
+                                needs_redraw = true;
                             } else if app.new_task.is_some() {
                                 app.new_task = None;
                                 app.status = "Canceled new task".to_string();
@@ -942,6 +1166,9 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                             } else if app.diff_overlay.is_some() {
                                 app.diff_overlay = None;
                                 needs_redraw = true;
+                            } else if app.search.is_some() {
+                                app.clear_search();
+                                needs_redraw = true;
                             } else {
                                 break 0;
                             }
@@ -1122,7 +1349,37 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                             }
                         }
                         // If a diff overlay is open, handle its keys first.
-                        if app.apply_modal.is_some() {
+                        if app.cancel_modal.is_some() {
+                            // Cancel confirmation modal: y/Enter cancels the task, n/Esc keeps it running
+                            match key.code {
+                                KeyCode::Char('y') | KeyCode::Enter => {
+                                    if !app.cancel_inflight
+                                        && let Some(m) = app.cancel_modal.clone()
+                                    {
+                                        app.cancel_inflight = true;
+                                        app.status = format!("Cancelling '{}'…", m.title);
+                                        let backend = Arc::clone(&backend);
+                                        let tx = tx.clone();
+                                        tokio::spawn(async move {
+                                            let result = codex_cloud_tasks_client::CloudBackend::cancel_task(&*backend, m.task_id.clone()).await;
+                                            let _ = tx.send(app::AppEvent::CancelFinished { id: m.task_id, title: m.title, result });
+                                        });
+                                        let _ = frame_tx.send(Instant::now());
+                                    }
+                                    needs_redraw = true;
+                                }
+                                KeyCode::Esc
+                                | KeyCode::Char('n')
+                                | KeyCode::Char('q')
+                                | KeyCode::Char('Q') => {
+                                    if !app.cancel_inflight {
+                                        app.cancel_modal = None;
+                                        needs_redraw = true;
+                                    }
+                                }
+                                _ => {}
+                            }
+                        } else if app.apply_modal.is_some() {
                             // Simple apply confirmation modal: y apply, p preflight, n/Esc cancel
                             match key.code {
                                 KeyCode::Char('y') => {
@@ -1131,6 +1388,7 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                         let job = ApplyJob {
                                             task_id: m.task_id.clone(),
                                             diff_override: m.diff_override.clone(),
+                                            target_dir: app.apply_dir.clone(),
                                         };
                                         if spawn_apply(&mut app, &backend, &tx, &frame_tx, job) {
                                             app.status = format!("Applying '{title}'...");
@@ -1138,12 +1396,23 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                         needs_redraw = true;
                                     }
                                 }
+                                KeyCode::Char('r') | KeyCode::Char('R') => {
+                                    // Re-issue the last failed apply with the same job.
+                                    if let Some(job) = app.take_retry_job() {
+                                        let title = app.apply_modal.as_ref().map(|m| m.title.clone()).unwrap_or_default();
+                                        if spawn_apply(&mut app, &backend, &tx, &frame_tx, job) {
+                                            app.status = format!("Retrying apply of '{title}'...");
+                                        }
+                                        needs_redraw = true;
+                                    }
+                                }
                                 KeyCode::Char('p') => {
                                     if let Some(m) = app.apply_modal.take() {
                                         let title = m.title.clone();
                                         let job = ApplyJob {
                                             task_id: m.task_id.clone(),
                                             diff_override: m.diff_override.clone(),
+                                            target_dir: app.apply_dir.clone(),
                                         };
                                         if spawn_preflight(&mut app, &backend, &tx, &frame_tx, title.clone(), job) {
                                             app.apply_modal = Some(app::ApplyModalState {
@@ -1201,6 +1470,7 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                             let job = ApplyJob {
                                                 task_id: task_id.clone(),
                                                 diff_override: diff_override.clone(),
+                                                target_dir: app.apply_dir.clone(),
                                             };
                                             if spawn_preflight(&mut app, &backend, &tx, &frame_tx, title.clone(), job) {
                                                 app.apply_modal = Some(app::ApplyModalState {
@@ -1220,6 +1490,25 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                         needs_redraw = true;
                                     }
                                 }
+                                KeyCode::Char('s') => {
+                                    if let Some(ov) = app.diff_overlay.as_ref() {
+                                        let diff = ov.current_attempt().and_then(|attempt| attempt.diff_raw.clone());
+                                        app.status = match diff {
+                                            Some(diff) => {
+                                                let dir = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+                                                match app::export_diff(&dir, &ov.task_id, &diff) {
+                                                    Ok(path) => format!("Saved diff to {}", path.display()),
+                                                    Err(e) => {
+                                                        append_error_log(format!("export diff failed for {}: {e}", ov.task_id.0));
+                                                        format!("Failed to save diff: {e}")
+                                                    }
+                                                }
+                                            }
+                                            None => "No diff available to save.".to_string(),
+                                        };
+                                        needs_redraw = true;
+                                    }
+                                }
                                 KeyCode::Tab => {
                                     cycle_attempt(1);
                                 }
@@ -1294,6 +1583,10 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                     if let Some(ov) = &mut app.diff_overlay { let step = ov.sd.state.viewport_h.saturating_sub(1) as i16; ov.sd.page_by(-step); }
                                     needs_redraw = true;
                                 }
+                                KeyCode::Char('w') => {
+                                    if let Some(ov) = &mut app.diff_overlay { ov.sd.toggle_wrap(); }
+                                    needs_redraw = true;
+                                }
                                 KeyCode::Home => { if let Some(ov) = &mut app.diff_overlay { ov.sd.to_top(); } needs_redraw = true; }
                                 KeyCode::End  => { if let Some(ov) = &mut app.diff_overlay { ov.sd.to_bottom(); } needs_redraw = true; }
                                 _ => {}
@@ -1362,28 +1655,103 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                         let backend = Arc::clone(&backend);
                                         let tx = tx.clone();
                                         let env_sel = app.env_filter.clone();
+                                        let generation = app.list_generation;
                                         tokio::spawn(async move {
                                             let res = app::load_tasks(&*backend, env_sel.as_deref()).await;
-                                            let _ = tx.send(app::AppEvent::TasksLoaded { env: env_sel, result: res });
+                                            let _ = tx.send(app::AppEvent::TasksLoaded { env: env_sel, generation, result: res });
                                         });
                                     }
                                 }
                                 _ => {}
                             }
+                        } else if app.search.as_ref().is_some_and(|s| s.editing) {
+                            // Search box: type to filter titles; Enter keeps the filter, Esc clears it.
+                            match key.code {
+                                KeyCode::Esc => {
+                                    app.clear_search();
+                                    needs_redraw = true;
+                                }
+                                KeyCode::Enter => {
+                                    if let Some(search) = app.search.as_mut() { search.editing = false; }
+                                    needs_redraw = true;
+                                }
+                                KeyCode::Backspace => {
+                                    if let Some(mut query) = app.search.as_ref().map(|s| s.query.clone()) {
+                                        query.pop();
+                                        app.set_search_query(query);
+                                    }
+                                    needs_redraw = true;
+                                }
+                                KeyCode::Down => { app.next(); needs_redraw = true; }
+                                KeyCode::Up => { app.prev(); needs_redraw = true; }
+                                KeyCode::Char(ch) if !key.modifiers.contains(KeyModifiers::CONTROL) && !key.modifiers.contains(KeyModifiers::ALT) => {
+                                    if let Some(mut query) = app.search.as_ref().map(|s| s.query.clone()) {
+                                        query.push(ch);
+                                        app.set_search_query(query);
+                                    }
+                                    needs_redraw = true;
+                                }
+                                _ => {}
+                            }
                         } else {
                             // Base list view keys
                             match key.code {
+                                // Esc drops an active search before it quits.
+                                KeyCode::Esc if app.search.is_some() => {
+                                    app.clear_search();
+                                    needs_redraw = true;
+                                }
                                 KeyCode::Char('q') | KeyCode::Esc => {
                                     break 0;
                                 }
+                                KeyCode::Char('/') => {
+                                    app.open_search();
+                                    needs_redraw = true;
+                                }
                                 KeyCode::Down | KeyCode::Char('j') => {
                                     app.next();
                                     needs_redraw = true;
+                                    // Infinite scroll: fetch the next page as the selection nears the end.
+                                    if app.should_load_more() {
+                                        app.page_inflight = true;
+                                        app.status = "Loading more tasks…".to_string();
+                                        let backend = Arc::clone(&backend);
+                                        let tx = tx.clone();
+                                        let env_sel = app.env_filter.clone();
+                                        let cursor = app.next_cursor.clone();
+                                        let generation = app.list_generation;
+                                        tokio::spawn(async move {
+                                            let res = app::load_tasks_page(&*backend, env_sel.as_deref(), cursor).await;
+                                            let _ = tx.send(app::AppEvent::TasksPageLoaded { env: env_sel, generation, result: res });
+                                        });
+                                    }
                                 }
                                 KeyCode::Up | KeyCode::Char('k') => {
                                     app.prev();
                                     needs_redraw = true;
                                 }
+                                KeyCode::Char('x') => {
+                                    app.open_cancel_modal();
+                                    needs_redraw = true;
+                                }
+                                KeyCode::Char('c') => {
+                                    if let Some(task) = app.selected_task() {
+                                        let url = util::task_url(&base_url, &task.id.0);
+                                        app.status = match util::copy_to_clipboard(&url) {
+                                            Ok(()) => format!("Copied task URL: {url}"),
+                                            Err(e) => {
+                                                append_error_log(format!("clipboard: copy failed: {e}"));
+                                                format!("Clipboard unavailable; task URL: {url}")
+                                            }
+                                        };
+                                        needs_redraw = true;
+                                    }
+                                }
+                                KeyCode::Char('f') | KeyCode::Char('F') => {
+                                    // Filter client-side; no refetch needed.
+                                    app.cycle_status_filter();
+                                    needs_redraw = true;
+                                }
                                 // Ensure 'r' does not refresh tasks when the env modal is open.
                                 KeyCode::Char('r') | KeyCode::Char('R') => {
                                     if app.env_modal.is_some() { break 0; }
@@ -1401,9 +1769,10 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                     let backend = Arc::clone(&backend);
                                     let tx = tx.clone();
                                     let env_sel = app.env_filter.clone();
+                                    let generation = app.list_generation;
                                     tokio::spawn(async move {
                                         let res = app::load_tasks(&*backend, env_sel.as_deref()).await;
-                                        let _ = tx.send(app::AppEvent::TasksLoaded { env: env_sel, result: res });
+                                        let _ = tx.send(app::AppEvent::TasksLoaded { env: env_sel, generation, result: res });
                                     });
                                 }
                                 KeyCode::Char('o') | KeyCode::Char('O') => {
@@ -1429,7 +1798,7 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                     needs_redraw = true;
                                 }
                                 KeyCode::Enter => {
-                                    if let Some(task) = app.tasks.get(app.selected).cloned() {
+                                    if let Some(task) = app.selected_task().cloned() {
                                         app.status = format!("Loading details for {title}…", title = task.title);
                                         app.details_inflight = true;
                                         // Open empty overlay immediately; content arrives via events
@@ -1440,9 +1809,30 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                         );
                                         app.diff_overlay = Some(overlay);
                                         needs_redraw = true;
-                                        // Spawn background details load (diff first, then messages fallback)
                                         let id = task.id.clone();
                                         let title = task.title.clone();
+                                        let updated_at = task.updated_at;
+                                        // Serve a fresh cached copy without touching the network.
+                                        if let Some(cached) = details_cache.as_ref().and_then(|cache| cache.load(&id, updated_at)) {
+                                            if let Some(diff) = cached.diff {
+                                                let _ = tx.send(app::AppEvent::DetailsDiffLoaded { id: id.clone(), title: title.clone(), diff });
+                                            }
+                                            let _ = tx.send(app::AppEvent::DetailsMessagesLoaded {
+                                                id,
+                                                title,
+                                                messages: cached.messages,
+                                                prompt: cached.prompt,
+                                                turn_id: cached.turn_id,
+                                                sibling_turn_ids: cached.sibling_turn_ids,
+                                                attempt_placement: cached.attempt_placement,
+                                                attempt_status: cached.attempt_status,
+                                            });
+                                            continue;
+                                        }
+                                        // The conversation task caches the details once the diff
+                                        // fetch has succeeded; a failed diff fetch is never cached.
+                                        let (diff_done_tx, diff_done_rx) = tokio::sync::oneshot::channel::<Option<String>>();
+                                        // Spawn background details load (diff first, then messages fallback)
                                         {
                                             let backend = Arc::clone(&backend);
                                             let tx = tx.clone();
@@ -1451,9 +1841,11 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                             tokio::spawn(async move {
                                                 match codex_cloud_tasks_client::CloudBackend::get_task_diff(&*backend, diff_id.clone()).await {
                                                     Ok(Some(diff)) => {
+                                                        let _ = diff_done_tx.send(Some(diff.clone()));
                                                         let _ = tx.send(app::AppEvent::DetailsDiffLoaded { id: diff_id, title: diff_title, diff });
                                                     }
                                                     Ok(None) => {
+                                                        let _ = diff_done_tx.send(None);
                                                         match codex_cloud_tasks_client::CloudBackend::get_task_text(&*backend, diff_id.clone()).await {
                                                             Ok(text) => {
                                                                 let evt = app::AppEvent::DetailsMessagesLoaded {
@@ -1503,19 +1895,24 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                             let tx = tx.clone();
                                             let msg_id = id;
                                             let msg_title = title;
+                                            let cache = details_cache.clone();
                                             tokio::spawn(async move {
                                                 if let Ok(text) = codex_cloud_tasks_client::CloudBackend::get_task_text(&*backend, msg_id.clone()).await {
                                                     let evt = app::AppEvent::DetailsMessagesLoaded {
-                                                        id: msg_id,
+                                                        id: msg_id.clone(),
                                                         title: msg_title,
-                                                        messages: text.messages,
-                                                        prompt: text.prompt,
-                                                        turn_id: text.turn_id,
-                                                        sibling_turn_ids: text.sibling_turn_ids,
+                                                        messages: text.messages.clone(),
+                                                        prompt: text.prompt.clone(),
+                                                        turn_id: text.turn_id.clone(),
+                                                        sibling_turn_ids: text.sibling_turn_ids.clone(),
                                                         attempt_placement: text.attempt_placement,
                                                         attempt_status: text.attempt_status,
                                                     };
                                                     let _ = tx.send(evt);
+                                                    // The sender is dropped without a value when the diff fetch failed.
+                                                    if let (Some(cache), Ok(diff)) = (&cache, diff_done_rx.await) {
+                                                        cache.store(&msg_id, updated_at, diff.as_deref(), &text);
+                                                    }
                                                 }
                                             });
                                         }
@@ -1530,7 +1927,7 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                         continue;
                                     }
 
-                                    if let Some(task) = app.tasks.get(app.selected).cloned() {
+                                    if let Some(task) = app.selected_task().cloned() {
                                         match codex_cloud_tasks_client::CloudBackend::get_task_diff(&*backend, task.id.clone()).await {
                                             Ok(Some(diff)) => {
                                                 let diff_override = Some(diff.clone());
@@ -1539,6 +1936,7 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                                 let job = ApplyJob {
                                                     task_id: task_id.clone(),
                                                     diff_override: diff_override.clone(),
+                                                    target_dir: app.apply_dir.clone(),
                                                 };
                                                 if spawn_preflight(
                                                     &mut app,
@@ -1712,6 +2110,9 @@ fn pretty_lines_from_error(raw: &str) -> Vec<String> {
 
 #[cfg(test)]
 mod tests {
+    use super::environments_output;
+    use super::exec_output;
+    use super::read_query_file;
     use codex_tui::ComposerAction;
     use codex_tui::ComposerInput;
     use crossterm::event::KeyCode;
@@ -1748,4 +2149,69 @@ mod tests {
             .join("");
         assert!(footer.contains("⌃O env"));
     }
+
+    #[test]
+    fn read_query_file_trims_trailing_whitespace() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("prompt.md");
+        std::fs::write(&path, "  Fix the flaky test\nin core.\n\n  ").expect("write");
+        assert_eq!(
+            read_query_file(&path).expect("read"),
+            "  Fix the flaky test\nin core."
+        );
+    }
+
+    #[test]
+    fn read_query_file_errors_on_missing_or_empty_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let missing = dir.path().join("missing.md");
+        let err = read_query_file(&missing).expect_err("missing file should fail");
+        assert!(
+            err.to_string().starts_with("failed to read query from"),
+            "unexpected error: {err}"
+        );
+
+        let empty = dir.path().join("empty.md");
+        std::fs::write(&empty, " \n\t\n").expect("write");
+        let err = read_query_file(&empty).expect_err("empty file should fail");
+        assert_eq!(
+            err.to_string(),
+            format!("no query provided in {} (file is empty).", empty.display())
+        );
+    }
+
+    #[test]
+    fn exec_output_json_includes_created_task() {
+        let url = "https://chatgpt.com/codex/tasks/task_123";
+        let output = exec_output("task_123", url, "env-1", true);
+        let value: serde_json::Value = serde_json::from_str(&output).expect("valid json");
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "id": "task_123",
+                "url": url,
+                "environment": "env-1",
+            })
+        );
+        assert_eq!(exec_output("task_123", url, "env-1", false), url);
+    }
+
+    #[test]
+    fn environments_output_lists_mock_environments() {
+        let environments = crate::env_detect::mock_environments();
+        assert_eq!(
+            environments_output(&environments, false),
+            "env-A\tEnv A\nenv-B\tEnv B"
+        );
+
+        let value: serde_json::Value =
+            serde_json::from_str(&environments_output(&environments, true)).expect("valid json");
+        assert_eq!(
+            value,
+            serde_json::json!([
+                { "id": "env-A", "label": "Env A" },
+                { "id": "env-B", "label": "Env B" },
+            ])
+        );
+    }
 }
@@ -1,3 +1,8 @@
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
 use unicode_width::UnicodeWidthChar;
 use unicode_width::UnicodeWidthStr;
 
@@ -22,15 +27,31 @@ impl ScrollViewState {
 ///
 /// Owns raw lines, caches wrapped lines for a given width, and maintains
 /// a small scroll state that is clamped whenever geometry shrinks.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct ScrollableDiff {
     raw: Vec<String>,
     wrapped: Vec<String>,
     wrapped_src_idx: Vec<usize>,
     wrap_cols: Option<u16>,
+    wrap: bool,
+    diff_gutter: bool,
     pub state: ScrollViewState,
 }
 
+impl Default for ScrollableDiff {
+    fn default() -> Self {
+        Self {
+            raw: Vec::new(),
+            wrapped: Vec::new(),
+            wrapped_src_idx: Vec::new(),
+            wrap_cols: None,
+            wrap: true,
+            diff_gutter: false,
+            state: ScrollViewState::default(),
+        }
+    }
+}
+
 impl ScrollableDiff {
     pub fn new() -> Self {
         Self::default()
@@ -56,6 +77,27 @@ impl ScrollableDiff {
         self.state.clamp();
     }
 
+    /// Whether long lines are soft-wrapped to the width; when off, each raw
+    /// line is one row and is cut off at the right edge.
+    pub fn wrap(&self) -> bool {
+        self.wrap
+    }
+
+    /// Toggle soft-wrapping. Rows are rebuilt on the next `set_width`.
+    pub fn toggle_wrap(&mut self) {
+        self.wrap = !self.wrap;
+        self.wrap_cols = None;
+    }
+
+    /// Treat content as a unified diff: continuation rows of wrapped `+`, `-`
+    /// and context lines repeat the gutter marker.
+    pub fn set_diff_gutter(&mut self, diff_gutter: bool) {
+        if self.diff_gutter != diff_gutter {
+            self.diff_gutter = diff_gutter;
+            self.wrap_cols = None;
+        }
+    }
+
     /// Update viewport height and clamp scroll if needed.
     pub fn set_viewport(&mut self, height: u16) {
         self.state.viewport_h = height;
@@ -75,6 +117,20 @@ impl ScrollableDiff {
         self.raw.get(idx).map(String::as_str).unwrap_or("")
     }
 
+    /// Wrapped rows styled as a unified diff. Each row is styled by the raw line
+    /// it came from, so continuation rows match their first row; with `color`
+    /// off only bold/dim modifiers are used.
+    pub fn styled_diff_lines(&self, color: bool) -> Vec<Line<'static>> {
+        self.wrapped
+            .iter()
+            .zip(self.wrapped_src_idx.iter())
+            .map(|(row, &src_idx)| {
+                let style = diff_line_style(self.raw_line_at(src_idx), color);
+                Line::from(vec![Span::styled(row.clone(), style)])
+            })
+            .collect()
+    }
+
     /// Scroll by a signed delta; clamps to content.
     pub fn scroll_by(&mut self, delta: i16) {
         let s = self.state.scroll as i32 + delta as i32;
@@ -112,8 +168,10 @@ impl ScrollableDiff {
     }
 
     fn rewrap(&mut self, width: u16) {
-        if width == 0 {
-            self.wrapped = self.raw.clone();
+        if width == 0 || !self.wrap {
+            // Normalize tabs for width accounting (MVP: 4 spaces).
+            self.wrapped = self.raw.iter().map(|l| l.replace('\t', "    ")).collect();
+            self.wrapped_src_idx = (0..self.raw.len()).collect();
             self.state.content_h = self.wrapped.len() as u16;
             return;
         }
@@ -121,56 +179,208 @@ impl ScrollableDiff {
         let mut out: Vec<String> = Vec::new();
         let mut out_idx: Vec<usize> = Vec::new();
         for (raw_idx, raw) in self.raw.iter().enumerate() {
-            // Normalize tabs for width accounting (MVP: 4 spaces).
-            let raw = raw.replace('\t', "    ");
-            if raw.is_empty() {
-                out.push(String::new());
-                out_idx.push(raw_idx);
-                continue;
-            }
-            let mut line = String::new();
-            let mut line_cols = 0usize;
-            let mut last_soft_idx: Option<usize> = None; // last whitespace or punctuation break
-            for (_i, ch) in raw.char_indices() {
-                if ch == '\n' {
-                    out.push(std::mem::take(&mut line));
-                    out_idx.push(raw_idx);
-                    line_cols = 0;
-                    last_soft_idx = None;
-                    continue;
-                }
-                let w = UnicodeWidthChar::width(ch).unwrap_or(0);
-                if line_cols.saturating_add(w) > max_cols {
-                    if let Some(split) = last_soft_idx {
-                        let (prefix, rest) = line.split_at(split);
-                        out.push(prefix.trim_end().to_string());
-                        out_idx.push(raw_idx);
-                        line = rest.trim_start().to_string();
-                        last_soft_idx = None;
-                        // retry add current ch now that line may be shorter
-                    } else if !line.is_empty() {
-                        out.push(std::mem::take(&mut line));
-                        out_idx.push(raw_idx);
-                    }
-                }
-                if ch.is_whitespace()
-                    || matches!(
-                        ch,
-                        ',' | ';' | '.' | ':' | ')' | ']' | '}' | '|' | '/' | '?' | '!' | '-' | '_'
-                    )
-                {
-                    last_soft_idx = Some(line.len());
-                }
-                line.push(ch);
-                line_cols = UnicodeWidthStr::width(line.as_str());
-            }
-            if !line.is_empty() {
-                out.push(line);
-                out_idx.push(raw_idx);
-            }
+            let marker = if self.diff_gutter {
+                diff_gutter_marker(raw)
+            } else {
+                None
+            };
+            let rows = wrap_line(raw, max_cols, marker);
+            out_idx.extend(std::iter::repeat_n(raw_idx, rows.len()));
+            out.extend(rows);
         }
         self.wrapped = out;
         self.wrapped_src_idx = out_idx;
         self.state.content_h = self.wrapped.len() as u16;
     }
 }
+
+/// Whether diff coloring is enabled; honors the `NO_COLOR` convention.
+pub fn diff_colors_enabled() -> bool {
+    std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
+
+fn diff_line_style(raw: &str, color: bool) -> Style {
+    let style = Style::default();
+    if raw.starts_with("@@") {
+        let style = style.add_modifier(Modifier::BOLD);
+        return if color {
+            style.fg(Color::Magenta)
+        } else {
+            style
+        };
+    }
+    if raw.starts_with("+++") || raw.starts_with("---") {
+        return style.add_modifier(Modifier::DIM);
+    }
+    match raw.chars().next() {
+        Some('+') if color => style.fg(Color::Green),
+        Some('-') if color => style.fg(Color::Red),
+        _ => style,
+    }
+}
+
+/// Gutter marker repeated on continuation rows of a wrapped diff line, so
+/// added/removed lines keep their marker (and styling) on every visual row.
+fn diff_gutter_marker(raw: &str) -> Option<char> {
+    if raw.starts_with("+++") || raw.starts_with("---") {
+        return None;
+    }
+    raw.chars().next().filter(|c| matches!(c, '+' | '-' | ' '))
+}
+
+/// Soft-wrap one raw line into visual rows of at most `max_cols` columns,
+/// preferring to break after whitespace or punctuation. When `marker` is set,
+/// each continuation row starts with it.
+pub(crate) fn wrap_line(raw: &str, max_cols: usize, marker: Option<char>) -> Vec<String> {
+    // Normalize tabs for width accounting (MVP: 4 spaces).
+    let raw = raw.replace('\t', "    ");
+    if raw.is_empty() {
+        return vec![String::new()];
+    }
+    let continuation = || marker.map(String::from).unwrap_or_default();
+    let mut out: Vec<String> = Vec::new();
+    let mut line = String::new();
+    // Bytes at the start of `line` that belong to the gutter marker.
+    let mut start_len = 0usize;
+    let mut line_cols = 0usize;
+    let mut last_soft_idx: Option<usize> = None; // last whitespace or punctuation break
+    for ch in raw.chars() {
+        if ch == '\n' {
+            out.push(std::mem::replace(&mut line, continuation()));
+            start_len = line.len();
+            line_cols = UnicodeWidthStr::width(line.as_str());
+            last_soft_idx = None;
+            continue;
+        }
+        let w = UnicodeWidthChar::width(ch).unwrap_or(0);
+        if line_cols.saturating_add(w) > max_cols {
+            if let Some(split) = last_soft_idx.filter(|&split| split > start_len) {
+                let (prefix, rest) = line.split_at(split);
+                out.push(prefix.trim_end().to_string());
+                let rest = rest.trim_start().to_string();
+                line = continuation() + &rest;
+                start_len = line.len() - rest.len();
+                last_soft_idx = None;
+                // retry add current ch now that line may be shorter
+            } else if line.len() > start_len {
+                out.push(std::mem::replace(&mut line, continuation()));
+                start_len = line.len();
+                last_soft_idx = None;
+            }
+        }
+        if ch.is_whitespace()
+            || matches!(
+                ch,
+                ',' | ';' | '.' | ':' | ')' | ']' | '}' | '|' | '/' | '?' | '!' | '-' | '_'
+            )
+        {
+            last_soft_idx = Some(line.len());
+        }
+        line.push(ch);
+        line_cols = UnicodeWidthStr::width(line.as_str());
+    }
+    if line.len() > start_len || out.is_empty() {
+        out.push(line);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_line_wraps_to_expected_rows() {
+        let rows = wrap_line(&"x".repeat(25), 10, None);
+        assert_eq!(rows, vec!["x".repeat(10), "x".repeat(10), "x".repeat(5)]);
+    }
+
+    #[test]
+    fn continuation_rows_keep_diff_marker() {
+        let raw = format!("+{}", "a".repeat(20));
+        let rows = wrap_line(&raw, 10, diff_gutter_marker(&raw));
+        assert_eq!(
+            rows,
+            vec![
+                format!("+{}", "a".repeat(9)),
+                format!("+{}", "a".repeat(9)),
+                "+aa".to_string(),
+            ]
+        );
+    }
+
+    fn diff_with_width(lines: &[&str], width: u16) -> ScrollableDiff {
+        let mut sd = ScrollableDiff::new();
+        sd.set_diff_gutter(true);
+        sd.set_content(lines.iter().map(ToString::to_string).collect());
+        sd.set_width(width);
+        sd
+    }
+
+    #[test]
+    fn diff_rows_are_styled_by_prefix() {
+        let sd = diff_with_width(
+            &[
+                "--- a/x",
+                "+++ b/x",
+                "@@ -1 +1 @@ fn main",
+                " keep",
+                "-old",
+                "+new line that wraps",
+            ],
+            12,
+        );
+
+        let styled =
+            |text: &str, style: Style| Line::from(vec![Span::styled(text.to_string(), style)]);
+        let dim = Style::default().add_modifier(Modifier::DIM);
+        let hunk = Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD);
+        let green = Style::default().fg(Color::Green);
+        assert_eq!(
+            sd.styled_diff_lines(true),
+            vec![
+                styled("--- a/x", dim),
+                styled("+++ b/x", dim),
+                styled("@@ -1 +1 @@", hunk),
+                styled("fn main", hunk),
+                styled(" keep", Style::default()),
+                styled("-old", Style::default().fg(Color::Red)),
+                styled("+new line", green),
+                styled("+that wraps", green),
+            ]
+        );
+    }
+
+    #[test]
+    fn no_color_keeps_only_modifiers() {
+        let sd = diff_with_width(&["@@ -1 +1 @@", "-old", "+new"], 40);
+
+        let styled =
+            |text: &str, style: Style| Line::from(vec![Span::styled(text.to_string(), style)]);
+        assert_eq!(
+            sd.styled_diff_lines(false),
+            vec![
+                styled("@@ -1 +1 @@", Style::default().add_modifier(Modifier::BOLD)),
+                styled("-old", Style::default()),
+                styled("+new", Style::default()),
+            ]
+        );
+    }
+
+    #[test]
+    fn toggling_wrap_recomputes_rows() {
+        let mut sd = ScrollableDiff::new();
+        sd.set_content(vec!["word ".repeat(10), "short".to_string()]);
+        sd.set_width(20);
+        assert_eq!(sd.state.content_h, 4);
+        assert_eq!(sd.wrapped_src_indices(), &[0, 0, 0, 1]);
+
+        sd.toggle_wrap();
+        sd.set_width(20);
+        assert!(!sd.wrap());
+        assert_eq!(sd.state.content_h, 2);
+        assert_eq!(sd.wrapped_src_indices(), &[0, 1]);
+    }
+}
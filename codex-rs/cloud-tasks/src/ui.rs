@@ -20,10 +20,12 @@ use std::time::Instant;
 
 use crate::app::App;
 use crate::app::AttemptView;
+use crate::app::StatusFilter;
 use chrono::Local;
 use chrono::Utc;
 use codex_cloud_tasks_client::AttemptStatus;
 use codex_cloud_tasks_client::TaskStatus;
+use codex_common::fuzzy_match::fuzzy_indices;
 use codex_tui::render_markdown_text;
 
 pub fn draw(frame: &mut Frame, app: &mut App) {
@@ -55,6 +57,9 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
     if app.apply_modal.is_some() {
         draw_apply_modal(frame, area, app);
     }
+    if app.cancel_modal.is_some() {
+        draw_cancel_modal(frame, area, app);
+    }
 }
 
 // ===== Overlay helpers (geometry + styling) =====
@@ -175,7 +180,12 @@ pub fn draw_new_task_page(frame: &mut Frame, area: Rect, app: &mut App) {
 }
 
 fn draw_list(frame: &mut Frame, area: Rect, app: &mut App) {
-    let items: Vec<ListItem> = app.tasks.iter().map(|t| render_task_item(app, t)).collect();
+    let visible = app.visible_tasks();
+    let visible_len = visible.len();
+    let items: Vec<ListItem> = visible
+        .into_iter()
+        .map(|t| render_task_item(app, t))
+        .collect();
 
     // Selection reflects the actual task index (no artificial spacer item).
     let mut state = ListState::default().with_selected(Some(app.selected));
@@ -183,6 +193,7 @@ fn draw_list(frame: &mut Frame, area: Rect, app: &mut App) {
     let dim_bg = app.env_modal.is_some()
         || app.apply_modal.is_some()
         || app.best_of_modal.is_some()
+        || app.cancel_modal.is_some()
         || app.diff_overlay.is_some();
     // Dynamic title includes current environment filter
     let suffix_span = if let Some(ref id) = app.env_filter {
@@ -196,15 +207,30 @@ fn draw_list(frame: &mut Frame, area: Rect, app: &mut App) {
     } else {
         " • All".dim()
     };
+    let filter_span = match app.status_filter {
+        StatusFilter::All => "".into(),
+        filter => format!(" • {}", filter.label()).cyan(),
+    };
+    let search_span = match &app.search {
+        Some(search) if search.editing => format!(" • /{}▏", search.query).cyan(),
+        Some(search) => format!(" • /{}", search.query).cyan(),
+        None => "".into(),
+    };
     // Percent scrolled based on selection position in the list (0% at top, 100% at bottom).
-    let percent_span = if app.tasks.len() <= 1 {
+    let percent_span = if visible_len <= 1 {
         "  • 0%".dim()
     } else {
-        let p = ((app.selected as f32) / ((app.tasks.len() - 1) as f32) * 100.0).round() as i32;
+        let p = ((app.selected as f32) / ((visible_len - 1) as f32) * 100.0).round() as i32;
         format!("  • {}%", p.clamp(0, 100)).dim()
     };
     let title_line = {
-        let base = Line::from(vec!["Cloud Tasks".into(), suffix_span, percent_span]);
+        let base = Line::from(vec![
+            "Cloud Tasks".into(),
+            suffix_span,
+            filter_span,
+            search_span,
+            percent_span,
+        ]);
         if dim_bg {
             base.style(Style::default().add_modifier(Modifier::DIM))
         } else {
@@ -251,7 +277,16 @@ fn draw_footer(frame: &mut Frame, area: Rect, app: &mut App) {
         } else {
             help.push("a".dim());
             help.push(": Apply  ".dim());
+            help.push("s".dim());
+            help.push(": Save diff  ".dim());
         }
+        help.push("w".dim());
+        let wrap_label = if ov.sd.wrap() {
+            ": Unwrap  "
+        } else {
+            ": Wrap  "
+        };
+        help.push(wrap_label.dim());
         if ov.attempt_count() > 1 {
             help.push("Tab".dim());
             help.push(": Next attempt  ".dim());
@@ -261,6 +296,14 @@ fn draw_footer(frame: &mut Frame, area: Rect, app: &mut App) {
     } else {
         help.push("a".dim());
         help.push(": Apply  ".dim());
+        help.push("x".dim());
+        help.push(": Cancel task  ".dim());
+        help.push("c".dim());
+        help.push(": Copy URL  ".dim());
+        help.push("f".dim());
+        help.push(": Filter  ".dim());
+        help.push("/".dim());
+        help.push(": Search  ".dim());
     }
     help.push("o : Set Env  ".dim());
     if app.new_task.is_some() {
@@ -287,6 +330,8 @@ fn draw_footer(frame: &mut Frame, area: Rect, app: &mut App) {
     frame.render_widget(para, top[0]);
     // Right side: spinner or clear the spinner area if idle to prevent stale glyphs.
     if app.refresh_inflight
+        || app.page_inflight
+        || app.cancel_inflight
         || app.details_inflight
         || app.env_loading
         || app.apply_preflight_inflight
@@ -433,11 +478,11 @@ fn draw_diff_overlay(frame: &mut Frame, area: Rect, app: &mut App) {
         .map(|o| matches!(o.current_view, crate::app::DetailView::Diff))
         .unwrap_or(false);
     let styled_lines: Vec<Line<'static>> = if is_diff_view {
-        let raw = app.diff_overlay.as_ref().map(|o| o.sd.wrapped_lines());
-        raw.unwrap_or(&[])
-            .iter()
-            .map(|l| style_diff_line(l))
-            .collect()
+        let color = crate::scrollable_diff::diff_colors_enabled();
+        app.diff_overlay
+            .as_ref()
+            .map(|o| o.sd.styled_diff_lines(color))
+            .unwrap_or_default()
     } else {
         app.diff_overlay
             .as_ref()
@@ -467,26 +512,65 @@ fn draw_diff_overlay(frame: &mut Frame, area: Rect, app: &mut App) {
     }
 }
 
-pub fn draw_apply_modal(frame: &mut Frame, area: Rect, app: &mut App) {
+pub fn draw_cancel_modal(frame: &mut Frame, area: Rect, app: &mut App) {
     use ratatui::widgets::Wrap;
     let inner = overlay_outer(area);
-    let title = Line::from("Apply Changes?".magenta().bold());
+    let title = Line::from("Cancel Task?".magenta().bold());
     let block = overlay_block().title(title);
     frame.render_widget(Clear, inner);
     frame.render_widget(block.clone(), inner);
     let content = overlay_content(inner);
 
-    if let Some(m) = &app.apply_modal {
-        // Header
+    if let Some(m) = &app.cancel_modal {
         let header = Paragraph::new(Line::from(
-            format!("Apply '{}' ?", m.title).magenta().bold(),
+            format!("Cancel '{}' ?", m.title).magenta().bold(),
         ))
         .wrap(Wrap { trim: true });
-        // Footer instructions
         let footer =
-            Paragraph::new(Line::from("Press Y to apply, P to preflight, N to cancel.").dim())
+            Paragraph::new(Line::from("Press Y to cancel the task, N to keep it running.").dim())
                 .wrap(Wrap { trim: true });
 
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ])
+            .split(content);
+
+        frame.render_widget(header, rows[0]);
+        if app.cancel_inflight {
+            draw_centered_spinner(frame, rows[1], &mut app.spinner_start, "Cancelling…");
+        }
+        frame.render_widget(footer, rows[2]);
+    }
+}
+
+pub fn draw_apply_modal(frame: &mut Frame, area: Rect, app: &mut App) {
+    use ratatui::widgets::Wrap;
+    let inner = overlay_outer(area);
+    let title = Line::from("Apply Changes?".magenta().bold());
+    let block = overlay_block().title(title);
+    frame.render_widget(Clear, inner);
+    frame.render_widget(block.clone(), inner);
+    let content = overlay_content(inner);
+
+    if let Some(m) = &app.apply_modal {
+        // Header
+        let prompt = match &app.apply_dir {
+            Some(dir) => format!("Apply '{}' to {} ?", m.title, dir.display()),
+            None => format!("Apply '{}' ?", m.title),
+        };
+        let header = Paragraph::new(Line::from(prompt.magenta().bold())).wrap(Wrap { trim: true });
+        // Footer instructions
+        let instructions = if app.apply_error.is_some() {
+            "Press R to retry, P to preflight, N to cancel."
+        } else {
+            "Press Y to apply, P to preflight, N to cancel."
+        };
+        let footer = Paragraph::new(Line::from(instructions).dim()).wrap(Wrap { trim: true });
+
         // Split into header/body/footer
         let rows = Layout::default()
             .direction(Direction::Vertical)
@@ -748,42 +832,7 @@ fn attempt_status_span(status: AttemptStatus) -> Option<ratatui::text::Span<'sta
     }
 }
 
-fn style_diff_line(raw: &str) -> Line<'static> {
-    use ratatui::style::Color;
-    use ratatui::style::Modifier;
-    use ratatui::style::Style;
-    use ratatui::text::Span;
-
-    if raw.starts_with("@@") {
-        return Line::from(vec![Span::styled(
-            raw.to_string(),
-            Style::default()
-                .fg(Color::Magenta)
-                .add_modifier(Modifier::BOLD),
-        )]);
-    }
-    if raw.starts_with("+++") || raw.starts_with("---") {
-        return Line::from(vec![Span::styled(
-            raw.to_string(),
-            Style::default().add_modifier(Modifier::DIM),
-        )]);
-    }
-    if raw.starts_with('+') {
-        return Line::from(vec![Span::styled(
-            raw.to_string(),
-            Style::default().fg(Color::Green),
-        )]);
-    }
-    if raw.starts_with('-') {
-        return Line::from(vec![Span::styled(
-            raw.to_string(),
-            Style::default().fg(Color::Red),
-        )]);
-    }
-    Line::from(vec![Span::raw(raw.to_string())])
-}
-
-fn render_task_item(_app: &App, t: &codex_cloud_tasks_client::TaskSummary) -> ListItem<'static> {
+fn render_task_item(app: &App, t: &codex_cloud_tasks_client::TaskSummary) -> ListItem<'static> {
     let status = match t.status {
         TaskStatus::Ready => "READY".green(),
         TaskStatus::Pending => "PENDING".magenta(),
@@ -791,13 +840,25 @@ fn render_task_item(_app: &App, t: &codex_cloud_tasks_client::TaskSummary) -> Li
         TaskStatus::Error => "ERROR".red(),
     };
 
-    // Title line: [STATUS] Title
-    let title = Line::from(vec![
-        "[".into(),
-        status,
-        "] ".into(),
-        t.title.clone().into(),
-    ]);
+    // Title line: [STATUS] Title, with search matches in bold
+    let mut title_spans: Vec<ratatui::text::Span> = vec!["[".into(), status, "] ".into()];
+    let match_indices = app
+        .search
+        .as_ref()
+        .and_then(|search| fuzzy_indices(&t.title, &search.query))
+        .unwrap_or_default();
+    if match_indices.is_empty() {
+        title_spans.push(t.title.clone().into());
+    } else {
+        for (idx, ch) in t.title.chars().enumerate() {
+            if match_indices.binary_search(&idx).is_ok() {
+                title_spans.push(ch.to_string().bold());
+            } else {
+                title_spans.push(ch.to_string().into());
+            }
+        }
+    }
+    let title = Line::from(title_spans);
 
     // Meta line: environment label and relative time (dim)
     let mut meta: Vec<ratatui::text::Span> = Vec::new();
@@ -943,11 +1004,12 @@ pub fn draw_env_modal(frame: &mut Frame, area: Rect, app: &mut App) {
         ])
         .split(content);
 
-    // Subheader with usage hints (dim cyan)
-    let subheader = Paragraph::new(Line::from(
-        "Type to search, Enter select, Esc cancel".cyan().dim(),
-    ))
-    .wrap(Wrap { trim: true });
+    // Subheader with usage hints (dim cyan), or why loading failed (red)
+    let subheader_line = match &app.env_error {
+        Some(err) => Line::from(err.clone().red()),
+        None => Line::from("Type to search, Enter select, Esc cancel".cyan().dim()),
+    };
+    let subheader = Paragraph::new(subheader_line).wrap(Wrap { trim: true });
     frame.render_widget(subheader, rows[0]);
 
     let query = app
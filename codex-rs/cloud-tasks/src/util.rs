@@ -5,6 +5,8 @@ use reqwest::header::HeaderMap;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_login::AuthManager;
+use codex_login::AuthMode;
+use codex_login::CodexAuth;
 
 pub fn set_user_agent_suffix(suffix: &str) {
     if let Ok(mut guard) = codex_core::default_client::USER_AGENT_SUFFIX.lock() {
@@ -70,11 +72,103 @@ pub async fn load_auth_manager() -> Option<AuthManager> {
     ))
 }
 
-/// Build headers for ChatGPT-backed requests: `User-Agent`, optional `Authorization`,
-/// and optional `ChatGPT-Account-Id`.
+/// Whether `base_url` points at a codex-api deployment rather than the ChatGPT backend.
+pub fn uses_codex_api(base_url: &str) -> bool {
+    !normalize_base_url(base_url).contains("/backend-api")
+}
+
+/// Credentials for the Cloud Tasks backend.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CloudAuth {
+    /// ChatGPT sign-in, used with the ChatGPT backend.
+    ChatGpt {
+        token: String,
+        account_id: Option<String>,
+    },
+    /// API key, used with codex-api deployments.
+    ApiKey(String),
+}
+
+impl CloudAuth {
+    /// `Authorization` plus, for ChatGPT sign-in, `ChatGPT-Account-Id`.
+    pub fn headers(&self) -> HeaderMap {
+        use reqwest::header::AUTHORIZATION;
+        use reqwest::header::HeaderName;
+        use reqwest::header::HeaderValue;
+
+        let mut headers = HeaderMap::new();
+        let (token, account_id) = match self {
+            Self::ChatGpt { token, account_id } => (token, account_id.as_ref()),
+            Self::ApiKey(key) => (key, None),
+        };
+        if let Ok(hv) = HeaderValue::from_str(&format!("Bearer {token}")) {
+            headers.insert(AUTHORIZATION, hv);
+        }
+        if let Some(acc) = account_id
+            && let Ok(name) = HeaderName::from_bytes(b"ChatGPT-Account-Id")
+            && let Ok(hv) = HeaderValue::from_str(acc)
+        {
+            headers.insert(name, hv);
+        }
+        headers
+    }
+}
+
+/// Pick credentials for `base_url`. codex-api deployments take an API key, from
+/// `api_key` (normally `CODEX_API_KEY`) or else an API-key login in auth storage;
+/// the ChatGPT backend needs a ChatGPT sign-in.
+pub async fn cloud_auth_for(
+    base_url: &str,
+    api_key: Option<String>,
+    auth: Option<CodexAuth>,
+) -> anyhow::Result<CloudAuth> {
+    if uses_codex_api(base_url) {
+        if let Some(key) = api_key {
+            return Ok(CloudAuth::ApiKey(key));
+        }
+        if let Some(auth) = auth.filter(|auth| auth.mode == AuthMode::ApiKey)
+            && let Ok(key) = auth.get_token().await
+            && !key.is_empty()
+        {
+            return Ok(CloudAuth::ApiKey(key));
+        }
+        return Err(anyhow::anyhow!(
+            "{base_url} is a codex-api deployment and needs an API key. Set {} or run 'codex login --with-api-key', then re-run 'codex cloud'.",
+            codex_login::CODEX_API_KEY_ENV_VAR
+        ));
+    }
+
+    if let Some(auth) = auth.filter(|auth| auth.mode == AuthMode::ChatGPT)
+        && let Ok(token) = auth.get_token().await
+        && !token.is_empty()
+    {
+        let account_id = auth
+            .get_account_id()
+            .or_else(|| extract_chatgpt_account_id(&token));
+        return Ok(CloudAuth::ChatGpt { token, account_id });
+    }
+    Err(anyhow::anyhow!(
+        "Not signed in. Please run 'codex login' to sign in with ChatGPT, then re-run 'codex cloud'."
+    ))
+}
+
+/// Credentials for the configured Cloud Tasks base URL, from the environment and auth storage.
+pub async fn load_cloud_auth(base_url: &str) -> anyhow::Result<CloudAuth> {
+    let auth = load_auth_manager()
+        .await
+        .as_ref()
+        .and_then(AuthManager::auth);
+    cloud_auth_for(
+        base_url,
+        codex_core::auth::read_codex_api_key_from_env(),
+        auth,
+    )
+    .await
+}
+
+/// Build headers for Cloud Tasks requests: `User-Agent` plus the `Authorization` and
+/// optional `ChatGPT-Account-Id` headers for the configured base URL, when signed in.
 pub async fn build_chatgpt_headers() -> HeaderMap {
-    use reqwest::header::AUTHORIZATION;
-    use reqwest::header::HeaderName;
     use reqwest::header::HeaderValue;
     use reqwest::header::USER_AGENT;
 
@@ -85,23 +179,10 @@ pub async fn build_chatgpt_headers() -> HeaderMap {
         USER_AGENT,
         HeaderValue::from_str(&ua).unwrap_or(HeaderValue::from_static("codex-cli")),
     );
-    if let Some(am) = load_auth_manager().await
-        && let Some(auth) = am.auth()
-        && let Ok(tok) = auth.get_token().await
-        && !tok.is_empty()
-    {
-        let v = format!("Bearer {tok}");
-        if let Ok(hv) = HeaderValue::from_str(&v) {
-            headers.insert(AUTHORIZATION, hv);
-        }
-        if let Some(acc) = auth
-            .get_account_id()
-            .or_else(|| extract_chatgpt_account_id(&tok))
-            && let Ok(name) = HeaderName::from_bytes(b"ChatGPT-Account-Id")
-            && let Ok(hv) = HeaderValue::from_str(&acc)
-        {
-            headers.insert(name, hv);
-        }
+    let base_url = std::env::var("CODEX_CLOUD_TASKS_BASE_URL")
+        .unwrap_or_else(|_| "https://chatgpt.com/backend-api".to_string());
+    if let Ok(auth) = load_cloud_auth(&base_url).await {
+        headers.extend(auth.headers());
     }
     headers
 }
@@ -120,3 +201,119 @@ pub fn task_url(base_url: &str, task_id: &str) -> String {
     }
     format!("{normalized}/codex/tasks/{task_id}")
 }
+
+/// Copy `text` to the system clipboard. Fails when no clipboard is reachable,
+/// e.g. in a headless SSH session.
+#[cfg(not(target_os = "android"))]
+pub fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+    clipboard
+        .set_text(text.to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "android")]
+pub fn copy_to_clipboard(_text: &str) -> Result<(), String> {
+    Err("clipboard is not supported on this platform".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn task_url_for_supported_base_urls() {
+        let urls = [
+            "https://chatgpt.com/backend-api",
+            "https://chatgpt.com/",
+            "http://localhost:8080/api/codex",
+            "https://example.test/codex",
+            "https://example.test",
+        ]
+        .map(|base| task_url(base, "task_123"));
+
+        assert_eq!(
+            urls,
+            [
+                "https://chatgpt.com/codex/tasks/task_123",
+                "https://chatgpt.com/codex/tasks/task_123",
+                "http://localhost:8080/codex/tasks/task_123",
+                "https://example.test/codex/tasks/task_123",
+                "https://example.test/codex/tasks/task_123",
+            ]
+            .map(str::to_string)
+        );
+    }
+
+    #[tokio::test]
+    async fn codex_api_uses_api_key_without_account_header() {
+        let auth = cloud_auth_for(
+            "http://localhost:8080/api/codex",
+            Some("sk-test".to_string()),
+            Some(CodexAuth::create_dummy_chatgpt_auth_for_testing()),
+        )
+        .await
+        .expect("api key auth");
+        assert_eq!(auth, CloudAuth::ApiKey("sk-test".to_string()));
+
+        let headers = auth.headers();
+        assert_eq!(
+            headers
+                .get(reqwest::header::AUTHORIZATION)
+                .and_then(|v| v.to_str().ok()),
+            Some("Bearer sk-test")
+        );
+        assert!(headers.get("ChatGPT-Account-Id").is_none());
+    }
+
+    #[tokio::test]
+    async fn codex_api_falls_back_to_stored_api_key() {
+        let auth = cloud_auth_for(
+            "http://localhost:8080/api/codex",
+            None,
+            Some(CodexAuth::from_api_key("sk-stored")),
+        )
+        .await
+        .expect("api key auth");
+        assert_eq!(auth, CloudAuth::ApiKey("sk-stored".to_string()));
+    }
+
+    #[tokio::test]
+    async fn codex_api_without_api_key_is_an_error() {
+        let err = cloud_auth_for(
+            "http://localhost:8080/api/codex",
+            None,
+            Some(CodexAuth::create_dummy_chatgpt_auth_for_testing()),
+        )
+        .await
+        .expect_err("chatgpt sign-in is not enough for codex-api");
+        assert!(
+            err.to_string().contains("needs an API key"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn chatgpt_backend_uses_account_header() {
+        let auth = cloud_auth_for(
+            "https://chatgpt.com/backend-api",
+            Some("sk-test".to_string()),
+            Some(CodexAuth::create_dummy_chatgpt_auth_for_testing()),
+        )
+        .await
+        .expect("chatgpt auth");
+        assert_eq!(
+            auth,
+            CloudAuth::ChatGpt {
+                token: "Access Token".to_string(),
+                account_id: Some("account_id".to_string()),
+            }
+        );
+        assert_eq!(
+            auth.headers()
+                .get("ChatGPT-Account-Id")
+                .and_then(|v| v.to_str().ok()),
+            Some("account_id")
+        );
+    }
+}
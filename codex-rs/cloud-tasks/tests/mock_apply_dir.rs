@@ -0,0 +1,56 @@
+use codex_cloud_tasks_client::ApplyStatus;
+use codex_cloud_tasks_client::CloudBackend;
+use codex_cloud_tasks_client::CloudTaskError;
+use codex_cloud_tasks_client::MockClient;
+use codex_cloud_tasks_client::TaskId;
+
+#[tokio::test]
+async fn mock_backend_applies_to_target_dir() {
+    let client = MockClient;
+    let dir = tempfile::tempdir().unwrap();
+
+    let preflight = CloudBackend::apply_task_preflight(
+        &client,
+        TaskId("T-1000".to_string()),
+        None,
+        Some(dir.path().to_path_buf()),
+    )
+    .await
+    .unwrap();
+    let outcome = CloudBackend::apply_task(
+        &client,
+        TaskId("T-1000".to_string()),
+        None,
+        Some(dir.path().to_path_buf()),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        (preflight.message, outcome.status, outcome.message),
+        (
+            format!(
+                "Preflight passed for task T-1000 against {} (mock)",
+                dir.path().display()
+            ),
+            ApplyStatus::Success,
+            format!("Applied task T-1000 to {} (mock)", dir.path().display()),
+        )
+    );
+}
+
+#[tokio::test]
+async fn mock_backend_rejects_missing_target_dir() {
+    let client = MockClient;
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing");
+
+    let err = CloudBackend::apply_task(&client, TaskId("T-1000".to_string()), None, Some(missing))
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(err, CloudTaskError::Io(_)),
+        "unexpected error: {err:?}"
+    );
+}
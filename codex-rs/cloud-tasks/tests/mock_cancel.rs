@@ -0,0 +1,27 @@
+use codex_cloud_tasks_client::CloudBackend;
+use codex_cloud_tasks_client::CloudTaskError;
+use codex_cloud_tasks_client::MockClient;
+use codex_cloud_tasks_client::TaskId;
+
+#[tokio::test]
+async fn mock_backend_cancels_pending_task() {
+    let client = MockClient;
+
+    CloudBackend::cancel_task(&client, TaskId("T-1001".to_string()))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn mock_backend_reports_finished_task() {
+    let client = MockClient;
+
+    let err = CloudBackend::cancel_task(&client, TaskId("T-1000".to_string()))
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(&err, CloudTaskError::AlreadyFinished(id) if id == "T-1000"),
+        "unexpected error: {err:?}"
+    );
+}
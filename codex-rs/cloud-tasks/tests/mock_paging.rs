@@ -0,0 +1,37 @@
+use codex_cloud_tasks_client::CloudBackend;
+use codex_cloud_tasks_client::Cursor;
+use codex_cloud_tasks_client::MockClient;
+
+#[tokio::test]
+async fn mock_backend_pages_through_tasks() {
+    let client = MockClient;
+
+    let (first, cursor) = CloudBackend::list_tasks_page(&client, None, None, 2)
+        .await
+        .unwrap();
+    assert_eq!(
+        first.iter().map(|t| t.id.0.as_str()).collect::<Vec<_>>(),
+        vec!["T-1000", "T-1001"]
+    );
+    assert_eq!(cursor, Some(Cursor("2".to_string())));
+
+    let (second, cursor) = CloudBackend::list_tasks_page(&client, None, cursor, 2)
+        .await
+        .unwrap();
+    assert_eq!(
+        second.iter().map(|t| t.id.0.as_str()).collect::<Vec<_>>(),
+        vec!["T-1002"]
+    );
+    assert_eq!(cursor, None);
+}
+
+#[tokio::test]
+async fn mock_backend_single_page_has_no_cursor() {
+    let client = MockClient;
+
+    let (tasks, cursor) = CloudBackend::list_tasks_page(&client, Some("env-B"), None, 10)
+        .await
+        .unwrap();
+
+    assert_eq!((tasks.len(), cursor), (2, None));
+}
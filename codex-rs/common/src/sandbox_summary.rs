@@ -9,6 +9,7 @@ pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
             network_access,
             exclude_tmpdir_env_var,
             exclude_slash_tmp,
+            allow_hosts: _,
         } => {
             let mut summary = "workspace-write".to_string();
 
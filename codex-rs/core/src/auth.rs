@@ -21,7 +21,9 @@ use codex_protocol::config_types::ForcedLoginMethod;
 pub use crate::auth::storage::AuthCredentialsStoreMode;
 pub use crate::auth::storage::AuthDotJson;
 use crate::auth::storage::AuthStorageBackend;
+pub use crate::auth::storage::KeyringMigration;
 use crate::auth::storage::create_auth_storage;
+pub use crate::auth::storage::migrate_file_to_keyring;
 use crate::config::Config;
 use crate::default_client::CodexHttpClient;
 use crate::error::RefreshTokenFailedError;
@@ -212,6 +212,68 @@ impl AuthStorageBackend for KeyringAuthStorage {
     }
 }
 
+/// Result of [`migrate_file_to_keyring`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyringMigration {
+    /// There was no auth file to migrate.
+    NoFile,
+    /// The keyring already held credentials for this `CODEX_HOME`; it was left
+    /// untouched, as was the file.
+    AlreadyPresent,
+    /// The credentials were saved to the keyring.
+    Migrated,
+}
+
+/// Move the credentials in an `auth.json` file into `store` under `service`,
+/// using the same key keyring-backed storage uses for the file's `CODEX_HOME`.
+/// When `delete_file` is set the file is removed once the keyring holds them.
+///
+/// Running it again is a no-op: credentials already in the keyring are never
+/// overwritten, and a missing file means there is nothing to do.
+pub fn migrate_file_to_keyring(
+    store: &dyn KeyringStore,
+    json_path: &Path,
+    service: &str,
+    delete_file: bool,
+) -> std::io::Result<KeyringMigration> {
+    let codex_home = json_path.parent().unwrap_or_else(|| Path::new("."));
+    let file_storage = FileAuthStorage::new(codex_home.to_path_buf());
+    let auth = match file_storage.try_read_auth_json(json_path) {
+        Ok(auth) => auth,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(KeyringMigration::NoFile);
+        }
+        Err(err) => return Err(err),
+    };
+
+    let key = compute_store_key(codex_home)?;
+    let existing = store.load(service, &key).map_err(|error| {
+        std::io::Error::other(format!(
+            "failed to load CLI auth from keyring: {}",
+            error.message()
+        ))
+    })?;
+    if existing.is_some() {
+        return Ok(KeyringMigration::AlreadyPresent);
+    }
+
+    let serialized = serde_json::to_string(&auth).map_err(std::io::Error::other)?;
+    store.save(service, &key, &serialized).map_err(|error| {
+        std::io::Error::other(format!(
+            "failed to write CLI auth to keyring: {}",
+            error.message()
+        ))
+    })?;
+    if delete_file {
+        match std::fs::remove_file(json_path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(KeyringMigration::Migrated)
+}
+
 #[derive(Clone, Debug)]
 struct AutoAuthStorage {
     keyring_storage: Arc<KeyringAuthStorage>,
@@ -259,7 +321,7 @@ pub(super) fn create_auth_storage(
     codex_home: PathBuf,
     mode: AuthCredentialsStoreMode,
 ) -> Arc<dyn AuthStorageBackend> {
-    let keyring_store: Arc<dyn KeyringStore> = Arc::new(DefaultKeyringStore);
+    let keyring_store: Arc<dyn KeyringStore> = Arc::new(DefaultKeyringStore::default());
     create_auth_storage_with_keyring_store(codex_home, mode, keyring_store)
 }
 
@@ -459,6 +521,69 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn migrate_file_to_keyring_moves_auth_into_keyring() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let mock_keyring = MockKeyringStore::default();
+        let auth = auth_with_prefix("migrate");
+        FileAuthStorage::new(codex_home.path().to_path_buf()).save(&auth)?;
+        let auth_file = get_auth_file(codex_home.path());
+
+        let outcome = migrate_file_to_keyring(&mock_keyring, &auth_file, KEYRING_SERVICE, true)?;
+
+        assert_eq!(outcome, KeyringMigration::Migrated);
+        let key = compute_store_key(codex_home.path())?;
+        assert_keyring_saved_auth_and_removed_fallback(
+            &mock_keyring,
+            &key,
+            codex_home.path(),
+            &auth,
+        );
+        let storage =
+            KeyringAuthStorage::new(codex_home.path().to_path_buf(), Arc::new(mock_keyring));
+        assert_eq!(storage.load()?, Some(auth));
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_file_to_keyring_without_file_is_noop() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let mock_keyring = MockKeyringStore::default();
+        let auth_file = get_auth_file(codex_home.path());
+
+        let outcome = migrate_file_to_keyring(&mock_keyring, &auth_file, KEYRING_SERVICE, true)?;
+
+        assert_eq!(outcome, KeyringMigration::NoFile);
+        let key = compute_store_key(codex_home.path())?;
+        assert!(!mock_keyring.contains(&key));
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_file_to_keyring_keeps_existing_keyring_entry() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let mock_keyring = MockKeyringStore::default();
+        let existing = auth_with_prefix("keyring");
+        seed_keyring_with_auth(
+            &mock_keyring,
+            || compute_store_key(codex_home.path()),
+            &existing,
+        )?;
+        FileAuthStorage::new(codex_home.path().to_path_buf()).save(&auth_with_prefix("file"))?;
+        let auth_file = get_auth_file(codex_home.path());
+
+        let outcome = migrate_file_to_keyring(&mock_keyring, &auth_file, KEYRING_SERVICE, true)?;
+
+        assert_eq!(outcome, KeyringMigration::AlreadyPresent);
+        assert!(auth_file.exists(), "file is kept when nothing was migrated");
+        let key = compute_store_key(codex_home.path())?;
+        assert_eq!(
+            mock_keyring.saved_value(&key),
+            Some(serde_json::to_string(&existing)?)
+        );
+        Ok(())
+    }
+
     #[test]
     fn keyring_auth_storage_compute_store_key_for_home_directory() -> anyhow::Result<()> {
         let codex_home = PathBuf::from("~/.codex");
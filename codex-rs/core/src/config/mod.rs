@@ -809,11 +809,13 @@ impl ConfigToml {
                     network_access,
                     exclude_tmpdir_env_var,
                     exclude_slash_tmp,
+                    allow_hosts,
                 }) => SandboxPolicy::WorkspaceWrite {
                     writable_roots: writable_roots.clone(),
                     network_access: *network_access,
                     exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
                     exclude_slash_tmp: *exclude_slash_tmp,
+                    allow_hosts: allow_hosts.clone(),
                 },
                 None => SandboxPolicy::new_workspace_write_policy(),
             },
@@ -1516,6 +1518,7 @@ exclude_slash_tmp = true
                         network_access: false,
                         exclude_tmpdir_env_var: true,
                         exclude_slash_tmp: true,
+                        allow_hosts: Vec::new(),
                     },
                     forced_auto_mode_downgraded_on_windows: false,
                 }
@@ -1561,6 +1564,7 @@ trust_level = "trusted"
                         network_access: false,
                         exclude_tmpdir_env_var: true,
                         exclude_slash_tmp: true,
+                        allow_hosts: Vec::new(),
                     },
                     forced_auto_mode_downgraded_on_windows: false,
                 }
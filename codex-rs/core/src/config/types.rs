@@ -398,6 +398,9 @@ pub struct SandboxWorkspaceWrite {
     pub exclude_tmpdir_env_var: bool,
     #[serde(default)]
     pub exclude_slash_tmp: bool,
+    /// Hosts reachable while `network_access` is off (Windows sandbox only).
+    #[serde(default)]
+    pub allow_hosts: Vec<String>,
 }
 
 impl From<SandboxWorkspaceWrite> for codex_app_server_protocol::SandboxSettings {
@@ -407,6 +410,7 @@ impl From<SandboxWorkspaceWrite> for codex_app_server_protocol::SandboxSettings
             network_access: Some(sandbox_workspace_write.network_access),
             exclude_tmpdir_env_var: Some(sandbox_workspace_write.exclude_tmpdir_env_var),
             exclude_slash_tmp: Some(sandbox_workspace_write.exclude_slash_tmp),
+            allow_hosts: sandbox_workspace_write.allow_hosts,
         }
     }
 }
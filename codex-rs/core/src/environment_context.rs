@@ -205,6 +205,7 @@ mod tests {
             network_access,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            allow_hosts: Vec::new(),
         }
     }
 
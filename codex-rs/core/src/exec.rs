@@ -172,6 +172,7 @@ async fn exec_windows_sandbox(
     sandbox_policy: &SandboxPolicy,
 ) -> Result<RawExecToolCallOutput> {
     use crate::config::find_codex_home;
+    use codex_windows_sandbox::SandboxRunOptions;
     use codex_windows_sandbox::run_windows_sandbox_capture;
 
     let ExecParams {
@@ -187,7 +188,6 @@ async fn exec_windows_sandbox(
             "failed to serialize Windows sandbox policy: {err}"
         )))
     })?;
-    let sandbox_cwd = cwd.clone();
     let codex_home = find_codex_home().map_err(|err| {
         CodexErr::Io(io::Error::other(format!(
             "windows sandbox: failed to resolve codex_home: {err}"
@@ -196,12 +196,14 @@ async fn exec_windows_sandbox(
     let spawn_res = tokio::task::spawn_blocking(move || {
         run_windows_sandbox_capture(
             policy_str.as_str(),
-            &sandbox_cwd,
             codex_home.as_ref(),
             command,
             &cwd,
             env,
-            timeout_ms,
+            SandboxRunOptions {
+                timeout_ms,
+                ..Default::default()
+            },
         )
     })
     .await;
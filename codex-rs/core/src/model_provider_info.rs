@@ -308,6 +308,10 @@ pub const DEFAULT_OLLAMA_PORT: u16 = 11434;
 pub const LMSTUDIO_OSS_PROVIDER_ID: &str = "lmstudio";
 pub const OLLAMA_OSS_PROVIDER_ID: &str = "ollama";
 
+/// Overrides the base URL of the built-in LM Studio provider, e.g.
+/// `http://192.168.1.10:1234/v1`. Takes precedence over `CODEX_OSS_BASE_URL`.
+pub const LMSTUDIO_BASE_URL_ENV_VAR: &str = "CODEX_LMSTUDIO_BASE_URL";
+
 /// Built-in default provider list.
 pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
     use ModelProviderInfo as P;
@@ -361,10 +365,7 @@ pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
             OLLAMA_OSS_PROVIDER_ID,
             create_oss_provider(DEFAULT_OLLAMA_PORT, WireApi::Chat),
         ),
-        (
-            LMSTUDIO_OSS_PROVIDER_ID,
-            create_oss_provider(DEFAULT_LMSTUDIO_PORT, WireApi::Responses),
-        ),
+        (LMSTUDIO_OSS_PROVIDER_ID, create_lmstudio_provider()),
     ]
     .into_iter()
     .map(|(k, v)| (k.to_string(), v))
@@ -391,6 +392,16 @@ pub fn create_oss_provider(default_provider_port: u16, wire_api: WireApi) -> Mod
     create_oss_provider_with_base_url(&codex_oss_base_url, wire_api)
 }
 
+fn create_lmstudio_provider() -> ModelProviderInfo {
+    match std::env::var(LMSTUDIO_BASE_URL_ENV_VAR)
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+    {
+        Some(url) => create_oss_provider_with_base_url(url.trim(), WireApi::Responses),
+        None => create_oss_provider(DEFAULT_LMSTUDIO_PORT, WireApi::Responses),
+    }
+}
+
 pub fn create_oss_provider_with_base_url(base_url: &str, wire_api: WireApi) -> ModelProviderInfo {
     ModelProviderInfo {
         name: "gpt-oss".into(),
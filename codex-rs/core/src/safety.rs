@@ -214,6 +214,7 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            allow_hosts: Vec::new(),
         };
 
         assert!(is_write_patch_constrained_to_writable_paths(
@@ -235,6 +236,7 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            allow_hosts: Vec::new(),
         };
         assert!(is_write_patch_constrained_to_writable_paths(
             &add_outside,
@@ -186,6 +186,7 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            allow_hosts: Vec::new(),
         };
 
         let args = create_seatbelt_command_args(
@@ -262,6 +263,7 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            allow_hosts: Vec::new(),
         };
 
         let args = create_seatbelt_command_args(
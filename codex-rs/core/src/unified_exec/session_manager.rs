@@ -429,6 +429,7 @@ impl UnifiedExecSessionManager {
             env.cwd.as_path(),
             &env.env,
             &env.arg0,
+            codex_utils_pty::TerminalSize::default(),
         )
         .await
         .map_err(|err| UnifiedExecError::create_session(err.to_string()))?;
@@ -560,6 +560,7 @@ async fn apply_patch_cli_rejects_path_traversal_outside_workspace(
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        allow_hosts: Vec::new(),
     };
     harness
         .submit_with_policy(
@@ -615,6 +616,7 @@ async fn apply_patch_cli_rejects_move_path_traversal_outside_workspace(
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        allow_hosts: Vec::new(),
     };
     harness
         .submit_with_policy("attempt move traversal via apply_patch", sandbox_policy)
@@ -463,6 +463,7 @@ async fn apply_patch_cli_rejects_path_traversal_outside_workspace() -> Result<()
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        allow_hosts: Vec::new(),
     };
     harness
         .submit_with_policy(
@@ -512,6 +513,7 @@ async fn apply_patch_cli_rejects_move_path_traversal_outside_workspace() -> Resu
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        allow_hosts: Vec::new(),
     };
     harness
         .submit_with_policy("attempt move traversal via apply_patch", sandbox_policy)
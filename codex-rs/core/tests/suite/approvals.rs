@@ -628,6 +628,7 @@ fn scenarios() -> Vec<ScenarioSpec> {
         network_access,
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        allow_hosts: Vec::new(),
     };
 
     vec![
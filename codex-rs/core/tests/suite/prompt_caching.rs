@@ -310,6 +310,7 @@ async fn overrides_turn_context_but_keeps_cached_prefix_and_key_constant() -> an
                 network_access: true,
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
+                allow_hosts: Vec::new(),
             }),
             model: Some("o3".to_string()),
             effort: Some(Some(ReasoningEffort::High)),
@@ -497,6 +498,7 @@ async fn per_turn_overrides_keep_cached_prefix_and_key_constant() -> anyhow::Res
                 network_access: true,
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
+                allow_hosts: Vec::new(),
             },
             model: "o3".to_string(),
             effort: Some(ReasoningEffort::High),
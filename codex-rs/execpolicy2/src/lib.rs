@@ -9,6 +9,7 @@ pub use error::Error;
 pub use error::Result;
 pub use parser::PolicyParser;
 pub use policy::Evaluation;
+pub use policy::EvaluationExplanation;
 pub use policy::Policy;
 pub use rule::Rule;
 pub use rule::RuleMatch;
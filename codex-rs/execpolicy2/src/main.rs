@@ -28,6 +28,21 @@ enum Cli {
         )]
         command: Vec<String>,
     },
+
+    /// Explain which rule decides a command and why.
+    Explain {
+        #[arg(short, long = "policy", value_name = "PATH", required = true)]
+        policies: Vec<PathBuf>,
+
+        /// Command tokens to explain.
+        #[arg(
+            value_name = "COMMAND",
+            required = true,
+            trailing_var_arg = true,
+            allow_hyphen_values = true
+        )]
+        command: Vec<String>,
+    },
 }
 
 fn main() -> Result<()> {
@@ -38,6 +53,7 @@ fn main() -> Result<()> {
             command,
             pretty,
         } => cmd_check(policies, command, pretty),
+        Cli::Explain { policies, command } => cmd_explain(policies, command),
     }
 }
 
@@ -54,6 +70,12 @@ fn cmd_check(policy_paths: Vec<PathBuf>, args: Vec<String>, pretty: bool) -> Res
     Ok(())
 }
 
+fn cmd_explain(policy_paths: Vec<PathBuf>, args: Vec<String>) -> Result<()> {
+    let policy = load_policies(&policy_paths)?;
+    println!("{}", policy.explain(&args).reason);
+    Ok(())
+}
+
 fn load_policies(policy_paths: &[PathBuf]) -> Result<codex_execpolicy2::Policy> {
     let mut parser = PolicyParser::new();
     for policy_path in policy_paths {
@@ -63,6 +63,20 @@ impl PolicyParser {
         Ok(())
     }
 
+    /// Parses each `(policy_identifier, policy_file_contents)` pair in order, as if
+    /// [`PolicyParser::parse`] were called for each. Rules accumulate; use
+    /// [`crate::Policy::merge`] to layer policies where later rules override
+    /// earlier ones.
+    pub fn parse_many<'a, Sources>(&mut self, sources: Sources) -> Result<()>
+    where
+        Sources: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        for (policy_identifier, policy_file_contents) in sources {
+            self.parse(policy_identifier, policy_file_contents)?;
+        }
+        Ok(())
+    }
+
     pub fn build(self) -> crate::policy::Policy {
         self.builder.into_inner().build()
     }
@@ -4,6 +4,8 @@ use crate::rule::RuleRef;
 use multimap::MultiMap;
 use serde::Deserialize;
 use serde::Serialize;
+use shlex::try_join;
+use std::sync::Arc;
 
 #[derive(Clone, Debug)]
 pub struct Policy {
@@ -23,17 +25,89 @@ impl Policy {
         &self.rules_by_program
     }
 
+    /// All rules in a stable order: programs sorted by name, then each
+    /// program's rules in the order they were declared. Positions in this
+    /// order are what [`RuleMatch::rule_index`] reports.
+    pub fn iter_rules(&self) -> impl Iterator<Item = &RuleRef> {
+        self.sorted_programs()
+            .into_iter()
+            .flat_map(|program| self.rules_by_program.get_vec(program).into_iter().flatten())
+    }
+
+    fn sorted_programs(&self) -> Vec<&String> {
+        let mut programs: Vec<&String> = self.rules_by_program.keys().collect();
+        programs.sort();
+        programs
+    }
+
+    /// Layers `other` on top of this policy, e.g. a project-local policy over an
+    /// organization-wide base.
+    ///
+    /// A rule in `other` with the same pattern as a rule in `self` replaces it,
+    /// so `other` can loosen a base rule (e.g. `allow` where the base says
+    /// `forbidden`) by redeclaring its pattern. All other rules are kept, and
+    /// evaluation still picks the strictest decision among matches, so `other`
+    /// can always tighten the base by adding rules. For each program, the kept
+    /// rules from `self` come first (in their original order), followed by the
+    /// rules from `other` (in theirs).
+    pub fn merge(&self, other: &Policy) -> Policy {
+        let mut rules_by_program = MultiMap::new();
+        for (program, rules) in self.rules_by_program.iter_all() {
+            let overrides = other.rules_by_program.get_vec(program);
+            for rule in rules {
+                let overridden = overrides.is_some_and(|overrides| {
+                    overrides
+                        .iter()
+                        .any(|other_rule| other_rule.same_pattern(rule.as_ref()))
+                });
+                if !overridden {
+                    rules_by_program.insert(program.clone(), Arc::clone(rule));
+                }
+            }
+        }
+        for (program, rules) in other.rules_by_program.iter_all() {
+            for rule in rules {
+                rules_by_program.insert(program.clone(), Arc::clone(rule));
+            }
+        }
+        Policy::new(rules_by_program)
+    }
+
+    /// Renders the policy as source that [`crate::PolicyParser`] parses back
+    /// into an equivalent policy. Output is canonical: programs are sorted,
+    /// rules for the same program keep their order, and every rule spells out
+    /// its decision. Comments and `match` / `not_match` examples are not kept
+    /// by the parser, so they are not emitted.
+    pub fn to_source(&self) -> String {
+        self.iter_rules()
+            .map(|rule| rule.to_source())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     pub fn check(&self, cmd: &[String]) -> Evaluation {
-        let rules = match cmd.first() {
-            Some(first) => match self.rules_by_program.get_vec(first) {
-                Some(rules) => rules,
-                None => return Evaluation::NoMatch,
-            },
-            None => return Evaluation::NoMatch,
+        let Some(first) = cmd.first() else {
+            return Evaluation::NoMatch;
+        };
+        let Some(rules) = self.rules_by_program.get_vec(first) else {
+            return Evaluation::NoMatch;
         };
 
-        let matched_rules: Vec<RuleMatch> =
-            rules.iter().filter_map(|rule| rule.matches(cmd)).collect();
+        // Rules for programs sorting before `first` come earlier in `iter_rules()`.
+        let offset: usize = self
+            .rules_by_program
+            .iter_all()
+            .filter(|(program, _)| program.as_str() < first.as_str())
+            .map(|(_, rules)| rules.len())
+            .sum();
+        let matched_rules: Vec<RuleMatch> = rules
+            .iter()
+            .enumerate()
+            .filter_map(|(index, rule)| {
+                rule.matches(cmd)
+                    .map(|rule_match| rule_match.with_rule_index(offset + index))
+            })
+            .collect();
         match matched_rules.iter().map(RuleMatch::decision).max() {
             Some(decision) => Evaluation::Match {
                 decision,
@@ -43,6 +117,50 @@ impl Policy {
         }
     }
 
+    /// Like [`Policy::check`], but reports which rule decided the outcome and
+    /// why. When several rules match, the first one with the strictest
+    /// decision is reported.
+    pub fn explain(&self, cmd: &[String]) -> EvaluationExplanation {
+        let command = render_tokens(cmd);
+        let matches: Vec<(&RuleRef, RuleMatch)> = cmd
+            .first()
+            .and_then(|first| self.rules_by_program.get_vec(first))
+            .into_iter()
+            .flatten()
+            .filter_map(|rule| rule.matches(cmd).map(|rule_match| (rule, rule_match)))
+            .collect();
+        let Some((rule, rule_match)) = matches
+            .iter()
+            .min_by_key(|(_, rule_match)| std::cmp::Reverse(rule_match.decision()))
+        else {
+            return EvaluationExplanation {
+                rule: None,
+                decision: None,
+                reason: format!("`{command}` matched no rule"),
+            };
+        };
+
+        let decision = rule_match.decision();
+        let verdict = match decision {
+            Decision::Allow => "is allowed",
+            Decision::Prompt => "requires approval",
+            Decision::Forbidden => "is forbidden",
+        };
+        let RuleMatch::PrefixRuleMatch { matched_prefix, .. } = rule_match;
+        let mut reason = format!(
+            "`{command}` {verdict}: matched prefix rule `{}`",
+            render_tokens(matched_prefix)
+        );
+        if matches.len() > 1 {
+            reason.push_str(&format!(" (strictest of {} matching rules)", matches.len()));
+        }
+        EvaluationExplanation {
+            rule: Some(Arc::clone(rule)),
+            decision: Some(decision),
+            reason,
+        }
+    }
+
     pub fn check_multiple<Commands>(&self, commands: Commands) -> Evaluation
     where
         Commands: IntoIterator,
@@ -82,3 +200,17 @@ impl Evaluation {
         matches!(self, Self::Match { .. })
     }
 }
+
+/// Result of [`Policy::explain`].
+#[derive(Clone, Debug)]
+pub struct EvaluationExplanation {
+    /// Rule that determined the decision, or `None` when nothing matched.
+    pub rule: Option<RuleRef>,
+    pub decision: Option<Decision>,
+    /// Human-readable summary, e.g. ``"`git push` is forbidden: matched prefix rule `git push`"``.
+    pub reason: String,
+}
+
+fn render_tokens(tokens: &[String]) -> String {
+    try_join(tokens.iter().map(String::as_str)).unwrap_or_else(|_| tokens.join(" "))
+}
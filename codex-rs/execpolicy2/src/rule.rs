@@ -63,6 +63,10 @@ pub enum RuleMatch {
         #[serde(rename = "matchedPrefix")]
         matched_prefix: Vec<String>,
         decision: Decision,
+        /// Position of the matched rule in [`crate::Policy::iter_rules`], set
+        /// by [`crate::Policy::check`]. Not serialized.
+        #[serde(skip)]
+        rule_index: Option<usize>,
     },
 }
 
@@ -72,6 +76,19 @@ impl RuleMatch {
             Self::PrefixRuleMatch { decision, .. } => *decision,
         }
     }
+
+    pub fn rule_index(&self) -> Option<usize> {
+        match self {
+            Self::PrefixRuleMatch { rule_index, .. } => *rule_index,
+        }
+    }
+
+    pub(crate) fn with_rule_index(mut self, index: usize) -> Self {
+        match &mut self {
+            Self::PrefixRuleMatch { rule_index, .. } => *rule_index = Some(index),
+        }
+        self
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -84,6 +101,13 @@ pub trait Rule: Any + Debug + Send + Sync {
     fn program(&self) -> &str;
 
     fn matches(&self, cmd: &[String]) -> Option<RuleMatch>;
+
+    /// Whether `other` matches exactly the same commands as this rule. Used by
+    /// [`crate::Policy::merge`] to let a later rule replace an earlier one.
+    fn same_pattern(&self, other: &dyn Rule) -> bool;
+
+    /// Policy source that parses back into an equivalent rule.
+    fn to_source(&self) -> String;
 }
 
 pub type RuleRef = Arc<dyn Rule>;
@@ -99,8 +123,41 @@ impl Rule for PrefixRule {
             .map(|matched_prefix| RuleMatch::PrefixRuleMatch {
                 matched_prefix,
                 decision: self.decision,
+                rule_index: None,
             })
     }
+
+    fn same_pattern(&self, other: &dyn Rule) -> bool {
+        (other as &dyn Any)
+            .downcast_ref::<PrefixRule>()
+            .is_some_and(|other| other.pattern == self.pattern)
+    }
+
+    fn to_source(&self) -> String {
+        let tokens = std::iter::once(string_literal(&self.pattern.first))
+            .chain(self.pattern.rest.iter().map(|token| match token {
+                PatternToken::Single(token) => string_literal(token),
+                PatternToken::Alts(alternatives) => format!(
+                    "[{}]",
+                    alternatives
+                        .iter()
+                        .map(|alt| string_literal(alt))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            }))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "prefix_rule(\n    pattern = [{tokens}],\n    decision = {},\n)\n",
+            string_literal(self.decision.as_str())
+        )
+    }
+}
+
+// JSON string escapes are a subset of Starlark's.
+fn string_literal(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
 }
 
 /// Count how many rules match each provided example and error if any example is unmatched.
@@ -54,6 +54,7 @@ prefix_rule(
             matched_rules: vec![RuleMatch::PrefixRuleMatch {
                 matched_prefix: tokens(&["git", "status"]),
                 decision: Decision::Allow,
+                rule_index: Some(0),
             }],
         },
         evaluation
@@ -111,6 +112,7 @@ prefix_rule(
             matched_rules: vec![RuleMatch::PrefixRuleMatch {
                 matched_prefix: tokens(&["git"]),
                 decision: Decision::Prompt,
+                rule_index: Some(0),
             }],
         },
         status_eval
@@ -124,10 +126,12 @@ prefix_rule(
                 RuleMatch::PrefixRuleMatch {
                     matched_prefix: tokens(&["git"]),
                     decision: Decision::Prompt,
+                    rule_index: Some(0),
                 },
                 RuleMatch::PrefixRuleMatch {
                     matched_prefix: tokens(&["git", "commit"]),
                     decision: Decision::Forbidden,
+                    rule_index: Some(1),
                 },
             ],
         },
@@ -178,6 +182,7 @@ prefix_rule(
             matched_rules: vec![RuleMatch::PrefixRuleMatch {
                 matched_prefix: tokens(&["bash", "-c"]),
                 decision: Decision::Allow,
+                rule_index: Some(0),
             }],
         },
         bash_eval
@@ -190,6 +195,7 @@ prefix_rule(
             matched_rules: vec![RuleMatch::PrefixRuleMatch {
                 matched_prefix: tokens(&["sh", "-l"]),
                 decision: Decision::Allow,
+                rule_index: Some(1),
             }],
         },
         sh_eval
@@ -235,6 +241,7 @@ prefix_rule(
             matched_rules: vec![RuleMatch::PrefixRuleMatch {
                 matched_prefix: tokens(&["npm", "i", "--legacy-peer-deps"]),
                 decision: Decision::Allow,
+                rule_index: Some(0),
             }],
         },
         npm_i
@@ -247,6 +254,7 @@ prefix_rule(
             matched_rules: vec![RuleMatch::PrefixRuleMatch {
                 matched_prefix: tokens(&["npm", "install", "--no-save"]),
                 decision: Decision::Allow,
+                rule_index: Some(0),
             }],
         },
         npm_install
@@ -277,6 +285,7 @@ prefix_rule(
             matched_rules: vec![RuleMatch::PrefixRuleMatch {
                 matched_prefix: tokens(&["git", "status"]),
                 decision: Decision::Allow,
+                rule_index: Some(0),
             }],
         },
         match_eval
@@ -317,10 +326,12 @@ prefix_rule(
                 RuleMatch::PrefixRuleMatch {
                     matched_prefix: tokens(&["git"]),
                     decision: Decision::Prompt,
+                    rule_index: Some(0),
                 },
                 RuleMatch::PrefixRuleMatch {
                     matched_prefix: tokens(&["git", "commit"]),
                     decision: Decision::Forbidden,
+                    rule_index: Some(1),
                 },
             ],
         },
@@ -359,17 +370,420 @@ prefix_rule(
                 RuleMatch::PrefixRuleMatch {
                     matched_prefix: tokens(&["git"]),
                     decision: Decision::Prompt,
+                    rule_index: Some(0),
                 },
                 RuleMatch::PrefixRuleMatch {
                     matched_prefix: tokens(&["git"]),
                     decision: Decision::Prompt,
+                    rule_index: Some(0),
                 },
                 RuleMatch::PrefixRuleMatch {
                     matched_prefix: tokens(&["git", "commit"]),
                     decision: Decision::Forbidden,
+                    rule_index: Some(1),
                 },
             ],
         },
         evaluation
     );
 }
+
+#[test]
+fn explain_names_the_deciding_rule() {
+    let policy_src = r#"
+prefix_rule(
+    pattern = ["git", "status"],
+)
+prefix_rule(
+    pattern = ["git"],
+    decision = "prompt",
+)
+prefix_rule(
+    pattern = ["git", "push"],
+    decision = "forbidden",
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser
+        .parse("test.codexpolicy", policy_src)
+        .expect("parse policy");
+    let policy = parser.build();
+    let explain = |cmd: &[&str]| {
+        let explanation = policy.explain(&tokens(cmd));
+        (
+            rule_snapshots(explanation.rule.as_slice()),
+            explanation.decision,
+            explanation.reason,
+        )
+    };
+
+    assert_eq!(
+        (
+            vec![RuleSnapshot::Prefix(PrefixRule {
+                pattern: PrefixPattern {
+                    first: Arc::from("git"),
+                    rest: vec![PatternToken::Single("push".to_string())].into(),
+                },
+                decision: Decision::Forbidden,
+            })],
+            Some(Decision::Forbidden),
+            "`git push origin main` is forbidden: matched prefix rule `git push` (strictest of 2 matching rules)"
+                .to_string(),
+        ),
+        explain(&["git", "push", "origin", "main"])
+    );
+    assert_eq!(
+        (
+            vec![RuleSnapshot::Prefix(PrefixRule {
+                pattern: PrefixPattern {
+                    first: Arc::from("git"),
+                    rest: Vec::<PatternToken>::new().into(),
+                },
+                decision: Decision::Prompt,
+            })],
+            Some(Decision::Prompt),
+            "`git status` requires approval: matched prefix rule `git` (strictest of 2 matching rules)"
+                .to_string(),
+        ),
+        explain(&["git", "status"])
+    );
+    assert_eq!(
+        (Vec::new(), None, "`ls -l` matched no rule".to_string()),
+        explain(&["ls", "-l"])
+    );
+}
+
+#[test]
+fn explain_reports_allowing_rule() {
+    let policy_src = r#"
+prefix_rule(
+    pattern = ["cargo", ["build", "test"]],
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser
+        .parse("test.codexpolicy", policy_src)
+        .expect("parse policy");
+    let policy = parser.build();
+
+    let explanation = policy.explain(&tokens(&["cargo", "test", "-p", "codex-core"]));
+    assert_eq!(
+        (
+            rule_snapshots(explanation.rule.as_slice()),
+            explanation.decision,
+            explanation.reason,
+        ),
+        (
+            vec![RuleSnapshot::Prefix(PrefixRule {
+                pattern: PrefixPattern {
+                    first: Arc::from("cargo"),
+                    rest: vec![PatternToken::Alts(vec![
+                        "build".to_string(),
+                        "test".to_string(),
+                    ])]
+                    .into(),
+                },
+                decision: Decision::Allow,
+            })],
+            Some(Decision::Allow),
+            "`cargo test -p codex-core` is allowed: matched prefix rule `cargo test`".to_string(),
+        )
+    );
+}
+
+#[test]
+fn merged_policy_can_tighten_and_loosen_base() {
+    let base_policy = r#"
+prefix_rule(
+    pattern = ["git", "push"],
+    decision = "forbidden",
+)
+    "#;
+    let base_status_policy = r#"
+prefix_rule(
+    pattern = ["git", "status"],
+)
+    "#;
+    let project_policy = r#"
+prefix_rule(
+    pattern = ["git", "push"],
+    decision = "prompt",
+)
+prefix_rule(
+    pattern = ["git", "status", "--ignored"],
+    decision = "forbidden",
+)
+    "#;
+    let mut base_parser = PolicyParser::new();
+    base_parser
+        .parse_many([
+            ("base.codexpolicy", base_policy),
+            ("base_status.codexpolicy", base_status_policy),
+        ])
+        .expect("parse base policies");
+    let base = base_parser.build();
+    let mut project_parser = PolicyParser::new();
+    project_parser
+        .parse("project.codexpolicy", project_policy)
+        .expect("parse project policy");
+    let project = project_parser.build();
+
+    let merged = base.merge(&project);
+
+    let git_rules = merged.rules().get_vec("git").expect("git rules");
+    assert_eq!(
+        vec![
+            RuleSnapshot::Prefix(PrefixRule {
+                pattern: PrefixPattern {
+                    first: Arc::from("git"),
+                    rest: vec![PatternToken::Single("status".to_string())].into(),
+                },
+                decision: Decision::Allow,
+            }),
+            RuleSnapshot::Prefix(PrefixRule {
+                pattern: PrefixPattern {
+                    first: Arc::from("git"),
+                    rest: vec![PatternToken::Single("push".to_string())].into(),
+                },
+                decision: Decision::Prompt,
+            }),
+            RuleSnapshot::Prefix(PrefixRule {
+                pattern: PrefixPattern {
+                    first: Arc::from("git"),
+                    rest: vec![
+                        PatternToken::Single("status".to_string()),
+                        PatternToken::Single("--ignored".to_string()),
+                    ]
+                    .into(),
+                },
+                decision: Decision::Forbidden,
+            }),
+        ],
+        rule_snapshots(git_rules)
+    );
+    assert_eq!(
+        (
+            Evaluation::Match {
+                decision: Decision::Prompt,
+                matched_rules: vec![RuleMatch::PrefixRuleMatch {
+                    matched_prefix: tokens(&["git", "push"]),
+                    decision: Decision::Prompt,
+                    rule_index: Some(1),
+                }],
+            },
+            Evaluation::Match {
+                decision: Decision::Forbidden,
+                matched_rules: vec![
+                    RuleMatch::PrefixRuleMatch {
+                        matched_prefix: tokens(&["git", "status"]),
+                        decision: Decision::Allow,
+                        rule_index: Some(0),
+                    },
+                    RuleMatch::PrefixRuleMatch {
+                        matched_prefix: tokens(&["git", "status", "--ignored"]),
+                        decision: Decision::Forbidden,
+                        rule_index: Some(2),
+                    },
+                ],
+            },
+        ),
+        (
+            merged.check(&tokens(&["git", "push", "origin"])),
+            merged.check(&tokens(&["git", "status", "--ignored"])),
+        )
+    );
+}
+
+#[test]
+fn merge_keeps_stricter_base_rules_with_different_patterns() {
+    let base_policy = r#"
+prefix_rule(
+    pattern = ["rm"],
+    decision = "forbidden",
+)
+    "#;
+    let project_policy = r#"
+prefix_rule(
+    pattern = ["rm", "-i"],
+)
+    "#;
+    let mut base_parser = PolicyParser::new();
+    base_parser
+        .parse("base.codexpolicy", base_policy)
+        .expect("parse base policy");
+    let mut project_parser = PolicyParser::new();
+    project_parser
+        .parse("project.codexpolicy", project_policy)
+        .expect("parse project policy");
+
+    let merged = base_parser.build().merge(&project_parser.build());
+
+    assert_eq!(
+        Evaluation::Match {
+            decision: Decision::Forbidden,
+            matched_rules: vec![
+                RuleMatch::PrefixRuleMatch {
+                    matched_prefix: tokens(&["rm"]),
+                    decision: Decision::Forbidden,
+                    rule_index: Some(0),
+                },
+                RuleMatch::PrefixRuleMatch {
+                    matched_prefix: tokens(&["rm", "-i"]),
+                    decision: Decision::Allow,
+                    rule_index: Some(1),
+                },
+            ],
+        },
+        merged.check(&tokens(&["rm", "-i", "notes.txt"]))
+    );
+}
+
+#[test]
+fn to_source_round_trips_through_the_parser() {
+    let policy_src = r#"
+# Comments and examples are dropped by to_source.
+prefix_rule(
+    pattern = ["git", ["status", "log"]],
+    match = ["git status"],
+)
+prefix_rule(
+    pattern = ["git"],
+    decision = "prompt",
+)
+prefix_rule(
+    pattern = [["bash", "sh"], "-c"],
+    decision = "forbidden",
+)
+prefix_rule(
+    pattern = ["echo", "it's \"quoted\"", "back\\slash"],
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser
+        .parse("test.codexpolicy", policy_src)
+        .expect("parse policy");
+    let policy = parser.build();
+
+    let source = policy.to_source();
+    let mut reparser = PolicyParser::new();
+    reparser
+        .parse("roundtrip.codexpolicy", &source)
+        .expect("parse rendered policy");
+    let reparsed = reparser.build();
+
+    let corpus = [
+        vec!["git", "status"],
+        vec!["git", "log", "-p"],
+        vec!["git", "push"],
+        vec!["bash", "-c", "ls"],
+        vec!["sh", "-c", "ls"],
+        vec!["bash", "script.sh"],
+        vec!["echo", "it's \"quoted\"", "back\\slash"],
+        vec!["echo", "it's quoted"],
+        vec!["ls"],
+        vec![],
+    ];
+    for cmd in corpus {
+        let cmd = tokens(&cmd);
+        assert_eq!(policy.check(&cmd), reparsed.check(&cmd), "{cmd:?}");
+    }
+    assert_eq!(source, reparsed.to_source());
+}
+
+#[test]
+fn to_source_is_canonical() {
+    let policy_src = r#"
+prefix_rule(pattern = ["npm", ["ci", "test"]], decision = "allow")
+prefix_rule(pattern = ["cargo"], decision = "prompt")
+    "#;
+    let mut parser = PolicyParser::new();
+    parser
+        .parse("test.codexpolicy", policy_src)
+        .expect("parse policy");
+
+    assert_eq!(
+        r#"prefix_rule(
+    pattern = ["cargo"],
+    decision = "prompt",
+)
+
+prefix_rule(
+    pattern = ["npm", ["ci", "test"]],
+    decision = "allow",
+)
+"#,
+        parser.build().to_source()
+    );
+}
+
+#[test]
+fn rule_index_points_into_iter_rules() {
+    let policy_src = r#"
+prefix_rule(
+    pattern = ["npm", "publish"],
+    decision = "forbidden",
+)
+prefix_rule(
+    pattern = ["git", "status"],
+)
+prefix_rule(
+    pattern = ["git", "push"],
+    decision = "prompt",
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser
+        .parse("test.codexpolicy", policy_src)
+        .expect("parse policy");
+    let policy = parser.build();
+    let rules: Vec<RuleRef> = policy.iter_rules().cloned().collect();
+
+    let snapshots = rule_snapshots(&rules);
+    let prefix_rule = |first: &str, second: &str, decision| {
+        RuleSnapshot::Prefix(PrefixRule {
+            pattern: PrefixPattern {
+                first: Arc::from(first),
+                rest: vec![PatternToken::Single(second.to_string())].into(),
+            },
+            decision,
+        })
+    };
+    assert_eq!(
+        vec![
+            prefix_rule("git", "status", Decision::Allow),
+            prefix_rule("git", "push", Decision::Prompt),
+            prefix_rule("npm", "publish", Decision::Forbidden),
+        ],
+        snapshots
+    );
+
+    let Evaluation::Match { matched_rules, .. } =
+        policy.check(&tokens(&["npm", "publish", "--dry-run"]))
+    else {
+        panic!("expected npm publish to match");
+    };
+    let indices: Vec<Option<usize>> = matched_rules.iter().map(RuleMatch::rule_index).collect();
+    assert_eq!(vec![Some(2)], indices);
+    assert_eq!(
+        prefix_rule("npm", "publish", Decision::Forbidden),
+        snapshots[2]
+    );
+}
+
+#[test]
+fn rule_index_is_not_serialized() {
+    let rule_match = RuleMatch::PrefixRuleMatch {
+        matched_prefix: tokens(&["git", "status"]),
+        decision: Decision::Allow,
+        rule_index: Some(3),
+    };
+    assert_eq!(
+        serde_json::json!({
+            "prefixRuleMatch": {
+                "matchedPrefix": ["git", "status"],
+                "decision": "allow",
+            }
+        }),
+        serde_json::to_value(&rule_match).expect("serialize rule match")
+    );
+}
@@ -1,7 +1,9 @@
+use std::collections::BTreeMap;
 use std::collections::VecDeque;
 use std::fs;
 use std::io::Write;
 use std::io::{self};
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -10,16 +12,36 @@ use std::time::Duration;
 use anyhow::Result;
 use anyhow::anyhow;
 use codex_protocol::ConversationId;
+use regex_lite::Regex;
+use sentry::transports::DefaultTransportFactory;
+use sentry::types::Dsn;
 use tracing_subscriber::fmt::writer::MakeWriter;
 
+mod tar;
+
 const DEFAULT_MAX_BYTES: usize = 4 * 1024 * 1024; // 4 MiB
 const SENTRY_DSN: &str =
     "https://ae32ed50620d7a7792c1ce5df38b3e3e@o33249.ingest.us.sentry.io/4510195390611458";
 const UPLOAD_TIMEOUT_SECS: u64 = 10;
+const FEEDBACK_QUEUE_DIR: &str = "feedback-queue";
+/// Most envelopes kept in the offline queue; older ones are dropped first.
+const MAX_QUEUED_ENVELOPES: usize = 20;
+/// Extension given to queued files that could not be read or parsed, so they
+/// are kept for inspection but never retried.
+const INVALID_ENVELOPE_EXTENSION: &str = "invalid";
+/// Patterns masked in snapshots by default. Text matched by the first capture
+/// group (if any) is kept and the rest of the match is replaced with `***`.
+const DEFAULT_REDACTION_PATTERNS: &[&str] = &[
+    r"(Bearer )[A-Za-z0-9._-]+",
+    r"\b(sk-)[A-Za-z0-9_-]+",
+    r#"(?i)(ChatGPT-Account-Id:\s*)[^\s"]+"#,
+];
+const REDACTION_REPLACEMENT: &str = "${1}***";
 
 #[derive(Clone)]
 pub struct CodexFeedback {
     inner: Arc<FeedbackInner>,
+    redactions: Arc<Vec<Regex>>,
 }
 
 impl Default for CodexFeedback {
@@ -33,12 +55,32 @@ impl CodexFeedback {
         Self::with_capacity(DEFAULT_MAX_BYTES)
     }
 
+    /// Create a feedback sink that keeps the last `max_bytes` bytes of logs.
+    pub fn with_max_bytes(max_bytes: usize) -> Self {
+        Self::with_capacity(max_bytes)
+    }
+
     pub(crate) fn with_capacity(max_bytes: usize) -> Self {
         Self {
             inner: Arc::new(FeedbackInner::new(max_bytes)),
+            redactions: Arc::new(default_redactions()),
         }
     }
 
+    /// Replace the patterns masked in snapshots. If a pattern has a capture
+    /// group, the text it matches is preserved and only the remainder of the
+    /// match is replaced with `***`.
+    pub fn with_redaction(mut self, patterns: Vec<Regex>) -> Self {
+        self.redactions = Arc::new(patterns);
+        self
+    }
+
+    /// Maximum number of log bytes retained for a snapshot.
+    pub fn capacity(&self) -> usize {
+        let guard = self.inner.ring.lock().expect("mutex poisoned");
+        guard.max
+    }
+
     pub fn make_writer(&self) -> FeedbackMakeWriter {
         FeedbackMakeWriter {
             inner: self.inner.clone(),
@@ -50,13 +92,44 @@ impl CodexFeedback {
             let guard = self.inner.ring.lock().expect("mutex poisoned");
             guard.snapshot_bytes()
         };
+        let bytes = redact(bytes, &self.redactions);
         CodexLogSnapshot {
             bytes,
             thread_id: session_id
                 .map(|id| id.to_string())
                 .unwrap_or("no-active-thread-".to_string() + &ConversationId::new().to_string()),
+            queue_dir: None,
+        }
+    }
+}
+
+/// The redaction patterns applied by default: bearer tokens, `sk-` API keys
+/// and `ChatGPT-Account-Id` header values.
+pub fn default_redactions() -> Vec<Regex> {
+    DEFAULT_REDACTION_PATTERNS
+        .iter()
+        .map(|pattern| Regex::new(pattern).expect("default redaction pattern should compile"))
+        .collect()
+}
+
+fn redact(bytes: Vec<u8>, patterns: &[Regex]) -> Vec<u8> {
+    if patterns.is_empty() {
+        return bytes;
+    }
+    // Redact each valid UTF-8 run and keep any other bytes (e.g. a character
+    // split by ring-buffer eviction) untouched.
+    let mut out = Vec::with_capacity(bytes.len());
+    for chunk in bytes.utf8_chunks() {
+        let mut text = chunk.valid().to_string();
+        for pattern in patterns {
+            text = pattern
+                .replace_all(&text, REDACTION_REPLACEMENT)
+                .into_owned();
         }
+        out.extend_from_slice(text.as_bytes());
+        out.extend_from_slice(chunk.invalid());
     }
+    out
 }
 
 struct FeedbackInner {
@@ -152,9 +225,36 @@ impl RingBuffer {
 pub struct CodexLogSnapshot {
     bytes: Vec<u8>,
     pub thread_id: String,
+    queue_dir: Option<PathBuf>,
+}
+
+/// Returned by [`CodexLogSnapshot::upload_feedback`] when the envelope could
+/// not be delivered and was written to the offline queue instead.
+#[derive(Debug)]
+pub struct FeedbackQueued {
+    pub path: PathBuf,
+}
+
+impl std::fmt::Display for FeedbackQueued {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "feedback could not be delivered and was queued at {}",
+            self.path.display()
+        )
+    }
 }
 
+impl std::error::Error for FeedbackQueued {}
+
 impl CodexLogSnapshot {
+    /// Queue undelivered uploads under `codex_home/feedback-queue/` so they
+    /// can be retried later with [`Self::flush_queued_feedback`].
+    pub fn with_offline_queue(mut self, codex_home: &Path) -> Self {
+        self.queue_dir = Some(codex_home.join(FEEDBACK_QUEUE_DIR));
+        self
+    }
+
     pub(crate) fn as_bytes(&self) -> &[u8] {
         &self.bytes
     }
@@ -168,17 +268,36 @@ impl CodexLogSnapshot {
     }
 
     /// Upload feedback to Sentry with optional attachments.
+    ///
+    /// `metadata` is attached as `metadata.json` unless it is `Value::Null`.
     pub fn upload_feedback(
         &self,
         classification: &str,
         reason: Option<&str>,
         include_logs: bool,
         rollout_path: Option<&std::path::Path>,
+        metadata: serde_json::Value,
+    ) -> Result<()> {
+        self.upload_feedback_with_transport(
+            classification,
+            reason,
+            include_logs,
+            rollout_path,
+            metadata,
+            Arc::new(DefaultTransportFactory {}),
+        )
+    }
+
+    fn upload_feedback_with_transport(
+        &self,
+        classification: &str,
+        reason: Option<&str>,
+        include_logs: bool,
+        rollout_path: Option<&std::path::Path>,
+        metadata: serde_json::Value,
+        transport: Arc<dyn sentry::TransportFactory>,
     ) -> Result<()> {
-        use std::collections::BTreeMap;
-        use std::fs;
         use std::str::FromStr;
-        use std::sync::Arc;
 
         use sentry::Client;
         use sentry::ClientOptions;
@@ -187,25 +306,15 @@ impl CodexLogSnapshot {
         use sentry::protocol::EnvelopeItem;
         use sentry::protocol::Event;
         use sentry::protocol::Level;
-        use sentry::transports::DefaultTransportFactory;
-        use sentry::types::Dsn;
 
         // Build Sentry client
         let client = Client::from_config(ClientOptions {
             dsn: Some(Dsn::from_str(SENTRY_DSN).map_err(|e| anyhow!("invalid DSN: {e}"))?),
-            transport: Some(Arc::new(DefaultTransportFactory {})),
+            transport: Some(transport),
             ..Default::default()
         });
 
-        let cli_version = env!("CARGO_PKG_VERSION");
-        let mut tags = BTreeMap::from([
-            (String::from("thread_id"), self.thread_id.to_string()),
-            (String::from("classification"), classification.to_string()),
-            (String::from("cli_version"), cli_version.to_string()),
-        ]);
-        if let Some(r) = reason {
-            tags.insert(String::from("reason"), r.to_string());
-        }
+        let tags = self.feedback_tags(classification, reason);
 
         let level = match classification {
             "bug" | "bad_result" => Level::Error,
@@ -237,32 +346,246 @@ impl CodexLogSnapshot {
         }
         envelope.add_item(EnvelopeItem::Event(event));
 
-        if include_logs {
+        for attachment in self.feedback_attachments(include_logs, rollout_path, &metadata)? {
             envelope.add_item(EnvelopeItem::Attachment(Attachment {
-                buffer: self.bytes.clone(),
-                filename: String::from("codex-logs.log"),
-                content_type: Some("text/plain".to_string()),
+                buffer: attachment.buffer,
+                filename: attachment.filename,
+                content_type: Some(attachment.content_type.to_string()),
                 ty: None,
             }));
         }
 
+        let queued = self.queue_dir.as_ref().map(|dir| (dir, envelope.clone()));
+        client.send_envelope(envelope);
+        if client.flush(Some(Duration::from_secs(UPLOAD_TIMEOUT_SECS))) {
+            return Ok(());
+        }
+        match queued {
+            Some((dir, envelope)) => {
+                let path = write_queued_envelope(dir, &self.thread_id, &envelope)?;
+                Err(FeedbackQueued { path }.into())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Write the attachments [`Self::upload_feedback`] would send, plus a
+    /// `feedback.json` with its tags, to a tar archive at `dest` for manual
+    /// submission. Nothing is sent over the network.
+    pub fn build_feedback_bundle(
+        &self,
+        classification: &str,
+        reason: Option<&str>,
+        include_logs: bool,
+        rollout_path: Option<&std::path::Path>,
+        metadata: serde_json::Value,
+        dest: &Path,
+    ) -> Result<()> {
+        let tags = self.feedback_tags(classification, reason);
+        let mut entries = vec![(
+            String::from("feedback.json"),
+            serde_json::to_vec_pretty(&tags)?,
+        )];
+        entries.extend(
+            self.feedback_attachments(include_logs, rollout_path, &metadata)?
+                .into_iter()
+                .map(|attachment| (attachment.filename, attachment.buffer)),
+        );
+        let mtime_secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let file = io::BufWriter::new(fs::File::create(dest)?);
+        tar::write_archive(file, &entries, mtime_secs)?;
+        Ok(())
+    }
+
+    fn feedback_tags(
+        &self,
+        classification: &str,
+        reason: Option<&str>,
+    ) -> BTreeMap<String, String> {
+        let cli_version = env!("CARGO_PKG_VERSION");
+        let mut tags = BTreeMap::from([
+            (String::from("thread_id"), self.thread_id.to_string()),
+            (String::from("classification"), classification.to_string()),
+            (String::from("cli_version"), cli_version.to_string()),
+        ]);
+        if let Some(r) = reason {
+            tags.insert(String::from("reason"), r.to_string());
+        }
+        tags
+    }
+
+    fn feedback_attachments(
+        &self,
+        include_logs: bool,
+        rollout_path: Option<&std::path::Path>,
+        metadata: &serde_json::Value,
+    ) -> Result<Vec<FeedbackAttachment>> {
+        let mut attachments = Vec::new();
+        if include_logs {
+            attachments.push(FeedbackAttachment {
+                buffer: self.bytes.clone(),
+                filename: String::from("codex-logs.log"),
+                content_type: "text/plain",
+            });
+        }
+
+        if !metadata.is_null() {
+            attachments.push(FeedbackAttachment {
+                buffer: serde_json::to_vec(metadata)?,
+                filename: String::from("metadata.json"),
+                content_type: "application/json",
+            });
+        }
+
         if let Some((path, data)) = rollout_path.and_then(|p| fs::read(p).ok().map(|d| (p, d))) {
             let fname = path
                 .file_name()
                 .map(|s| s.to_string_lossy().to_string())
                 .unwrap_or_else(|| "rollout.jsonl".to_string());
-            let content_type = "text/plain".to_string();
-            envelope.add_item(EnvelopeItem::Attachment(Attachment {
+            attachments.push(FeedbackAttachment {
                 buffer: data,
+                content_type: content_type_for(&fname),
                 filename: fname,
-                content_type: Some(content_type),
-                ty: None,
-            }));
+            });
         }
+        Ok(attachments)
+    }
 
-        client.send_envelope(envelope);
-        client.flush(Some(Duration::from_secs(UPLOAD_TIMEOUT_SECS)));
-        Ok(())
+    /// Retry every envelope queued under `codex_home/feedback-queue/`.
+    /// Delivered envelopes are removed; returns how many were sent. Entries
+    /// that cannot be read are renamed to `*.invalid` and skipped.
+    pub fn flush_queued_feedback(codex_home: &Path) -> Result<usize> {
+        Self::flush_queued_feedback_with_transport(codex_home, Arc::new(DefaultTransportFactory {}))
+    }
+
+    /// Run [`Self::flush_queued_feedback`] on a detached thread so front ends
+    /// can retry queued uploads at startup without waiting on the network.
+    pub fn flush_queued_feedback_in_background(codex_home: PathBuf) {
+        let spawned = std::thread::Builder::new()
+            .name("feedback-queue-flush".to_string())
+            .spawn(move || match Self::flush_queued_feedback(&codex_home) {
+                Ok(0) => {}
+                Ok(sent) => tracing::info!("delivered {sent} queued feedback upload(s)"),
+                Err(err) => tracing::warn!("failed to flush queued feedback: {err}"),
+            });
+        if let Err(err) = spawned {
+            tracing::warn!("failed to start queued feedback flush: {err}");
+        }
+    }
+
+    fn flush_queued_feedback_with_transport(
+        codex_home: &Path,
+        transport: Arc<dyn sentry::TransportFactory>,
+    ) -> Result<usize> {
+        use std::str::FromStr;
+
+        use sentry::Client;
+        use sentry::ClientOptions;
+        use sentry::protocol::Envelope;
+
+        let dir = codex_home.join(FEEDBACK_QUEUE_DIR);
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(err.into()),
+        };
+        let paths = queued_envelope_paths(entries);
+
+        let client = Client::from_config(ClientOptions {
+            dsn: Some(Dsn::from_str(SENTRY_DSN).map_err(|e| anyhow!("invalid DSN: {e}"))?),
+            transport: Some(transport),
+            ..Default::default()
+        });
+
+        let mut sent = 0;
+        for path in paths {
+            let envelope = match Envelope::from_path(&path) {
+                Ok(envelope) => envelope,
+                Err(err) => {
+                    tracing::warn!("skipping invalid queued feedback {}: {err}", path.display());
+                    // Best effort: if the rename fails the entry is skipped
+                    // again on the next flush.
+                    let _ = fs::rename(&path, path.with_extension(INVALID_ENVELOPE_EXTENSION));
+                    continue;
+                }
+            };
+            client.send_envelope(envelope);
+            if !client.flush(Some(Duration::from_secs(UPLOAD_TIMEOUT_SECS))) {
+                break;
+            }
+            if let Err(err) = fs::remove_file(&path) {
+                tracing::warn!(
+                    "failed to remove delivered feedback {}: {err}",
+                    path.display()
+                );
+            }
+            sent += 1;
+        }
+        Ok(sent)
+    }
+}
+
+struct FeedbackAttachment {
+    buffer: Vec<u8>,
+    filename: String,
+    content_type: &'static str,
+}
+
+/// MIME type for an attachment, inferred from its extension so Sentry renders
+/// it sensibly. Unknown extensions are sent as plain text.
+fn content_type_for(filename: &str) -> &'static str {
+    let extension = Path::new(filename)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("jsonl") => "application/x-ndjson",
+        Some("json") => "application/json",
+        _ => "text/plain",
+    }
+}
+
+fn write_queued_envelope(
+    dir: &Path,
+    thread_id: &str,
+    envelope: &sentry::protocol::Envelope,
+) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let path = dir.join(format!("{timestamp}-{thread_id}.envelope"));
+    let mut buf = Vec::new();
+    envelope.to_writer(&mut buf)?;
+    fs::write(&path, buf)?;
+    prune_queue(dir, MAX_QUEUED_ENVELOPES);
+    Ok(path)
+}
+
+/// Queued `*.envelope` files in `entries`, oldest first. File names start
+/// with a millisecond timestamp, so name order is queue order.
+fn queued_envelope_paths(entries: fs::ReadDir) -> Vec<PathBuf> {
+    let mut paths = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "envelope"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
+
+/// Delete the oldest queued envelopes until at most `max` remain.
+fn prune_queue(dir: &Path, max: usize) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let paths = queued_envelope_paths(entries);
+    let excess = paths.len().saturating_sub(max);
+    for path in &paths[..excess] {
+        let _ = fs::remove_file(path);
     }
 }
 
@@ -291,4 +614,248 @@ mod tests {
         // Capacity 8: after writing 10 bytes, we should keep the last 8.
         pretty_assertions::assert_eq!(std::str::from_utf8(snap.as_bytes()).unwrap(), "cdefghij");
     }
+
+    #[test]
+    fn snapshot_redacts_secrets() {
+        let fb = CodexFeedback::with_capacity(1024);
+        {
+            let mut w = fb.make_writer().make_writer();
+            w.write_all(b"Authorization: Bearer abc.DEF-123_xyz\n")
+                .unwrap();
+            w.write_all(b"api key sk-proj1234 for task-runner\n")
+                .unwrap();
+            w.write_all(b"ChatGPT-Account-Id: acct_42\n").unwrap();
+        }
+        let snap = fb.snapshot(None);
+        pretty_assertions::assert_eq!(
+            std::str::from_utf8(snap.as_bytes()).unwrap(),
+            "Authorization: Bearer ***\napi key sk-*** for task-runner\nChatGPT-Account-Id: ***\n"
+        );
+    }
+
+    #[test]
+    fn custom_redaction_patterns_replace_defaults() {
+        let fb = CodexFeedback::with_capacity(1024)
+            .with_redaction(vec![Regex::new(r"(password=)\S+").unwrap()]);
+        {
+            let mut w = fb.make_writer().make_writer();
+            w.write_all(b"password=hunter2 Bearer visible").unwrap();
+        }
+        let snap = fb.snapshot(None);
+        pretty_assertions::assert_eq!(
+            std::str::from_utf8(snap.as_bytes()).unwrap(),
+            "password=*** Bearer visible"
+        );
+    }
+
+    #[test]
+    fn upload_attaches_logs_and_metadata() {
+        use sentry::protocol::EnvelopeItem;
+        use sentry::test::TestTransport;
+
+        let fb = CodexFeedback::with_capacity(1024);
+        {
+            let mut w = fb.make_writer().make_writer();
+            w.write_all(b"log line\n").unwrap();
+        }
+        let snap = fb.snapshot(None);
+        let transport = TestTransport::new();
+
+        snap.upload_feedback_with_transport(
+            "bug",
+            Some("it broke"),
+            true,
+            None,
+            serde_json::json!({ "model": "gpt-5", "sandbox": "read-only" }),
+            Arc::new(transport.clone()),
+        )
+        .unwrap();
+
+        let envelopes = transport.fetch_and_clear_envelopes();
+        pretty_assertions::assert_eq!(envelopes.len(), 1);
+        let items: Vec<&EnvelopeItem> = envelopes[0].items().collect();
+        pretty_assertions::assert_eq!(items.len(), 3);
+        let metadata = items
+            .iter()
+            .find_map(|item| match item {
+                EnvelopeItem::Attachment(attachment) if attachment.filename == "metadata.json" => {
+                    Some(attachment)
+                }
+                _ => None,
+            })
+            .expect("metadata attachment");
+        pretty_assertions::assert_eq!(metadata.content_type.as_deref(), Some("application/json"));
+        pretty_assertions::assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&metadata.buffer).unwrap(),
+            serde_json::json!({ "model": "gpt-5", "sandbox": "read-only" })
+        );
+    }
+
+    #[test]
+    fn bundle_contains_logs_and_metadata() {
+        let fb = CodexFeedback::with_capacity(1024);
+        {
+            let mut w = fb.make_writer().make_writer();
+            w.write_all(b"log line\n").unwrap();
+        }
+        let snap = fb.snapshot(None);
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("feedback.tar");
+
+        snap.build_feedback_bundle(
+            "bug",
+            Some("it broke"),
+            true,
+            None,
+            serde_json::json!({ "model": "gpt-5" }),
+            &dest,
+        )
+        .unwrap();
+
+        let entries = crate::tar::read_archive(&std::fs::read(&dest).unwrap());
+        let names: Vec<&str> = entries.iter().map(|(name, _)| name.as_str()).collect();
+        pretty_assertions::assert_eq!(
+            names,
+            vec!["feedback.json", "codex-logs.log", "metadata.json"]
+        );
+        pretty_assertions::assert_eq!(entries[1].1, b"log line\n".to_vec());
+        pretty_assertions::assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&entries[2].1).unwrap(),
+            serde_json::json!({ "model": "gpt-5" })
+        );
+        let tags: serde_json::Value = serde_json::from_slice(&entries[0].1).unwrap();
+        pretty_assertions::assert_eq!(tags["classification"], "bug");
+        pretty_assertions::assert_eq!(tags["reason"], "it broke");
+        pretty_assertions::assert_eq!(tags["thread_id"], snap.thread_id.as_str());
+    }
+
+    #[test]
+    fn content_type_is_inferred_from_extension() {
+        let types = [
+            "rollout-2025-01-01T00-00-00-abc.jsonl",
+            "metadata.json",
+            "codex-logs.log",
+            "ROLLOUT.JSONL",
+            "notes",
+        ]
+        .map(content_type_for);
+        pretty_assertions::assert_eq!(
+            types,
+            [
+                "application/x-ndjson",
+                "application/json",
+                "text/plain",
+                "application/x-ndjson",
+                "text/plain",
+            ]
+        );
+    }
+
+    struct OfflineTransport;
+
+    impl sentry::Transport for OfflineTransport {
+        fn send_envelope(&self, _envelope: sentry::protocol::Envelope) {}
+
+        fn flush(&self, _timeout: Duration) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn undelivered_feedback_is_queued_and_retried() {
+        use sentry::test::TestTransport;
+
+        let codex_home = tempfile::tempdir().unwrap();
+        let fb = CodexFeedback::with_capacity(1024);
+        {
+            let mut w = fb.make_writer().make_writer();
+            w.write_all(b"log line\n").unwrap();
+        }
+        let snap = fb.snapshot(None).with_offline_queue(codex_home.path());
+
+        let err = snap
+            .upload_feedback_with_transport(
+                "bug",
+                None,
+                true,
+                None,
+                serde_json::Value::Null,
+                Arc::new(Arc::new(OfflineTransport)),
+            )
+            .unwrap_err();
+        let queued = err.downcast::<FeedbackQueued>().unwrap();
+        let queue_dir = codex_home.path().join(FEEDBACK_QUEUE_DIR);
+        pretty_assertions::assert_eq!(queued.path.parent(), Some(queue_dir.as_path()));
+        pretty_assertions::assert_eq!(fs::read_dir(&queue_dir).unwrap().count(), 1);
+
+        let transport = TestTransport::new();
+        let sent = CodexLogSnapshot::flush_queued_feedback_with_transport(
+            codex_home.path(),
+            Arc::new(transport.clone()),
+        )
+        .unwrap();
+        pretty_assertions::assert_eq!(sent, 1);
+        pretty_assertions::assert_eq!(transport.fetch_and_clear_envelopes().len(), 1);
+        pretty_assertions::assert_eq!(fs::read_dir(&queue_dir).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn invalid_queued_feedback_is_quarantined_and_skipped() {
+        use sentry::test::TestTransport;
+
+        let codex_home = tempfile::tempdir().unwrap();
+        let queue_dir = codex_home.path().join(FEEDBACK_QUEUE_DIR);
+        fs::create_dir_all(&queue_dir).unwrap();
+        fs::write(queue_dir.join("0-broken.envelope"), b"not an envelope").unwrap();
+        write_queued_envelope(&queue_dir, "thread", &sentry::protocol::Envelope::new()).unwrap();
+
+        let transport = TestTransport::new();
+        let sent = CodexLogSnapshot::flush_queued_feedback_with_transport(
+            codex_home.path(),
+            Arc::new(transport.clone()),
+        )
+        .unwrap();
+
+        pretty_assertions::assert_eq!(sent, 1);
+        let remaining = fs::read_dir(&queue_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect::<Vec<_>>();
+        pretty_assertions::assert_eq!(remaining, vec!["0-broken.invalid"]);
+    }
+
+    #[test]
+    fn queue_keeps_only_newest_envelopes() {
+        let dir = tempfile::tempdir().unwrap();
+        for idx in 0..5 {
+            fs::write(dir.path().join(format!("{idx}-thread.envelope")), b"").unwrap();
+        }
+
+        prune_queue(dir.path(), 2);
+
+        let entries = fs::read_dir(dir.path()).unwrap();
+        pretty_assertions::assert_eq!(
+            queued_envelope_paths(entries),
+            vec![
+                dir.path().join("3-thread.envelope"),
+                dir.path().join("4-thread.envelope"),
+            ]
+        );
+    }
+
+    #[test]
+    fn with_max_bytes_keeps_tail() {
+        let fb = CodexFeedback::with_max_bytes(1024);
+        pretty_assertions::assert_eq!(fb.capacity(), 1024);
+
+        let data: Vec<u8> = (0..4096).map(|i| (i % 251) as u8).collect();
+        {
+            let mut w = fb.make_writer().make_writer();
+            for chunk in data.chunks(100) {
+                w.write_all(chunk).unwrap();
+            }
+        }
+        let snap = fb.snapshot(None);
+        pretty_assertions::assert_eq!(snap.as_bytes(), &data[data.len() - 1024..]);
+    }
 }
@@ -0,0 +1,124 @@
+//! Minimal writer for uncompressed ustar archives, enough to bundle feedback
+//! attachments into a single file.
+
+use std::io;
+use std::io::Write;
+
+const BLOCK_SIZE: usize = 512;
+const NAME_LEN: usize = 100;
+
+/// Write `entries` as regular files into a tar archive.
+pub(crate) fn write_archive<W: Write>(
+    mut out: W,
+    entries: &[(String, Vec<u8>)],
+    mtime_secs: u64,
+) -> io::Result<()> {
+    for (name, data) in entries {
+        out.write_all(&header(name, data.len(), mtime_secs)?)?;
+        out.write_all(data)?;
+        let padding = (BLOCK_SIZE - data.len() % BLOCK_SIZE) % BLOCK_SIZE;
+        out.write_all(&vec![0; padding])?;
+    }
+    // An archive ends with two zero blocks.
+    out.write_all(&[0; 2 * BLOCK_SIZE])?;
+    out.flush()
+}
+
+fn header(name: &str, size: usize, mtime_secs: u64) -> io::Result<[u8; BLOCK_SIZE]> {
+    if name.is_empty() || name.len() > NAME_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("tar entry name must be 1-{NAME_LEN} bytes: {name}"),
+        ));
+    }
+    let mut block = [0u8; BLOCK_SIZE];
+    block[..name.len()].copy_from_slice(name.as_bytes());
+    write_octal(&mut block[100..108], 0o644)?;
+    write_octal(&mut block[108..116], 0)?;
+    write_octal(&mut block[116..124], 0)?;
+    write_octal(&mut block[124..136], size as u64)?;
+    write_octal(&mut block[136..148], mtime_secs)?;
+    block[156] = b'0';
+    block[257..263].copy_from_slice(b"ustar\0");
+    block[263..265].copy_from_slice(b"00");
+
+    // The checksum is computed with its own field filled with spaces.
+    block[148..156].fill(b' ');
+    let checksum: u64 = block.iter().map(|&b| u64::from(b)).sum();
+    write_octal(&mut block[148..155], checksum)?;
+    Ok(block)
+}
+
+/// Zero-padded octal followed by a NUL, filling `field`. Fails when `value`
+/// needs more digits than the field holds.
+fn write_octal(field: &mut [u8], value: u64) -> io::Result<()> {
+    let digits = field.len() - 1;
+    let text = format!("{value:0digits$o}");
+    if text.len() > digits {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{value} does not fit in a {digits}-digit tar header field"),
+        ));
+    }
+    field[..digits].copy_from_slice(text.as_bytes());
+    field[digits] = 0;
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) fn read_archive(bytes: &[u8]) -> Vec<(String, Vec<u8>)> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    while offset + BLOCK_SIZE <= bytes.len() {
+        let block = &bytes[offset..offset + BLOCK_SIZE];
+        if block.iter().all(|&b| b == 0) {
+            break;
+        }
+        let name_end = block[..NAME_LEN]
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(NAME_LEN);
+        let name = String::from_utf8_lossy(&block[..name_end]).to_string();
+        let size_field = String::from_utf8_lossy(&block[124..135]).to_string();
+        let size = usize::from_str_radix(&size_field, 8).expect("octal size");
+        let start = offset + BLOCK_SIZE;
+        entries.push((name, bytes[start..start + size].to_vec()));
+        offset = start + size.div_ceil(BLOCK_SIZE) * BLOCK_SIZE;
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn archive_round_trips_entries() {
+        let entries = vec![
+            ("a.log".to_string(), b"hello\n".to_vec()),
+            ("empty.json".to_string(), Vec::new()),
+            ("big.bin".to_string(), vec![7; BLOCK_SIZE + 1]),
+        ];
+        let mut bytes = Vec::new();
+        write_archive(&mut bytes, &entries, 0).unwrap();
+
+        assert_eq!(bytes.len() % BLOCK_SIZE, 0);
+        assert_eq!(read_archive(&bytes), entries);
+    }
+
+    #[test]
+    fn rejects_long_names() {
+        let entries = vec![("x".repeat(NAME_LEN + 1), Vec::new())];
+        let err = write_archive(Vec::new(), &entries, 0).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn rejects_values_that_overflow_header_fields() {
+        let entries = vec![("a.log".to_string(), Vec::new())];
+        // The mtime field holds 11 octal digits, i.e. values below 8^11.
+        let err = write_archive(Vec::new(), &entries, 1 << 33).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
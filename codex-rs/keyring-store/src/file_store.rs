@@ -0,0 +1,257 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::path::PathBuf;
+
+use keyring::Error as KeyringError;
+use tracing::trace;
+
+use crate::CredentialStoreError;
+use crate::KeyringStore;
+
+/// Credentials keyed by service, then account.
+type Credentials = BTreeMap<String, BTreeMap<String, String>>;
+
+/// A [`KeyringStore`] backed by a JSON file, for environments without a
+/// system keyring (CI, headless servers).
+///
+/// The file is created with `0600` permissions on Unix and every operation
+/// holds an advisory lock on it, so concurrent processes sharing the same
+/// path do not clobber each other's writes.
+#[derive(Debug, Clone)]
+pub struct FileKeyringStore {
+    path: PathBuf,
+}
+
+impl FileKeyringStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the credentials under a shared lock. A missing file is empty.
+    fn read(&self) -> Result<Credentials, CredentialStoreError> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Credentials::new());
+            }
+            Err(err) => return Err(io_error(err)),
+        };
+        file.lock_shared().map_err(io_error)?;
+        read_credentials(&mut file)
+    }
+
+    /// Apply `f` to the credentials under an exclusive lock and write the
+    /// result back.
+    fn update<T>(&self, f: impl FnOnce(&mut Credentials) -> T) -> Result<T, CredentialStoreError> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        let mut options = OpenOptions::new();
+        options.read(true).write(true).create(true).truncate(false);
+        #[cfg(unix)]
+        {
+            options.mode(0o600);
+        }
+        let mut file = options.open(&self.path).map_err(io_error)?;
+        file.lock().map_err(io_error)?;
+        #[cfg(unix)]
+        {
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))
+                .map_err(io_error)?;
+        }
+
+        let mut credentials = read_credentials(&mut file)?;
+        let result = f(&mut credentials);
+        let json = serde_json::to_vec_pretty(&credentials).map_err(json_error)?;
+        file.seek(SeekFrom::Start(0)).map_err(io_error)?;
+        file.set_len(0).map_err(io_error)?;
+        file.write_all(&json).map_err(io_error)?;
+        file.flush().map_err(io_error)?;
+        Ok(result)
+    }
+}
+
+impl KeyringStore for FileKeyringStore {
+    fn load(&self, service: &str, account: &str) -> Result<Option<String>, CredentialStoreError> {
+        trace!("file_keyring.load service={service}, account={account}");
+        let mut credentials = self.read()?;
+        Ok(credentials
+            .get_mut(service)
+            .and_then(|accounts| accounts.remove(account)))
+    }
+
+    fn save(&self, service: &str, account: &str, value: &str) -> Result<(), CredentialStoreError> {
+        trace!(
+            "file_keyring.save service={service}, account={account}, value_len={}",
+            value.len()
+        );
+        self.update(|credentials| {
+            credentials
+                .entry(service.to_string())
+                .or_default()
+                .insert(account.to_string(), value.to_string());
+        })
+    }
+
+    fn delete(&self, service: &str, account: &str) -> Result<bool, CredentialStoreError> {
+        trace!("file_keyring.delete service={service}, account={account}");
+        self.update(|credentials| {
+            let Some(accounts) = credentials.get_mut(service) else {
+                return false;
+            };
+            let removed = accounts.remove(account).is_some();
+            if accounts.is_empty() {
+                credentials.remove(service);
+            }
+            removed
+        })
+    }
+
+    fn save_many(&self, service: &str, pairs: &[(&str, &str)]) -> Result<(), CredentialStoreError> {
+        trace!(
+            "file_keyring.save_many service={service}, count={}",
+            pairs.len()
+        );
+        self.update(|credentials| {
+            let entries = credentials.entry(service.to_string()).or_default();
+            for (account, value) in pairs {
+                entries.insert((*account).to_string(), (*value).to_string());
+            }
+        })
+    }
+
+    fn delete_many(
+        &self,
+        service: &str,
+        accounts: &[&str],
+    ) -> Result<Vec<bool>, CredentialStoreError> {
+        trace!(
+            "file_keyring.delete_many service={service}, count={}",
+            accounts.len()
+        );
+        self.update(|credentials| {
+            let Some(entries) = credentials.get_mut(service) else {
+                return vec![false; accounts.len()];
+            };
+            let removed = accounts
+                .iter()
+                .map(|account| entries.remove(*account).is_some())
+                .collect();
+            if entries.is_empty() {
+                credentials.remove(service);
+            }
+            removed
+        })
+    }
+
+    fn list_accounts(&self, service: &str) -> Result<Vec<String>, CredentialStoreError> {
+        let mut credentials = self.read()?;
+        Ok(credentials
+            .remove(service)
+            .map(|accounts| accounts.into_keys().collect())
+            .unwrap_or_default())
+    }
+}
+
+fn read_credentials(file: &mut File) -> Result<Credentials, CredentialStoreError> {
+    let mut contents = String::new();
+    file.read_to_string(&mut contents).map_err(io_error)?;
+    if contents.trim().is_empty() {
+        return Ok(Credentials::new());
+    }
+    serde_json::from_str(&contents).map_err(json_error)
+}
+
+fn io_error(err: std::io::Error) -> CredentialStoreError {
+    CredentialStoreError::new(KeyringError::PlatformFailure(Box::new(err)))
+}
+
+fn json_error(err: serde_json::Error) -> CredentialStoreError {
+    CredentialStoreError::new(KeyringError::PlatformFailure(Box::new(err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[test]
+    fn load_missing_returns_none() {
+        let dir = tempdir().unwrap();
+        let store = FileKeyringStore::new(dir.path().join("credentials.json"));
+
+        assert_eq!(store.load("codex", "alice").unwrap(), None);
+        assert!(!store.path().exists());
+    }
+
+    #[test]
+    fn save_then_load_round_trips() {
+        let dir = tempdir().unwrap();
+        let store = FileKeyringStore::new(dir.path().join("credentials.json"));
+
+        store.save("codex", "alice", "secret-a").unwrap();
+        store.save("codex", "bob", "secret-b").unwrap();
+        store.save("other", "alice", "secret-c").unwrap();
+
+        let reopened = FileKeyringStore::new(store.path());
+        assert_eq!(
+            (
+                reopened.load("codex", "alice").unwrap(),
+                reopened.load("other", "alice").unwrap(),
+                reopened.list_accounts("codex").unwrap(),
+            ),
+            (
+                Some("secret-a".to_string()),
+                Some("secret-c".to_string()),
+                vec!["alice".to_string(), "bob".to_string()],
+            )
+        );
+    }
+
+    #[test]
+    fn delete_removes_only_the_account() {
+        let dir = tempdir().unwrap();
+        let store = FileKeyringStore::new(dir.path().join("credentials.json"));
+        store.save("codex", "alice", "secret-a").unwrap();
+        store.save("codex", "bob", "secret-b").unwrap();
+
+        assert_eq!(
+            (
+                store.delete("codex", "alice").unwrap(),
+                store.delete("codex", "alice").unwrap(),
+                store.load("codex", "alice").unwrap(),
+                store.load("codex", "bob").unwrap(),
+            ),
+            (true, false, None, Some("secret-b".to_string()))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_is_owner_only() {
+        let dir = tempdir().unwrap();
+        let store = FileKeyringStore::new(dir.path().join("credentials.json"));
+        store.save("codex", "alice", "secret-a").unwrap();
+
+        let mode = std::fs::metadata(store.path())
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
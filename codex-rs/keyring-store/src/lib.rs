@@ -5,9 +5,15 @@ use std::fmt;
 use std::fmt::Debug;
 use tracing::trace;
 
+mod file_store;
+
+pub use file_store::FileKeyringStore;
+
 #[derive(Debug)]
 pub enum CredentialStoreError {
     Other(KeyringError),
+    /// The store does not implement the named operation.
+    Unsupported(&'static str),
 }
 
 impl CredentialStoreError {
@@ -18,12 +24,16 @@ impl CredentialStoreError {
     pub fn message(&self) -> String {
         match self {
             Self::Other(error) => error.to_string(),
+            Self::Unsupported(operation) => unsupported_message(operation),
         }
     }
 
     pub fn into_error(self) -> KeyringError {
         match self {
             Self::Other(error) => error,
+            Self::Unsupported(operation) => {
+                KeyringError::PlatformFailure(unsupported_message(operation).into())
+            }
         }
     }
 }
@@ -32,10 +42,15 @@ impl fmt::Display for CredentialStoreError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Other(error) => write!(f, "{error}"),
+            Self::Unsupported(operation) => write!(f, "{}", unsupported_message(operation)),
         }
     }
 }
 
+fn unsupported_message(operation: &str) -> String {
+    format!("{operation} is not supported by this credential store")
+}
+
 impl Error for CredentialStoreError {}
 
 /// Shared credential store abstraction for keyring-backed implementations.
@@ -43,15 +58,69 @@ pub trait KeyringStore: Debug + Send + Sync {
     fn load(&self, service: &str, account: &str) -> Result<Option<String>, CredentialStoreError>;
     fn save(&self, service: &str, account: &str, value: &str) -> Result<(), CredentialStoreError>;
     fn delete(&self, service: &str, account: &str) -> Result<bool, CredentialStoreError>;
+
+    /// List the accounts with a stored credential for `service`.
+    fn list_accounts(&self, _service: &str) -> Result<Vec<String>, CredentialStoreError> {
+        Err(CredentialStoreError::Unsupported("list_accounts"))
+    }
+
+    /// Save several `(account, value)` pairs for `service`. Stores where a
+    /// batch is cheaper than individual saves should override this.
+    fn save_many(&self, service: &str, pairs: &[(&str, &str)]) -> Result<(), CredentialStoreError> {
+        for (account, value) in pairs {
+            self.save(service, account, value)?;
+        }
+        Ok(())
+    }
+
+    /// Delete several accounts for `service`, returning whether each one
+    /// existed. Stores where a batch is cheaper should override this.
+    fn delete_many(
+        &self,
+        service: &str,
+        accounts: &[&str],
+    ) -> Result<Vec<bool>, CredentialStoreError> {
+        accounts
+            .iter()
+            .map(|account| self.delete(service, account))
+            .collect()
+    }
 }
 
-#[derive(Debug)]
-pub struct DefaultKeyringStore;
+/// A [`KeyringStore`] backed by the platform keyring.
+///
+/// Entries are stored under the service name as given unless the store was
+/// created with [`DefaultKeyringStore::with_namespace`], which prefixes it so
+/// separate installs sharing one keyring do not overwrite each other.
+#[derive(Debug, Default, Clone)]
+pub struct DefaultKeyringStore {
+    namespace: Option<String>,
+}
+
+impl DefaultKeyringStore {
+    pub fn with_namespace(namespace: String) -> Self {
+        Self {
+            namespace: Some(namespace),
+        }
+    }
+
+    /// The keyring service name actually used for `service`.
+    fn service_name(&self, service: &str) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{namespace}:{service}"),
+            None => service.to_string(),
+        }
+    }
+
+    fn entry(&self, service: &str, account: &str) -> Result<Entry, CredentialStoreError> {
+        Entry::new(&self.service_name(service), account).map_err(CredentialStoreError::new)
+    }
+}
 
 impl KeyringStore for DefaultKeyringStore {
     fn load(&self, service: &str, account: &str) -> Result<Option<String>, CredentialStoreError> {
         trace!("keyring.load start, service={service}, account={account}");
-        let entry = Entry::new(service, account).map_err(CredentialStoreError::new)?;
+        let entry = self.entry(service, account)?;
         match entry.get_password() {
             Ok(password) => {
                 trace!("keyring.load success, service={service}, account={account}");
@@ -73,7 +142,7 @@ impl KeyringStore for DefaultKeyringStore {
             "keyring.save start, service={service}, account={account}, value_len={}",
             value.len()
         );
-        let entry = Entry::new(service, account).map_err(CredentialStoreError::new)?;
+        let entry = self.entry(service, account)?;
         match entry.set_password(value) {
             Ok(()) => {
                 trace!("keyring.save success, service={service}, account={account}");
@@ -88,7 +157,7 @@ impl KeyringStore for DefaultKeyringStore {
 
     fn delete(&self, service: &str, account: &str) -> Result<bool, CredentialStoreError> {
         trace!("keyring.delete start, service={service}, account={account}");
-        let entry = Entry::new(service, account).map_err(CredentialStoreError::new)?;
+        let entry = self.entry(service, account)?;
         match entry.delete_credential() {
             Ok(()) => {
                 trace!("keyring.delete success, service={service}, account={account}");
@@ -104,6 +173,44 @@ impl KeyringStore for DefaultKeyringStore {
             }
         }
     }
+
+    fn list_accounts(&self, service: &str) -> Result<Vec<String>, CredentialStoreError> {
+        // The keyring crate has no cross-platform search API, so the system
+        // keyring cannot enumerate accounts.
+        trace!("keyring.list_accounts unsupported, service={service}");
+        Err(CredentialStoreError::Unsupported("list_accounts"))
+    }
+}
+
+#[cfg(test)]
+mod default_store_tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn default_store_is_unprefixed() {
+        assert_eq!(
+            DefaultKeyringStore::default().service_name("Codex Auth"),
+            "Codex Auth"
+        );
+    }
+
+    #[test]
+    fn namespaces_use_separate_services() {
+        let first = DefaultKeyringStore::with_namespace("codex-a".to_string());
+        let second = DefaultKeyringStore::with_namespace("codex-b".to_string());
+
+        assert_eq!(
+            (
+                first.service_name("Codex Auth"),
+                second.service_name("Codex Auth"),
+            ),
+            (
+                "codex-a:Codex Auth".to_string(),
+                "codex-b:Codex Auth".to_string()
+            )
+        );
+    }
 }
 
 pub mod tests {
@@ -222,5 +329,116 @@ pub mod tests {
             guard.remove(account);
             Ok(removed)
         }
+
+        fn list_accounts(&self, _service: &str) -> Result<Vec<String>, CredentialStoreError> {
+            let credentials: Vec<(String, Arc<MockCredential>)> = {
+                let guard = self
+                    .credentials
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner);
+                guard
+                    .iter()
+                    .map(|(account, credential)| (account.clone(), credential.clone()))
+                    .collect()
+            };
+
+            let mut accounts = Vec::new();
+            for (account, credential) in credentials {
+                match credential.get_password() {
+                    Ok(_) => accounts.push(account),
+                    Err(KeyringError::NoEntry) => {}
+                    Err(error) => return Err(CredentialStoreError::new(error)),
+                }
+            }
+            accounts.sort();
+            Ok(accounts)
+        }
+
+        fn save_many(
+            &self,
+            _service: &str,
+            pairs: &[(&str, &str)],
+        ) -> Result<(), CredentialStoreError> {
+            let mut guard = self
+                .credentials
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            for (account, value) in pairs {
+                guard
+                    .entry((*account).to_string())
+                    .or_insert_with(|| Arc::new(MockCredential::default()))
+                    .set_password(value)
+                    .map_err(CredentialStoreError::new)?;
+            }
+            Ok(())
+        }
+
+        fn delete_many(
+            &self,
+            _service: &str,
+            accounts: &[&str],
+        ) -> Result<Vec<bool>, CredentialStoreError> {
+            let mut guard = self
+                .credentials
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            let mut removed = Vec::with_capacity(accounts.len());
+            for account in accounts {
+                let Some(credential) = guard.get(*account) else {
+                    removed.push(false);
+                    continue;
+                };
+                let existed = match credential.delete_credential() {
+                    Ok(()) => true,
+                    Err(KeyringError::NoEntry) => false,
+                    Err(error) => return Err(CredentialStoreError::new(error)),
+                };
+                guard.remove(*account);
+                removed.push(existed);
+            }
+            Ok(removed)
+        }
+    }
+
+    #[cfg(test)]
+    mod mock_store_tests {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn list_accounts_returns_saved_accounts() {
+            let store = MockKeyringStore::default();
+            store.save("codex", "bob", "secret-b").unwrap();
+            store.save("codex", "alice", "secret-a").unwrap();
+
+            assert_eq!(
+                store.list_accounts("codex").unwrap(),
+                vec!["alice".to_string(), "bob".to_string()]
+            );
+        }
+
+        #[test]
+        fn save_many_then_load_each_account() {
+            let store = MockKeyringStore::default();
+            store
+                .save_many("codex", &[("alice", "secret-a"), ("bob", "secret-b")])
+                .unwrap();
+
+            assert_eq!(
+                (
+                    store.load("codex", "alice").unwrap(),
+                    store.load("codex", "bob").unwrap(),
+                ),
+                (Some("secret-a".to_string()), Some("secret-b".to_string()))
+            );
+            assert_eq!(
+                store.delete_many("codex", &["alice", "carol"]).unwrap(),
+                vec![true, false]
+            );
+            assert_eq!(
+                store.list_accounts("codex").unwrap(),
+                vec!["bob".to_string()]
+            );
+        }
     }
 }
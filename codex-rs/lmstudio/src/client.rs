@@ -2,6 +2,8 @@ use codex_core::LMSTUDIO_OSS_PROVIDER_ID;
 use codex_core::config::Config;
 use std::io;
 use std::path::Path;
+use std::time::Duration;
+use tokio::time::Instant;
 
 #[derive(Clone)]
 pub struct LMStudioClient {
@@ -9,6 +11,8 @@ pub struct LMStudioClient {
     base_url: String,
 }
 
+const LOAD_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 const LMSTUDIO_CONNECTION_ERROR: &str = "LM Studio is not responding. Install from https://lmstudio.ai/download and run 'lms server start'.";
 
 impl LMStudioClient {
@@ -29,17 +33,36 @@ impl LMStudioClient {
             )
         })?;
 
-        let client = reqwest::Client::builder()
-            .connect_timeout(std::time::Duration::from_secs(5))
-            .build()
-            .unwrap_or_else(|_| reqwest::Client::new());
+        Self::try_from_base_url(base_url).await
+    }
 
-        let client = LMStudioClient {
-            client,
+    /// Client for an LM Studio server at `base_url` (the OpenAI-compatible
+    /// root, e.g. `http://192.168.1.10:1234/v1`), bypassing provider config.
+    pub fn with_base_url(base_url: &str) -> io::Result<Self> {
+        let parsed = reqwest::Url::parse(base_url).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid LM Studio base URL '{base_url}': {e}"),
+            )
+        })?;
+        if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "invalid LM Studio base URL '{base_url}': expected an http(s) URL with a host"
+                ),
+            ));
+        }
+        Ok(Self {
+            client: build_http_client(),
             base_url: base_url.to_string(),
-        };
-        client.check_server().await?;
+        })
+    }
 
+    /// Like [`LMStudioClient::with_base_url`], but also checks that the server responds.
+    pub async fn try_from_base_url(base_url: &str) -> io::Result<Self> {
+        let client = Self::with_base_url(base_url)?;
+        client.check_server().await?;
         Ok(client)
     }
 
@@ -91,6 +114,70 @@ impl LMStudioClient {
         }
     }
 
+    // LM Studio's native REST API is served next to the OpenAI-compatible `/v1` routes.
+    fn rest_api_url(&self, path: &str) -> String {
+        let base_url = self.base_url.trim_end_matches('/');
+        let root = base_url.strip_suffix("/v1").unwrap_or(base_url);
+        format!("{root}/api/v0/{path}")
+    }
+
+    // Whether LM Studio reports `model` as loaded into memory. Failing to reach
+    // the server at all is reported as `ConnectionRefused`.
+    async fn is_model_loaded(&self, model: &str) -> io::Result<bool> {
+        let url = self.rest_api_url("models");
+        let response = self.client.get(&url).send().await.map_err(|e| {
+            let kind = if e.is_connect() {
+                io::ErrorKind::ConnectionRefused
+            } else {
+                io::ErrorKind::Other
+            };
+            io::Error::new(kind, format!("Request failed: {e}"))
+        })?;
+
+        if !response.status().is_success() {
+            return Err(io::Error::other(format!(
+                "Failed to fetch model status: {}",
+                response.status()
+            )));
+        }
+        let json: serde_json::Value = response.json().await.map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("JSON parse error: {e}"))
+        })?;
+        let loaded = json["data"]
+            .as_array()
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "No 'data' array in response")
+            })?
+            .iter()
+            .any(|entry| entry["id"].as_str() == Some(model) && entry["state"] == "loaded");
+        Ok(loaded)
+    }
+
+    /// Poll LM Studio until `model` reports as loaded. Fails with
+    /// [`io::ErrorKind::TimedOut`] once `timeout` elapses. Only "not loaded
+    /// yet" and an unreachable server are retried; an error response (e.g. a
+    /// server without the status endpoint) fails right away.
+    pub async fn wait_until_loaded(&self, model: &str, timeout: Duration) -> io::Result<()> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.is_model_loaded(model).await {
+                Ok(true) => return Ok(()),
+                Ok(false) => {}
+                Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => {
+                    tracing::debug!("Failed to check whether model {model} is loaded: {e}");
+                }
+                Err(e) => return Err(e),
+            }
+            if Instant::now() + LOAD_POLL_INTERVAL > deadline {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("model {model} did not finish loading within {timeout:?}"),
+                ));
+            }
+            tokio::time::sleep(LOAD_POLL_INTERVAL).await;
+        }
+    }
+
     // Return the list of models available on the LM Studio server.
     pub async fn fetch_models(&self) -> io::Result<Vec<String>> {
         let url = format!("{}/models", self.base_url.trim_end_matches('/'));
@@ -166,17 +253,34 @@ impl LMStudioClient {
     }
 
     pub async fn download_model(&self, model: &str) -> std::io::Result<()> {
+        self.download_model_with_progress(model, |_, _| {}).await
+    }
+
+    /// Download `model` with `lms get`, calling `progress` with the bytes
+    /// downloaded so far and the total size when `lms` reports it.
+    pub async fn download_model_with_progress(
+        &self,
+        model: &str,
+        mut progress: impl FnMut(u64, Option<u64>),
+    ) -> std::io::Result<()> {
         let lms = Self::find_lms()?;
         eprintln!("Downloading model: {model}");
 
-        let status = std::process::Command::new(&lms)
+        let mut child = std::process::Command::new(&lms)
             .args(["get", "--yes", model])
             .stdout(std::process::Stdio::inherit())
-            .stderr(std::process::Stdio::null())
-            .status()
+            .stderr(std::process::Stdio::piped())
+            .spawn()
             .map_err(|e| {
                 std::io::Error::other(format!("Failed to execute '{lms} get --yes {model}': {e}"))
             })?;
+        // `lms` draws its progress bar on stderr.
+        if let Some(stderr) = child.stderr.take() {
+            report_download_progress(stderr, &mut progress)?;
+        }
+        let status = child.wait().map_err(|e| {
+            std::io::Error::other(format!("Failed to wait for '{lms} get --yes {model}': {e}"))
+        })?;
 
         if !status.success() {
             return Err(std::io::Error::other(format!(
@@ -192,17 +296,85 @@ impl LMStudioClient {
     /// Low-level constructor given a raw host root, e.g. "http://localhost:1234".
     #[cfg(test)]
     fn from_host_root(host_root: impl Into<String>) -> Self {
-        let client = reqwest::Client::builder()
-            .connect_timeout(std::time::Duration::from_secs(5))
-            .build()
-            .unwrap_or_else(|_| reqwest::Client::new());
         Self {
-            client,
+            client: build_http_client(),
             base_url: host_root.into(),
         }
     }
 }
 
+fn build_http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .connect_timeout(std::time::Duration::from_secs(5))
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+}
+
+// Read `lms` progress output (lines separated by `\r` or `\n`) and report
+// each `<downloaded> / <total>` pair. Sizes never go backwards.
+fn report_download_progress(
+    mut reader: impl io::Read,
+    progress: &mut impl FnMut(u64, Option<u64>),
+) -> io::Result<()> {
+    let mut line = Vec::new();
+    let mut buf = [0u8; 4096];
+    let mut last = 0;
+    let mut report = |line: &[u8]| {
+        if let Some((downloaded, total)) = parse_download_progress(&String::from_utf8_lossy(line))
+            && downloaded >= last
+        {
+            last = downloaded;
+            progress(downloaded, total);
+        }
+    };
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        for &byte in &buf[..n] {
+            if byte == b'\r' || byte == b'\n' {
+                report(&line);
+                line.clear();
+            } else {
+                line.push(byte);
+            }
+        }
+    }
+    report(&line);
+    Ok(())
+}
+
+// Parse a progress line such as `45.00% | 5.40 GB / 12.00 GB | 25 MB/s`.
+fn parse_download_progress(line: &str) -> Option<(u64, Option<u64>)> {
+    let (before, after) = line.split_once(" / ")?;
+    let mut before = before.split_whitespace().rev();
+    let unit = before.next()?;
+    let downloaded = parse_size(before.next()?, unit)?;
+    let mut after = after.split_whitespace();
+    let total = match (after.next(), after.next()) {
+        (Some(value), Some(unit)) => parse_size(value, unit),
+        _ => None,
+    };
+    Some((downloaded, total))
+}
+
+fn parse_size(value: &str, unit: &str) -> Option<u64> {
+    let value: f64 = value.parse().ok()?;
+    let multiplier = match unit {
+        "B" => 1.0,
+        "KB" | "kB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((value * multiplier).round() as u64)
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::expect_used, clippy::unwrap_used)]
@@ -351,6 +523,126 @@ mod tests {
         );
     }
 
+    fn model_status(state: &str) -> wiremock::ResponseTemplate {
+        wiremock::ResponseTemplate::new(200).set_body_raw(
+            serde_json::json!({
+                "data": [
+                    {"id": "other/model", "state": "loaded"},
+                    {"id": "openai/gpt-oss-20b", "state": state},
+                ]
+            })
+            .to_string(),
+            "application/json",
+        )
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_loaded_polls_until_loaded() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/api/v0/models"))
+            .respond_with(model_status("not-loaded"))
+            .up_to_n_times(2)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/api/v0/models"))
+            .respond_with(model_status("loaded"))
+            .mount(&server)
+            .await;
+
+        let client = LMStudioClient::from_host_root(format!("{}/v1", server.uri()));
+        client
+            .wait_until_loaded("openai/gpt-oss-20b", Duration::from_secs(10))
+            .await
+            .expect("model should load");
+
+        let requests = server.received_requests().await.expect("requests recorded");
+        assert_eq!(requests.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_loaded_times_out() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/api/v0/models"))
+            .respond_with(model_status("not-loaded"))
+            .mount(&server)
+            .await;
+
+        let client = LMStudioClient::from_host_root(format!("{}/v1", server.uri()));
+        let err = client
+            .wait_until_loaded("openai/gpt-oss-20b", Duration::from_millis(100))
+            .await
+            .expect_err("model never loads");
+
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_loaded_fails_fast_without_status_endpoint() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/api/v0/models"))
+            .respond_with(wiremock::ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = LMStudioClient::from_host_root(format!("{}/v1", server.uri()));
+        let err = client
+            .wait_until_loaded("openai/gpt-oss-20b", Duration::from_secs(10))
+            .await
+            .expect_err("missing endpoint should fail");
+
+        let requests = server.received_requests().await.expect("requests recorded");
+        assert_eq!(
+            (err.to_string(), requests.len()),
+            ("Failed to fetch model status: 404 Not Found".to_string(), 1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_loaded_retries_unreachable_server() {
+        // Nothing listens on a port we just released.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let addr = listener.local_addr().expect("local addr");
+        drop(listener);
+
+        let client = LMStudioClient::from_host_root(format!("http://{addr}/v1"));
+        let err = client
+            .wait_until_loaded("openai/gpt-oss-20b", Duration::from_millis(100))
+            .await
+            .expect_err("server never comes up");
+
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn test_report_download_progress_is_monotonic() {
+        let output = "Resolving model...\n\
+            [=>        ]  10.00% | 1.00 MB / 10.00 MB | 2 MB/s\r\
+            [===>      ]  35.00% | 3.50 MB / 10.00 MB | 2 MB/s\r\
+            [===>      ]  30.00% | 3.00 MB / 10.00 MB | 2 MB/s\r\
+            [==========] 100.00% | 10.00 MB / 10.00 MB | 2 MB/s\n\
+            Download complete\n";
+
+        let mut seen = Vec::new();
+        report_download_progress(output.as_bytes(), &mut |downloaded, total| {
+            seen.push((downloaded, total));
+        })
+        .expect("read progress");
+
+        assert_eq!(
+            seen,
+            vec![
+                (1_000_000, Some(10_000_000)),
+                (3_500_000, Some(10_000_000)),
+                (10_000_000, Some(10_000_000)),
+            ]
+        );
+    }
+
     #[test]
     fn test_find_lms() {
         let result = LMStudioClient::find_lms();
@@ -386,6 +678,35 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_with_base_url_is_used_for_fetch_models() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/custom/v1/models"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_raw(
+                serde_json::json!({"data": [{"id": "local/model"}]}).to_string(),
+                "application/json",
+            ))
+            .mount(&server)
+            .await;
+
+        let client = LMStudioClient::with_base_url(&format!("{}/custom/v1", server.uri()))
+            .expect("valid base url");
+        let models = client.fetch_models().await.expect("fetch models");
+
+        assert_eq!(models, vec!["local/model".to_string()]);
+    }
+
+    #[test]
+    fn test_with_base_url_rejects_malformed_urls() {
+        for url in ["localhost:1234", "not a url", "ftp://example.com/v1"] {
+            let err = LMStudioClient::with_base_url(url)
+                .err()
+                .expect("malformed url should be rejected");
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{url}");
+        }
+    }
+
     #[test]
     fn test_from_host_root() {
         let client = LMStudioClient::from_host_root("http://localhost:1234");
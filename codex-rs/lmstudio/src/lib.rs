@@ -1,19 +1,36 @@
 mod client;
 
 pub use client::LMStudioClient;
+pub use codex_core::LMSTUDIO_BASE_URL_ENV_VAR;
 use codex_core::config::Config;
+use std::time::Duration;
 
 /// Default OSS model to use when `--oss` is passed without an explicit `-m`.
 pub const DEFAULT_OSS_MODEL: &str = "openai/gpt-oss-20b";
 
+/// How long [`ensure_oss_ready`] waits for the model to report as loaded.
+const MODEL_LOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
 /// Prepare the local OSS environment when `--oss` is selected.
 ///
 /// - Ensures a local LM Studio server is reachable.
 /// - Checks if the model exists locally and downloads it if missing.
+/// - Loads the model and waits (best effort) until LM Studio reports it loaded.
 pub async fn ensure_oss_ready(config: &Config) -> std::io::Result<()> {
     let model: &str = config.model.as_ref();
 
-    // Verify local LM Studio is reachable.
+    // The provider reads LMSTUDIO_BASE_URL_ENV_VAR without validating it, so
+    // reject a malformed override here with an error that names the variable.
+    if let Ok(base_url) = std::env::var(LMSTUDIO_BASE_URL_ENV_VAR)
+        && !base_url.trim().is_empty()
+    {
+        LMStudioClient::with_base_url(base_url.trim()).map_err(|e| {
+            std::io::Error::new(e.kind(), format!("{LMSTUDIO_BASE_URL_ENV_VAR}: {e}"))
+        })?;
+    }
+
+    // Verify local LM Studio is reachable. The built-in provider already
+    // honors LMSTUDIO_BASE_URL_ENV_VAR, so requests go to the same server.
     let lmstudio_client = LMStudioClient::try_from_provider(config).await?;
 
     match lmstudio_client.fetch_models().await {
@@ -39,5 +56,14 @@ pub async fn ensure_oss_ready(config: &Config) -> std::io::Result<()> {
         }
     });
 
+    // Best effort: give the model a chance to finish loading so the first
+    // request does not fail with "model not ready".
+    if let Err(e) = lmstudio_client
+        .wait_until_loaded(model, MODEL_LOAD_TIMEOUT)
+        .await
+    {
+        tracing::warn!("Model {model} is not ready yet: {e}");
+    }
+
     Ok(())
 }
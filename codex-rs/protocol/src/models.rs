@@ -263,7 +263,11 @@ impl From<Vec<UserInput>> for ResponseInputItem {
                             image_url: image.into_data_url(),
                         },
                         Err(err) => {
-                            if matches!(&err, ImageProcessingError::Read { .. }) {
+                            if matches!(
+                                &err,
+                                ImageProcessingError::Read { .. }
+                                    | ImageProcessingError::TooLarge { .. }
+                            ) {
                                 local_image_error_placeholder(&path, &err)
                             } else if err.is_invalid_image() {
                                 invalid_image_error_placeholder(&path, &err)
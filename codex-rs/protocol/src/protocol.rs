@@ -275,6 +275,11 @@ pub enum SandboxPolicy {
         /// writable roots on UNIX. Defaults to `false`.
         #[serde(default)]
         exclude_slash_tmp: bool,
+
+        /// Hosts that stay reachable while outbound network access is
+        /// otherwise blocked. Only the Windows sandbox honors this today.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        allow_hosts: Vec<String>,
     },
 }
 
@@ -332,6 +337,7 @@ impl SandboxPolicy {
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            allow_hosts: Vec::new(),
         }
     }
 
@@ -356,6 +362,19 @@ impl SandboxPolicy {
         }
     }
 
+    /// Hosts that stay reachable while the rest of the network is blocked.
+    /// Only `WorkspaceWrite` policies without full network access carry any.
+    pub fn network_allow_hosts(&self) -> &[String] {
+        match self {
+            SandboxPolicy::WorkspaceWrite {
+                network_access: false,
+                allow_hosts,
+                ..
+            } => allow_hosts,
+            _ => &[],
+        }
+    }
+
     /// Returns the list of writable roots (tailored to the current working
     /// directory) together with subpaths that should remain read‑only under
     /// each writable root.
@@ -368,6 +387,7 @@ impl SandboxPolicy {
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
                 network_access: _,
+                allow_hosts: _,
             } => {
                 // Start from explicitly configured writable roots.
                 let mut roots: Vec<PathBuf> = writable_roots.clone();
@@ -1,12 +1,19 @@
+use std::collections::HashSet;
 use std::fs::File;
 use std::fs::{self};
+use std::io::Read;
 use std::io::Write;
 use std::net::SocketAddr;
 use std::net::TcpListener;
 use std::path::Path;
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
+use std::time::Instant;
 
 use anyhow::Context;
 use anyhow::Result;
@@ -26,9 +33,15 @@ use tiny_http::Request;
 use tiny_http::Response;
 use tiny_http::Server;
 use tiny_http::StatusCode;
+use tracing::info;
 
 mod read_api_key;
+mod signals;
 use read_api_key::read_auth_header_from_stdin;
+use signals::block_shutdown_signals;
+
+/// How long to wait for in-flight requests after a shutdown signal.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
 
 /// CLI arguments for the proxy.
 #[derive(Debug, Clone, Parser)]
@@ -38,7 +51,12 @@ pub struct Args {
     #[arg(long)]
     pub port: Option<u16>,
 
-    /// Path to a JSON file to write startup info (single line). Includes {"port": <u16>}.
+    /// Listen on this Unix domain socket instead of TCP (Unix only).
+    #[arg(long, value_name = "PATH", conflicts_with = "port")]
+    pub uds: Option<PathBuf>,
+
+    /// Path to a JSON file to write startup info (single line). Includes {"port": <u16>},
+    /// or {"socket": <path>} when listening on a Unix domain socket.
     #[arg(long, value_name = "FILE")]
     pub server_info: Option<PathBuf>,
 
@@ -46,65 +64,315 @@ pub struct Args {
     #[arg(long)]
     pub http_shutdown: bool,
 
+    /// Enable a JSON status endpoint at GET /status for liveness checks.
+    #[arg(long)]
+    pub status_endpoint: bool,
+
     /// Absolute URL the proxy should forward requests to (defaults to OpenAI).
     #[arg(long, default_value = "https://api.openai.com/v1/responses")]
     pub upstream_url: String,
+
+    /// Additional `METHOD /path` pair to forward (repeatable). When omitted, only
+    /// `POST /v1/responses` is forwarded.
+    #[arg(long = "allow-path", value_name = "METHOD PATH", value_parser = parse_allowed_path)]
+    pub allow_paths: Vec<AllowedPath>,
+
+    /// Request header to drop instead of forwarding upstream, matched
+    /// case-insensitively (repeatable), e.g. `Cookie`.
+    #[arg(long = "strip-header", value_name = "NAME", value_parser = parse_header_name)]
+    pub strip_headers: Vec<HeaderName>,
+
+    /// Log method, path, status, latency and byte counts for each forwarded
+    /// request to stderr. The Authorization header is never logged.
+    #[arg(long)]
+    pub log_requests: bool,
+
+    /// Retry upstream 429/500/502/503/504 responses up to this many times.
+    #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(i64).range(0..=16))]
+    pub max_retries: i64,
+
+    /// Delay before the first retry, doubled for each subsequent attempt.
+    #[arg(long, default_value_t = 250, value_parser = clap::value_parser!(i64).range(0..))]
+    pub retry_base_ms: i64,
+
+    /// Reject request bodies larger than this many bytes with `413`.
+    #[arg(long, default_value_t = DEFAULT_MAX_BODY_BYTES, value_parser = clap::value_parser!(i64).range(0..))]
+    pub max_body_bytes: i64,
+
+    /// Keep at most this many idle upstream connections per host for reuse.
+    /// Defaults to no limit.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(i64).range(0..))]
+    pub pool_max_idle_per_host: Option<i64>,
+
+    /// Close idle upstream connections after this many seconds. Defaults to 90.
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(i64).range(1..))]
+    pub pool_idle_timeout_secs: Option<i64>,
+
+    /// PEM file with certificates to trust for the upstream connection, in
+    /// addition to the built-in roots (see `--upstream-ca-only`).
+    #[arg(long, value_name = "PATH")]
+    pub upstream_ca: Option<PathBuf>,
+
+    /// Trust only the `--upstream-ca` certificates, pinning the upstream.
+    #[arg(long, requires = "upstream_ca")]
+    pub upstream_ca_only: bool,
+}
+
+const DEFAULT_MAX_BODY_BYTES: i64 = 64 * 1024 * 1024;
+
+const DEFAULT_PATH: &str = "/v1/responses";
+
+/// A request method and exact path (no query string) the proxy will forward.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AllowedPath {
+    pub method: Method,
+    pub path: String,
+}
+
+fn parse_header_name(value: &str) -> Result<HeaderName, String> {
+    // `HeaderName` normalizes to lowercase, so comparisons are case-insensitive.
+    HeaderName::from_bytes(value.trim().as_bytes())
+        .map_err(|_| format!("invalid header name `{value}`"))
+}
+
+/// Parses `"METHOD /path"`, e.g. `"POST /v1/chat/completions"`.
+fn parse_allowed_path(value: &str) -> Result<AllowedPath, String> {
+    let (method, path) = value
+        .trim()
+        .split_once(char::is_whitespace)
+        .ok_or_else(|| format!("expected `METHOD /path`, got `{value}`"))?;
+    let method = Method::from_str(&method.to_ascii_uppercase())
+        .map_err(|()| format!("invalid method `{method}`"))?;
+    let path = path.trim();
+    if !path.starts_with('/') || path.contains('?') {
+        return Err(format!(
+            "path must start with `/` and have no query string, got `{path}`"
+        ));
+    }
+    Ok(AllowedPath {
+        method,
+        path: path.to_string(),
+    })
 }
 
 #[derive(Serialize)]
 struct ServerInfo {
-    port: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    socket: Option<PathBuf>,
+    pid: u32,
+}
+
+/// Body of `GET /status`.
+#[derive(Serialize)]
+struct StatusInfo {
     pid: u32,
+    uptime_secs: i64,
+    requests_forwarded: i64,
+}
+
+/// Where the proxy is listening.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum BoundAddr {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl std::fmt::Display for BoundAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "{addr}"),
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
 }
 
 struct ForwardConfig {
     upstream_url: Url,
     host_header: HeaderValue,
+    allowed_paths: HashSet<AllowedPath>,
+    strip_headers: HashSet<HeaderName>,
+    log_requests: bool,
+    max_retries: i64,
+    retry_base: Duration,
+    max_body_bytes: usize,
+    status_endpoint: bool,
+    started: Instant,
+    /// Requests that got a response from upstream, reported by `/status`.
+    requests_forwarded: AtomicI64,
+}
+
+impl ForwardConfig {
+    fn from_args(args: &Args) -> Result<Self> {
+        let upstream_url = Url::parse(&args.upstream_url).context("parsing --upstream-url")?;
+        let host = match (upstream_url.host_str(), upstream_url.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_string(),
+            _ => return Err(anyhow!("upstream URL must include a host")),
+        };
+        let host_header =
+            HeaderValue::from_str(&host).context("constructing Host header from upstream URL")?;
+
+        let allowed_paths = if args.allow_paths.is_empty() {
+            HashSet::from([AllowedPath {
+                method: Method::Post,
+                path: DEFAULT_PATH.to_string(),
+            }])
+        } else {
+            args.allow_paths.iter().cloned().collect()
+        };
+
+        Ok(Self {
+            upstream_url,
+            host_header,
+            allowed_paths,
+            strip_headers: args.strip_headers.iter().cloned().collect(),
+            log_requests: args.log_requests,
+            max_retries: args.max_retries,
+            retry_base: Duration::from_millis(args.retry_base_ms as u64),
+            max_body_bytes: usize::try_from(args.max_body_bytes).unwrap_or(usize::MAX),
+            status_endpoint: args.status_endpoint,
+            started: Instant::now(),
+            requests_forwarded: AtomicI64::new(0),
+        })
+    }
+
+    /// `/v1/responses` goes to `--upstream-url` verbatim (which may point at a
+    /// differently named deployment); other paths replace its path component.
+    fn upstream_url_for(&self, path: &str) -> Url {
+        let mut url = self.upstream_url.clone();
+        if path != DEFAULT_PATH {
+            url.set_path(path);
+        }
+        url
+    }
+}
+
+/// Connection pool settings for the upstream client; `None` keeps reqwest's default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ClientConfig {
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    upstream_ca: Option<PathBuf>,
+    upstream_ca_only: bool,
+}
+
+impl ClientConfig {
+    fn from_args(args: &Args) -> Self {
+        Self {
+            pool_max_idle_per_host: args
+                .pool_max_idle_per_host
+                .map(|n| usize::try_from(n).unwrap_or(usize::MAX)),
+            pool_idle_timeout: args
+                .pool_idle_timeout_secs
+                .map(|secs| Duration::from_secs(secs as u64)),
+            upstream_ca: args.upstream_ca.clone(),
+            upstream_ca_only: args.upstream_ca_only,
+        }
+    }
+
+    fn build(&self) -> Result<Client> {
+        let mut builder = Client::builder()
+            // Disable reqwest's 30s default so long-lived response streams keep flowing.
+            .timeout(None::<Duration>);
+        if let Some(max_idle) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if let Some(path) = self.upstream_ca.as_deref() {
+            let pem = fs::read(path)
+                .with_context(|| format!("reading --upstream-ca {}", path.display()))?;
+            let certs = reqwest::Certificate::from_pem_bundle(&pem)
+                .with_context(|| format!("parsing --upstream-ca {}", path.display()))?;
+            if certs.is_empty() {
+                return Err(anyhow!(
+                    "--upstream-ca {} contains no PEM certificates",
+                    path.display()
+                ));
+            }
+            for cert in certs {
+                builder = builder.add_root_certificate(cert);
+            }
+            builder = builder.tls_built_in_root_certs(!self.upstream_ca_only);
+        }
+        builder.build().context("building reqwest client")
+    }
 }
 
 /// Entry point for the library main, for parity with other crates.
 pub fn run_main(args: Args) -> Result<()> {
+    // Read the key while SIGINT/SIGTERM still have their default action, so
+    // interrupting a proxy that waits on stdin terminates it.
     let auth_header = read_auth_header_from_stdin()?;
+    // Must happen before any threads are spawned so they inherit the mask.
+    let shutdown_signals = block_shutdown_signals().context("blocking shutdown signals")?;
+    let forward_config = Arc::new(ForwardConfig::from_args(&args)?);
+    let client = Arc::new(ClientConfig::from_args(&args).build()?);
+    if args.log_requests {
+        let _ = tracing_subscriber::fmt()
+            .with_writer(std::io::stderr)
+            .try_init();
+    }
 
-    let upstream_url = Url::parse(&args.upstream_url).context("parsing --upstream-url")?;
-    let host = match (upstream_url.host_str(), upstream_url.port()) {
-        (Some(host), Some(port)) => format!("{host}:{port}"),
-        (Some(host), None) => host.to_string(),
-        _ => return Err(anyhow!("upstream URL must include a host")),
-    };
-    let host_header =
-        HeaderValue::from_str(&host).context("constructing Host header from upstream URL")?;
-
-    let forward_config = Arc::new(ForwardConfig {
-        upstream_url,
-        host_header,
-    });
-
-    let (listener, bound_addr) = bind_listener(args.port)?;
+    let (server, bound_addr) = bind_server(&args)?;
     if let Some(path) = args.server_info.as_ref() {
-        write_server_info(path, bound_addr.port())?;
+        write_server_info(path, &bound_addr)?;
     }
-    let server = Server::from_listener(listener, None)
-        .map_err(|err| anyhow!("creating HTTP server: {err}"))?;
-    let client = Arc::new(
-        Client::builder()
-            // Disable reqwest's 30s default so long-lived response streams keep flowing.
-            .timeout(None::<Duration>)
-            .build()
-            .context("building reqwest client")?,
-    );
+    let server = Arc::new(server);
+    let shutdown_requested = Arc::new(AtomicBool::new(false));
+    shutdown_signals.spawn_handler(Arc::clone(&server), Arc::clone(&shutdown_requested));
 
     eprintln!("responses-api-proxy listening on {bound_addr}");
 
-    let http_shutdown = args.http_shutdown;
+    serve(
+        &server,
+        &client,
+        auth_header,
+        &forward_config,
+        args.http_shutdown,
+    );
+
+    if shutdown_requested.load(Ordering::SeqCst) {
+        Ok(())
+    } else {
+        Err(anyhow!("server stopped unexpectedly"))
+    }
+}
+
+/// Accept requests until `server` is unblocked, then give in-flight requests
+/// up to [`SHUTDOWN_GRACE`] to finish.
+fn serve(
+    server: &Server,
+    client: &Arc<Client>,
+    auth_header: &'static str,
+    forward_config: &Arc<ForwardConfig>,
+    http_shutdown: bool,
+) {
+    // Worker threads inherit the caller's subscriber so request logs are not lost.
+    let dispatch = tracing::dispatcher::get_default(tracing::Dispatch::clone);
+    let in_flight = Arc::new(AtomicI64::new(0));
     for request in server.incoming_requests() {
         let client = client.clone();
         let forward_config = forward_config.clone();
+        let dispatch = dispatch.clone();
+        let in_flight = InFlight::enter(&in_flight);
         std::thread::spawn(move || {
+            let _in_flight = in_flight;
+            let _guard = tracing::dispatcher::set_default(&dispatch);
             if http_shutdown && request.method() == &Method::Get && request.url() == "/shutdown" {
                 let _ = request.respond(Response::new_empty(StatusCode(200)));
                 std::process::exit(0);
             }
+            if forward_config.status_endpoint
+                && request.method() == &Method::Get
+                && request.url() == "/status"
+            {
+                let _ = request.respond(status_response(&forward_config));
+                return;
+            }
 
             if let Err(e) = forward_request(&client, auth_header, &forward_config, request) {
                 eprintln!("forwarding error: {e}");
@@ -112,7 +380,40 @@ pub fn run_main(args: Args) -> Result<()> {
         });
     }
 
-    Err(anyhow!("server stopped unexpectedly"))
+    let deadline = Instant::now() + SHUTDOWN_GRACE;
+    while in_flight.load(Ordering::SeqCst) > 0 && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+/// Counts a request as in flight until dropped.
+struct InFlight(Arc<AtomicI64>);
+
+impl InFlight {
+    fn enter(counter: &Arc<AtomicI64>) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(Arc::clone(counter))
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn status_response(config: &ForwardConfig) -> Response<std::io::Cursor<Vec<u8>>> {
+    let info = StatusInfo {
+        pid: std::process::id(),
+        uptime_secs: config.started.elapsed().as_secs() as i64,
+        requests_forwarded: config.requests_forwarded.load(Ordering::Relaxed),
+    };
+    let body = serde_json::to_vec(&info).unwrap_or_default();
+    let mut response = Response::from_data(body);
+    if let Ok(header) = Header::from_bytes("Content-Type", "application/json") {
+        response.add_header(header);
+    }
+    response
 }
 
 fn bind_listener(port: Option<u16>) -> Result<(TcpListener, SocketAddr)> {
@@ -122,15 +423,57 @@ fn bind_listener(port: Option<u16>) -> Result<(TcpListener, SocketAddr)> {
     Ok((listener, bound))
 }
 
-fn write_server_info(path: &Path, port: u16) -> Result<()> {
+fn bind_server(args: &Args) -> Result<(Server, BoundAddr)> {
+    let (server, bound) = match args.uds.as_deref() {
+        Some(path) => {
+            let listener = bind_unix_listener(path)?;
+            let server = Server::from_listener(listener, None);
+            (server, BoundAddr::Unix(path.to_path_buf()))
+        }
+        None => {
+            let (listener, addr) = bind_listener(args.port)?;
+            (Server::from_listener(listener, None), BoundAddr::Tcp(addr))
+        }
+    };
+    let server = server.map_err(|err| anyhow!("creating HTTP server: {err}"))?;
+    Ok((server, bound))
+}
+
+#[cfg(unix)]
+fn bind_unix_listener(path: &Path) -> Result<std::os::unix::net::UnixListener> {
+    use std::os::unix::fs::FileTypeExt;
+
+    // Replace a stale socket left behind by a previous run, but never clobber
+    // a regular file.
+    if let Ok(metadata) = fs::symlink_metadata(path)
+        && metadata.file_type().is_socket()
+    {
+        fs::remove_file(path)
+            .with_context(|| format!("failed to remove stale socket {}", path.display()))?;
+    }
+    std::os::unix::net::UnixListener::bind(path)
+        .with_context(|| format!("failed to bind {}", path.display()))
+}
+
+#[cfg(not(unix))]
+fn bind_unix_listener(_path: &Path) -> Result<TcpListener> {
+    Err(anyhow!("--uds is only supported on Unix platforms"))
+}
+
+fn write_server_info(path: &Path, bound: &BoundAddr) -> Result<()> {
     if let Some(parent) = path.parent()
         && !parent.as_os_str().is_empty()
     {
         fs::create_dir_all(parent)?;
     }
 
+    let (port, socket) = match bound {
+        BoundAddr::Tcp(addr) => (Some(addr.port()), None),
+        BoundAddr::Unix(path) => (None, Some(path.clone())),
+    };
     let info = ServerInfo {
         port,
+        socket,
         pid: std::process::id(),
     };
     let mut data = serde_json::to_string(&info)?;
@@ -146,10 +489,12 @@ fn forward_request(
     config: &ForwardConfig,
     mut req: Request,
 ) -> Result<()> {
-    // Only allow POST /v1/responses exactly, no query string.
-    let method = req.method().clone();
-    let url_path = req.url().to_string();
-    let allow = method == Method::Post && url_path == "/v1/responses";
+    // Only allow configured method/path pairs exactly, no query string.
+    let requested = AllowedPath {
+        method: req.method().clone(),
+        path: req.url().to_string(),
+    };
+    let allow = config.allowed_paths.contains(&requested);
 
     if !allow {
         let resp = Response::new_empty(StatusCode(403));
@@ -157,13 +502,22 @@ fn forward_request(
         return Ok(());
     }
 
-    // Read request body
-    let mut body = Vec::new();
-    let mut reader = req.as_reader();
-    std::io::Read::read_to_end(&mut reader, &mut body)?;
+    // Read request body, refusing anything over the configured limit.
+    let declared_too_large = req
+        .body_length()
+        .is_some_and(|len| len > config.max_body_bytes);
+    let body = if declared_too_large {
+        None
+    } else {
+        read_body_limited(req.as_reader(), config.max_body_bytes)?
+    };
+    let Some(body) = body else {
+        let _ = req.respond(Response::new_empty(StatusCode(413)));
+        return Ok(());
+    };
 
     // Build headers for upstream, forwarding everything from the incoming
-    // request except Authorization (we replace it below).
+    // request except Authorization (we replace it below) and `--strip-header`s.
     let mut headers = HeaderMap::new();
     for header in req.headers() {
         let name_ascii = header.field.as_str();
@@ -176,6 +530,9 @@ fn forward_request(
             Ok(name) => name,
             Err(_) => continue,
         };
+        if config.strip_headers.contains(&header_name) {
+            continue;
+        }
         if let Ok(value) = HeaderValue::from_bytes(header.value.as_bytes()) {
             headers.append(header_name, value);
         }
@@ -189,12 +546,30 @@ fn forward_request(
 
     headers.insert(HOST, config.host_header.clone());
 
-    let upstream_resp = client
-        .post(config.upstream_url.clone())
-        .headers(headers)
-        .body(body)
-        .send()
-        .context("forwarding request to upstream")?;
+    let upstream_method = reqwest::Method::from_bytes(requested.method.as_str().as_bytes())
+        .context("converting request method")?;
+    let upstream_url = config.upstream_url_for(&requested.path);
+    let request_bytes = body.len();
+    let started = Instant::now();
+    let mut attempt = 0;
+    let upstream_resp = loop {
+        let resp = client
+            .request(upstream_method.clone(), upstream_url.clone())
+            .headers(headers.clone())
+            .body(body.clone())
+            .send()
+            .context("forwarding request to upstream")?;
+        // Retry only on the status line; once we start streaming the body
+        // back to the client the response is committed.
+        if attempt >= config.max_retries || !is_retryable_status(resp.status()) {
+            break resp;
+        }
+        drop(resp);
+        std::thread::sleep(retry_delay(config.retry_base, attempt));
+        attempt += 1;
+    };
+    let latency = started.elapsed();
+    config.requests_forwarded.fetch_add(1, Ordering::Relaxed);
 
     // We have to create an adapter between a `reqwest::blocking::Response`
     // and a `tiny_http::Response`. Fortunately, `reqwest::blocking::Response`
@@ -224,14 +599,544 @@ fn forward_request(
         }
     });
 
+    let response_bytes = Arc::new(AtomicI64::new(0));
     let response = Response::new(
         StatusCode(status.as_u16()),
         response_headers,
-        upstream_resp,
+        CountingReader {
+            inner: upstream_resp,
+            count: Arc::clone(&response_bytes),
+        },
         content_length,
         None,
     );
 
     let _ = req.respond(response);
+
+    // Only non-secret fields are logged; headers are deliberately omitted.
+    if config.log_requests {
+        info!(
+            method = %requested.method,
+            path = %requested.path,
+            status = status.as_u16(),
+            latency_ms = latency.as_millis() as i64,
+            request_bytes,
+            response_bytes = response_bytes.load(Ordering::Relaxed),
+            "forwarded request"
+        );
+    }
     Ok(())
 }
+
+/// Reads `reader` to the end, returning `None` as soon as more than `limit`
+/// bytes have been seen.
+fn read_body_limited(reader: &mut dyn Read, limit: usize) -> std::io::Result<Option<Vec<u8>>> {
+    let mut body = Vec::new();
+    let mut buf = [0u8; 8_192];
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => return Ok(Some(body)),
+            Ok(n) => n,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        if body.len() + n > limit {
+            return Ok(None);
+        }
+        body.extend_from_slice(&buf[..n]);
+    }
+}
+
+fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 429 | 500 | 502 | 503 | 504)
+}
+
+fn retry_delay(base: Duration, attempt: i64) -> Duration {
+    let factor = 2_u32.saturating_pow(u32::try_from(attempt).unwrap_or(u32::MAX));
+    base.saturating_mul(factor)
+}
+
+/// Counts the bytes streamed back to the client.
+struct CountingReader<R> {
+    inner: R,
+    count: Arc<AtomicI64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count.fetch_add(n as i64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::sync::mpsc;
+
+    const TEST_AUTH_HEADER: &str = "Bearer test-key";
+
+    /// Starts an upstream that answers 200 and reports each `METHOD path` it sees.
+    fn spawn_upstream() -> (String, mpsc::Receiver<String>) {
+        spawn_flaky_upstream(Vec::new())
+    }
+
+    /// Like [`spawn_upstream`], but answers the first requests with `failures`.
+    fn spawn_flaky_upstream(failures: Vec<i32>) -> (String, mpsc::Receiver<String>) {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let addr = server.server_addr().to_ip().unwrap();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut failures = failures.into_iter();
+            for request in server.incoming_requests() {
+                let _ = tx.send(format!("{} {}", request.method(), request.url()));
+                let response = match failures.next() {
+                    Some(status) => Response::from_string("unavailable")
+                        .with_status_code(StatusCode(status as u16)),
+                    None => Response::from_string("ok"),
+                };
+                let _ = request.respond(response);
+            }
+        });
+        (format!("http://{addr}{DEFAULT_PATH}"), rx)
+    }
+
+    /// Starts an upstream that answers 200 and reports the header names of each request.
+    fn spawn_header_upstream() -> (String, mpsc::Receiver<Vec<String>>) {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let addr = server.server_addr().to_ip().unwrap();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let names = request
+                    .headers()
+                    .iter()
+                    .map(|header| header.field.as_str().as_str().to_ascii_lowercase())
+                    .collect();
+                let _ = tx.send(names);
+                let _ = request.respond(Response::from_string("ok"));
+            }
+        });
+        (format!("http://{addr}{DEFAULT_PATH}"), rx)
+    }
+
+    fn spawn_proxy(extra_args: &[&str]) -> SocketAddr {
+        let args = Args::parse_from(["responses-api-proxy"].iter().chain(extra_args));
+        let config = Arc::new(ForwardConfig::from_args(&args).unwrap());
+        let (listener, addr) = bind_listener(None).unwrap();
+        let server = Server::from_listener(listener, None).unwrap();
+        let client = Arc::new(Client::new());
+        let dispatch = tracing::dispatcher::get_default(tracing::Dispatch::clone);
+        std::thread::spawn(move || {
+            tracing::dispatcher::with_default(&dispatch, || {
+                serve(&server, &client, TEST_AUTH_HEADER, &config, false);
+            });
+        });
+        addr
+    }
+
+    #[test]
+    fn forwards_configured_extra_path() {
+        let (upstream, seen) = spawn_upstream();
+        let proxy = spawn_proxy(&[
+            "--upstream-url",
+            &upstream,
+            "--allow-path",
+            "POST /v1/chat/completions",
+        ]);
+
+        let resp = Client::new()
+            .post(format!("http://{proxy}/v1/chat/completions"))
+            .body("{}")
+            .send()
+            .unwrap();
+
+        assert_eq!(
+            (resp.status().as_u16(), seen.recv().unwrap()),
+            (200, "POST /v1/chat/completions".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_unconfigured_path() {
+        let (upstream, seen) = spawn_upstream();
+        let proxy = spawn_proxy(&[
+            "--upstream-url",
+            &upstream,
+            "--allow-path",
+            "POST /v1/chat/completions",
+        ]);
+        let client = Client::new();
+
+        let statuses = [
+            client.post(format!("http://{proxy}/v1/responses")),
+            client.get(format!("http://{proxy}/v1/chat/completions")),
+        ]
+        .map(|request| request.send().unwrap().status().as_u16());
+
+        assert_eq!(statuses, [403, 403]);
+        assert!(seen.try_recv().is_err());
+    }
+
+    #[test]
+    fn defaults_to_responses_path() {
+        let (upstream, seen) = spawn_upstream();
+        let proxy = spawn_proxy(&["--upstream-url", &upstream]);
+
+        let resp = Client::new()
+            .post(format!("http://{proxy}/v1/responses"))
+            .body("{}")
+            .send()
+            .unwrap();
+
+        assert_eq!(
+            (resp.status().as_u16(), seen.recv().unwrap()),
+            (200, format!("POST {DEFAULT_PATH}"))
+        );
+    }
+
+    #[test]
+    fn retries_transient_upstream_failures() {
+        let (upstream, seen) = spawn_flaky_upstream(vec![502, 503]);
+        let proxy = spawn_proxy(&[
+            "--upstream-url",
+            &upstream,
+            "--max-retries",
+            "3",
+            "--retry-base-ms",
+            "1",
+        ]);
+
+        let resp = Client::new()
+            .post(format!("http://{proxy}{DEFAULT_PATH}"))
+            .body("{}")
+            .send()
+            .unwrap();
+
+        assert_eq!(
+            (
+                resp.status().as_u16(),
+                resp.text().unwrap(),
+                seen.try_iter().count()
+            ),
+            (200, "ok".to_string(), 3)
+        );
+    }
+
+    #[test]
+    fn gives_up_after_max_retries() {
+        let (upstream, seen) = spawn_flaky_upstream(vec![503, 503, 503]);
+        let proxy = spawn_proxy(&[
+            "--upstream-url",
+            &upstream,
+            "--max-retries",
+            "1",
+            "--retry-base-ms",
+            "1",
+        ]);
+
+        let resp = Client::new()
+            .post(format!("http://{proxy}{DEFAULT_PATH}"))
+            .body("{}")
+            .send()
+            .unwrap();
+
+        assert_eq!((resp.status().as_u16(), seen.try_iter().count()), (503, 2));
+    }
+
+    #[test]
+    fn rejects_oversized_body() {
+        let (upstream, seen) = spawn_upstream();
+        let proxy = spawn_proxy(&["--upstream-url", &upstream, "--max-body-bytes", "16"]);
+        let client = Client::new();
+
+        let over = client
+            .post(format!("http://{proxy}{DEFAULT_PATH}"))
+            .body("x".repeat(17))
+            .send()
+            .unwrap();
+        assert_eq!((over.status().as_u16(), seen.try_recv().ok()), (413, None));
+
+        let at_limit = client
+            .post(format!("http://{proxy}{DEFAULT_PATH}"))
+            .body("x".repeat(16))
+            .send()
+            .unwrap();
+        assert_eq!(at_limit.status().as_u16(), 200);
+    }
+
+    #[test]
+    fn client_config_uses_pool_flags() {
+        let args = Args::parse_from([
+            "responses-api-proxy",
+            "--pool-max-idle-per-host",
+            "4",
+            "--pool-idle-timeout-secs",
+            "30",
+        ]);
+        let config = ClientConfig::from_args(&args);
+        assert_eq!(
+            config,
+            ClientConfig {
+                pool_max_idle_per_host: Some(4),
+                pool_idle_timeout: Some(Duration::from_secs(30)),
+                upstream_ca: None,
+                upstream_ca_only: false,
+            }
+        );
+        assert!(config.build().is_ok());
+
+        let defaults = ClientConfig::from_args(&Args::parse_from(["responses-api-proxy"]));
+        assert_eq!(
+            defaults,
+            ClientConfig {
+                pool_max_idle_per_host: None,
+                pool_idle_timeout: None,
+                upstream_ca: None,
+                upstream_ca_only: false,
+            }
+        );
+    }
+
+    #[test]
+    fn strips_configured_headers() {
+        let (upstream, seen) = spawn_header_upstream();
+        let proxy = spawn_proxy(&[
+            "--upstream-url",
+            &upstream,
+            "--strip-header",
+            "COOKIE",
+            "--strip-header",
+            "x-trace-id",
+        ]);
+
+        let resp = Client::new()
+            .post(format!("http://{proxy}{DEFAULT_PATH}"))
+            .header("Cookie", "session=abc")
+            .header("X-Trace-Id", "123")
+            .header("Content-Type", "application/json")
+            .body("{}")
+            .send()
+            .unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+
+        let names = seen.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(names.contains(&"content-type".to_string()), "{names:?}");
+        assert!(!names.contains(&"cookie".to_string()), "{names:?}");
+        assert!(!names.contains(&"x-trace-id".to_string()), "{names:?}");
+    }
+
+    #[test]
+    fn rejects_invalid_strip_header() {
+        let result = Args::try_parse_from(["responses-api-proxy", "--strip-header", "bad name"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn status_endpoint_reports_forwarded_requests() {
+        let (upstream, _seen) = spawn_upstream();
+        let proxy = spawn_proxy(&["--upstream-url", &upstream, "--status-endpoint"]);
+        let client = Client::new();
+        let resp = client
+            .post(format!("http://{proxy}{DEFAULT_PATH}"))
+            .body("{}")
+            .send()
+            .unwrap();
+        assert_eq!(resp.text().unwrap(), "ok");
+
+        let resp = client.get(format!("http://{proxy}/status")).send().unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("content-type").unwrap(),
+            "application/json"
+        );
+        let status: serde_json::Value = resp.json().unwrap();
+        let uptime_secs = status["uptime_secs"].as_i64().unwrap();
+        assert_eq!(
+            status,
+            serde_json::json!({
+                "pid": std::process::id(),
+                "uptime_secs": uptime_secs,
+                "requests_forwarded": 1,
+            })
+        );
+    }
+
+    #[test]
+    fn status_endpoint_is_off_by_default() {
+        let (upstream, _seen) = spawn_upstream();
+        let proxy = spawn_proxy(&["--upstream-url", &upstream]);
+
+        let resp = Client::new()
+            .get(format!("http://{proxy}/status"))
+            .send()
+            .unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn missing_upstream_ca_is_a_startup_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing.pem");
+        let args = Args::parse_from([
+            "responses-api-proxy",
+            "--upstream-ca",
+            path.to_str().unwrap(),
+        ]);
+
+        let err = ClientConfig::from_args(&args).build().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("reading --upstream-ca {}", path.display())
+        );
+    }
+
+    #[test]
+    fn unparseable_upstream_ca_is_a_startup_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ca.pem");
+        std::fs::write(&path, "not a certificate").unwrap();
+        let args = Args::parse_from([
+            "responses-api-proxy",
+            "--upstream-ca",
+            path.to_str().unwrap(),
+            "--upstream-ca-only",
+        ]);
+
+        let err = ClientConfig::from_args(&args).build().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "--upstream-ca {} contains no PEM certificates",
+                path.display()
+            )
+        );
+    }
+
+    #[test]
+    fn upstream_ca_only_requires_upstream_ca() {
+        let result = Args::try_parse_from(["responses-api-proxy", "--upstream-ca-only"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn read_body_limited_stops_past_limit() {
+        let mut small: &[u8] = b"abcd";
+        let mut large: &[u8] = b"abcde";
+
+        assert_eq!(
+            (
+                read_body_limited(&mut small, 4).unwrap(),
+                read_body_limited(&mut large, 4).unwrap(),
+            ),
+            (Some(b"abcd".to_vec()), None)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn serves_over_unix_socket() {
+        use std::os::unix::net::UnixStream;
+
+        let (upstream, _seen) = spawn_upstream();
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("proxy.sock");
+        let args = Args::parse_from([
+            "responses-api-proxy",
+            "--upstream-url",
+            &upstream,
+            "--uds",
+            socket.to_str().unwrap(),
+        ]);
+        let config = Arc::new(ForwardConfig::from_args(&args).unwrap());
+        let (server, bound) = bind_server(&args).unwrap();
+        assert_eq!(bound, BoundAddr::Unix(socket.clone()));
+        let client = Arc::new(Client::new());
+        std::thread::spawn(move || serve(&server, &client, TEST_AUTH_HEADER, &config, false));
+
+        let mut stream = UnixStream::connect(&socket).unwrap();
+        stream
+            .write_all(
+                b"POST /v1/responses HTTP/1.1\r\nHost: localhost\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}",
+            )
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.ends_with("\r\n\r\nok"), "{response}");
+
+        let info_path = dir.path().join("server-info.json");
+        write_server_info(&info_path, &bound).unwrap();
+        let info: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&info_path).unwrap()).unwrap();
+        assert_eq!(
+            info,
+            serde_json::json!({
+                "socket": socket,
+                "pid": std::process::id(),
+            })
+        );
+    }
+
+    #[test]
+    fn request_log_omits_auth_header() {
+        let logs = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_writer({
+                let logs = Arc::clone(&logs);
+                move || SharedWriter(Arc::clone(&logs))
+            })
+            .finish();
+        let (upstream, _seen) = spawn_upstream();
+        let proxy = tracing::subscriber::with_default(subscriber, || {
+            spawn_proxy(&["--upstream-url", &upstream, "--log-requests"])
+        });
+
+        let resp = Client::new()
+            .post(format!("http://{proxy}{DEFAULT_PATH}"))
+            .header("authorization", "Bearer client-secret")
+            .body("{}")
+            .send()
+            .unwrap();
+        assert_eq!(resp.text().unwrap(), "ok");
+
+        // The log line is written after the response has been streamed.
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let output = loop {
+            let output = String::from_utf8_lossy(&logs.lock().unwrap()).to_string();
+            if output.contains("forwarded request") || Instant::now() > deadline {
+                break output;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+
+        assert!(output.contains("forwarded request"), "{output}");
+        assert!(output.contains("path=/v1/responses status=200"), "{output}");
+        assert!(
+            output.contains("request_bytes=2 response_bytes=2"),
+            "{output}"
+        );
+        assert!(!output.contains("test-key"), "{output}");
+        assert!(!output.contains("client-secret"), "{output}");
+    }
+
+    struct SharedWriter(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl Write for SharedWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0
+                .lock()
+                .map_err(|_| std::io::Error::other("log buffer poisoned"))?
+                .extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+}
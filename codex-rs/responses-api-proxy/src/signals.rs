@@ -0,0 +1,63 @@
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+#[cfg(unix)]
+use std::sync::atomic::Ordering;
+
+use anyhow::Result;
+use tiny_http::Server;
+
+/// Signals that ask the proxy to stop accepting connections and exit cleanly.
+#[cfg(unix)]
+pub(crate) struct ShutdownSignals {
+    set: libc::sigset_t,
+}
+
+#[cfg(not(unix))]
+pub(crate) struct ShutdownSignals;
+
+/// Block SIGTERM and SIGINT on the calling thread so that every thread
+/// spawned afterwards inherits the mask and only the handler thread started
+/// by [`ShutdownSignals::spawn_handler`] observes them. Call this before any
+/// other threads exist.
+#[cfg(unix)]
+pub(crate) fn block_shutdown_signals() -> Result<ShutdownSignals> {
+    // SAFETY: `set` is initialised by `sigemptyset` before use and only
+    // passed to libc functions that expect a valid `sigset_t`.
+    unsafe {
+        let mut set: libc::sigset_t = std::mem::zeroed();
+        libc::sigemptyset(&mut set);
+        libc::sigaddset(&mut set, libc::SIGTERM);
+        libc::sigaddset(&mut set, libc::SIGINT);
+        let rc = libc::pthread_sigmask(libc::SIG_BLOCK, &set, std::ptr::null_mut());
+        if rc != 0 {
+            return Err(std::io::Error::from_raw_os_error(rc).into());
+        }
+        Ok(ShutdownSignals { set })
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn block_shutdown_signals() -> Result<ShutdownSignals> {
+    Ok(ShutdownSignals)
+}
+
+impl ShutdownSignals {
+    /// Wait for a shutdown signal on a dedicated thread, then record it in
+    /// `requested` and unblock `server` so its accept loop ends.
+    #[cfg(unix)]
+    pub(crate) fn spawn_handler(self, server: Arc<Server>, requested: Arc<AtomicBool>) {
+        let set = self.set;
+        std::thread::spawn(move || {
+            let mut signal = 0;
+            // SAFETY: `set` is a valid, initialised `sigset_t`.
+            if unsafe { libc::sigwait(&set, &mut signal) } == 0 {
+                eprintln!("responses-api-proxy received signal {signal}, shutting down");
+                requested.store(true, Ordering::SeqCst);
+                server.unblock();
+            }
+        });
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn spawn_handler(self, _server: Arc<Server>, _requested: Arc<AtomicBool>) {}
+}
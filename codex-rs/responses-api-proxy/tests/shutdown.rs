@@ -0,0 +1,62 @@
+#![cfg(unix)]
+
+use std::io::Write;
+use std::process::Command;
+use std::process::Stdio;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::Context;
+use anyhow::Result;
+use pretty_assertions::assert_eq;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+fn sigterm_exits_cleanly() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let server_info = dir.path().join("server-info.json");
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_codex-responses-api-proxy"))
+        .arg("--server-info")
+        .arg(&server_info)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    proxy
+        .stdin
+        .take()
+        .context("proxy stdin")?
+        .write_all(b"sk-test")?;
+
+    // The server info file is written once the proxy is listening.
+    let deadline = Instant::now() + TIMEOUT;
+    let pid = loop {
+        let info = std::fs::read_to_string(&server_info).unwrap_or_default();
+        if let Ok(info) = serde_json::from_str::<serde_json::Value>(&info) {
+            break info["pid"].as_i64().context("pid in server info")?;
+        }
+        anyhow::ensure!(Instant::now() < deadline, "proxy never wrote server info");
+        std::thread::sleep(Duration::from_millis(20));
+    };
+
+    let kill = Command::new("kill")
+        .args(["-TERM", &pid.to_string()])
+        .status()?;
+    assert!(kill.success());
+
+    let deadline = Instant::now() + TIMEOUT;
+    let status = loop {
+        if let Some(status) = proxy.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            proxy.kill()?;
+            anyhow::bail!("proxy did not exit after SIGTERM");
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+
+    assert_eq!(status.code(), Some(0));
+    Ok(())
+}
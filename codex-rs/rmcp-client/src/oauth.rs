@@ -95,7 +95,7 @@ pub(crate) fn load_oauth_tokens(
     url: &str,
     store_mode: OAuthCredentialsStoreMode,
 ) -> Result<Option<StoredOAuthTokens>> {
-    let keyring_store = DefaultKeyringStore;
+    let keyring_store = DefaultKeyringStore::default();
     match store_mode {
         OAuthCredentialsStoreMode::Auto => {
             load_oauth_tokens_from_keyring_with_fallback_to_file(&keyring_store, server_name, url)
@@ -171,7 +171,7 @@ pub fn save_oauth_tokens(
     tokens: &StoredOAuthTokens,
     store_mode: OAuthCredentialsStoreMode,
 ) -> Result<()> {
-    let keyring_store = DefaultKeyringStore;
+    let keyring_store = DefaultKeyringStore::default();
     match store_mode {
         OAuthCredentialsStoreMode::Auto => save_oauth_tokens_with_keyring_with_fallback_to_file(
             &keyring_store,
@@ -232,7 +232,7 @@ pub fn delete_oauth_tokens(
     url: &str,
     store_mode: OAuthCredentialsStoreMode,
 ) -> Result<bool> {
-    let keyring_store = DefaultKeyringStore;
+    let keyring_store = DefaultKeyringStore::default();
     delete_oauth_tokens_from_keyring_and_file(&keyring_store, store_mode, server_name, url)
 }
 
@@ -1,10 +1,20 @@
 #![deny(clippy::print_stdout)]
 
 use std::io;
+use std::io::Read;
 use std::io::Write;
 use std::net::Shutdown;
+use std::net::SocketAddr;
+use std::net::TcpStream;
 use std::path::Path;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::sync::mpsc;
+use std::sync::mpsc::RecvTimeoutError;
 use std::thread;
+use std::time::Duration;
+use std::time::Instant;
 
 use anyhow::Context;
 use anyhow::anyhow;
@@ -18,35 +28,320 @@ use uds_windows::UnixStream;
 /// Connects to the Unix Domain Socket at `socket_path` and relays data between
 /// standard input/output and the socket.
 pub fn run(socket_path: &Path) -> anyhow::Result<()> {
-    let mut stream = UnixStream::connect(socket_path)
-        .with_context(|| format!("failed to connect to socket at {}", socket_path.display()))?;
+    let stream = connect(socket_path)?;
+    relay(stream, io::stdin(), io::stdout(), None)?;
+    Ok(())
+}
+
+/// Like [`run`], but retries the connection up to `attempts` times, sleeping
+/// `base_delay` after the first failure and doubling the delay after each
+/// subsequent one. Returns the last connection error if every attempt fails.
+pub fn run_with_retry(
+    socket_path: &Path,
+    attempts: i32,
+    base_delay: Duration,
+) -> anyhow::Result<()> {
+    let stream = connect_with_retry(socket_path, attempts, base_delay)?;
+    relay(stream, io::stdin(), io::stdout(), None)?;
+    Ok(())
+}
+
+/// Like [`run`], but gives up once no bytes have moved in either direction
+/// for `idle_timeout`. The socket is shut down and an error whose root cause
+/// is an [`io::Error`] of kind [`io::ErrorKind::TimedOut`] is returned.
+pub fn run_with_idle_timeout(socket_path: &Path, idle_timeout: Duration) -> anyhow::Result<()> {
+    let stream = connect(socket_path)?;
+    relay(stream, io::stdin(), io::stdout(), Some(idle_timeout))?;
+    Ok(())
+}
+
+/// Connects to the TCP endpoint at `addr` and relays data between standard
+/// input/output and the connection.
+pub fn run_tcp(addr: SocketAddr) -> anyhow::Result<()> {
+    let stream =
+        TcpStream::connect(addr).with_context(|| format!("failed to connect to {addr}"))?;
+    relay(stream, io::stdin(), io::stdout(), None)?;
+    Ok(())
+}
+
+/// A connected stream that can be split into independent read and write
+/// halves for relaying.
+trait RelayStream: Read + Write + Send + Sized + 'static {
+    fn try_clone_stream(&self) -> io::Result<Self>;
+    fn shutdown_stream(&self, how: Shutdown) -> io::Result<()>;
+}
+
+impl RelayStream for UnixStream {
+    fn try_clone_stream(&self) -> io::Result<Self> {
+        self.try_clone()
+    }
+
+    fn shutdown_stream(&self, how: Shutdown) -> io::Result<()> {
+        self.shutdown(how)
+    }
+}
+
+impl RelayStream for TcpStream {
+    fn try_clone_stream(&self) -> io::Result<Self> {
+        self.try_clone()
+    }
+
+    fn shutdown_stream(&self, how: Shutdown) -> io::Result<()> {
+        self.shutdown(how)
+    }
+}
+
+fn connect(socket_path: &Path) -> anyhow::Result<UnixStream> {
+    UnixStream::connect(socket_path)
+        .with_context(|| format!("failed to connect to socket at {}", socket_path.display()))
+}
+
+fn connect_with_retry(
+    socket_path: &Path,
+    attempts: i32,
+    base_delay: Duration,
+) -> anyhow::Result<UnixStream> {
+    let mut delay = base_delay;
+    let mut attempt = 1;
+    loop {
+        match connect(socket_path) {
+            Ok(stream) => return Ok(stream),
+            Err(err) if attempt >= attempts => return Err(err),
+            Err(_) => {
+                thread::sleep(delay);
+                delay = delay.saturating_mul(2);
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Time of the most recent byte moved in either direction.
+#[derive(Clone)]
+struct Activity(Arc<Mutex<Instant>>);
+
+impl Activity {
+    fn new() -> Self {
+        Self(Arc::new(Mutex::new(Instant::now())))
+    }
+
+    fn touch(&self) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = Instant::now();
+    }
 
-    let mut reader = stream
-        .try_clone()
+    fn idle(&self) -> Duration {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .elapsed()
+    }
+}
+
+/// Reader that records [`Activity`] whenever it yields bytes.
+struct ActivityReader<R> {
+    inner: R,
+    activity: Activity,
+}
+
+impl<R: Read> Read for ActivityReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if n > 0 {
+            self.activity.touch();
+        }
+        Ok(n)
+    }
+}
+
+enum Finished<W> {
+    Input(io::Result<()>),
+    Output(io::Result<W>),
+}
+
+/// Copies `input` to the socket and the socket to `output` until both sides
+/// reach EOF, returning `output` once everything has been flushed.
+///
+/// With an `idle_timeout`, the socket is shut down and a
+/// [`io::ErrorKind::TimedOut`] error is returned if no bytes move in either
+/// direction for that long. The copy threads are left to unwind on their own
+/// in that case; a thread blocked reading `input` only exits with the process.
+fn relay<S, R, W>(
+    stream: S,
+    input: R,
+    mut output: W,
+    idle_timeout: Option<Duration>,
+) -> anyhow::Result<W>
+where
+    S: RelayStream,
+    R: Read + Send + 'static,
+    W: Write + Send + 'static,
+{
+    let reader = stream
+        .try_clone_stream()
         .context("failed to clone socket for reading")?;
+    let mut writer = stream;
+    let activity = Activity::new();
+    let (finished_tx, finished_rx) = mpsc::channel();
 
-    let stdout_thread = thread::spawn(move || -> io::Result<()> {
-        let stdout = io::stdout();
-        let mut handle = stdout.lock();
-        io::copy(&mut reader, &mut handle)?;
-        handle.flush()?;
-        Ok(())
+    let mut reader = ActivityReader {
+        inner: reader,
+        activity: activity.clone(),
+    };
+    let output_tx = finished_tx.clone();
+    thread::spawn(move || {
+        let result = io::copy(&mut reader, &mut output)
+            .and_then(|_| output.flush())
+            .map(|()| output);
+        let _ = output_tx.send(Finished::Output(result));
+    });
+
+    let mut input = ActivityReader {
+        inner: input,
+        activity: activity.clone(),
+    };
+    let shutdown_handle = writer
+        .try_clone_stream()
+        .context("failed to clone socket for shutdown")?;
+    thread::spawn(move || {
+        let result =
+            io::copy(&mut input, &mut writer).and_then(|_| writer.shutdown_stream(Shutdown::Write));
+        let _ = finished_tx.send(Finished::Input(result));
     });
 
-    let stdin = io::stdin();
-    {
-        let mut handle = stdin.lock();
-        io::copy(&mut handle, &mut stream).context("failed to copy data from stdin to socket")?;
+    let mut output = None;
+    let mut input_done = false;
+    while !(input_done && output.is_some()) {
+        let finished = match idle_timeout {
+            None => finished_rx.recv().ok(),
+            Some(limit) => {
+                let remaining = limit.saturating_sub(activity.idle());
+                if remaining.is_zero() {
+                    let _ = shutdown_handle.shutdown_stream(Shutdown::Both);
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        format!("no data relayed for {}ms", limit.as_millis()),
+                    )
+                    .into());
+                }
+                match finished_rx.recv_timeout(remaining) {
+                    Ok(finished) => Some(finished),
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => None,
+                }
+            }
+        };
+        match finished {
+            Some(Finished::Input(result)) => {
+                result.context("failed to copy data from stdin to socket")?;
+                input_done = true;
+            }
+            Some(Finished::Output(result)) => {
+                output = Some(result.context("failed to copy data from socket to stdout")?);
+            }
+            None => return Err(anyhow!("thread panicked while relaying socket data")),
+        }
     }
+    output.ok_or_else(|| anyhow!("socket relay finished without output"))
+}
 
-    stream
-        .shutdown(Shutdown::Write)
-        .context("failed to shutdown socket writer")?;
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::os::unix::net::UnixListener;
 
-    let stdout_result = stdout_thread
-        .join()
-        .map_err(|_| anyhow!("thread panicked while copying socket data to stdout"))?;
-    stdout_result.context("failed to copy data from socket to stdout")?;
+    #[test]
+    fn retry_connects_once_listener_appears() -> anyhow::Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        let socket_path = dir.path().join("socket");
 
-    Ok(())
+        let server_path = socket_path.clone();
+        let server_thread = thread::spawn(move || -> anyhow::Result<Vec<u8>> {
+            // Give the client time to fail at least once before listening.
+            thread::sleep(Duration::from_millis(200));
+            let listener = UnixListener::bind(&server_path)?;
+            let (mut connection, _) = listener.accept()?;
+            let mut received = Vec::new();
+            connection.read_to_end(&mut received)?;
+            connection.write_all(b"response")?;
+            Ok(received)
+        });
+
+        let stream = connect_with_retry(&socket_path, 20, Duration::from_millis(20))?;
+        let output = relay(stream, &b"request"[..], Vec::new(), None)?;
+        let received = server_thread
+            .join()
+            .map_err(|_| anyhow!("server thread panicked"))??;
+
+        assert_eq!(
+            (received, output),
+            (b"request".to_vec(), b"response".to_vec())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn relays_over_tcp() -> anyhow::Result<()> {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let echo_thread = thread::spawn(move || -> io::Result<()> {
+            let (mut connection, _) = listener.accept()?;
+            let mut reader = connection.try_clone()?;
+            io::copy(&mut reader, &mut connection)?;
+            Ok(())
+        });
+
+        let stream = TcpStream::connect(addr)?;
+        let output = relay(stream, &b"ping over tcp"[..], Vec::new(), None)?;
+        echo_thread
+            .join()
+            .map_err(|_| anyhow!("echo thread panicked"))??;
+
+        assert_eq!(output, b"ping over tcp".to_vec());
+        Ok(())
+    }
+
+    #[test]
+    fn idle_timeout_gives_up_on_silent_peer() -> anyhow::Result<()> {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let peer_thread = thread::spawn(move || -> io::Result<()> {
+            // Accept, then hold the connection open without ever writing.
+            let (_connection, _) = listener.accept()?;
+            let _ = release_rx.recv();
+            Ok(())
+        });
+
+        let stream = TcpStream::connect(addr)?;
+        let idle_timeout = Duration::from_millis(200);
+        let started = Instant::now();
+        let err = relay(stream, io::empty(), Vec::new(), Some(idle_timeout)).unwrap_err();
+        let elapsed = started.elapsed();
+        drop(release_tx);
+        peer_thread
+            .join()
+            .map_err(|_| anyhow!("peer thread panicked"))??;
+
+        let kind = err.downcast_ref::<io::Error>().map(io::Error::kind);
+        assert_eq!(kind, Some(io::ErrorKind::TimedOut));
+        assert!(
+            elapsed >= idle_timeout && elapsed < Duration::from_secs(5),
+            "relay gave up after {elapsed:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn retry_returns_last_error_when_nothing_listens() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let socket_path = dir.path().join("missing");
+
+        let err = connect_with_retry(&socket_path, 3, Duration::from_millis(1)).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            format!("failed to connect to socket at {}", socket_path.display())
+        );
+    }
 }
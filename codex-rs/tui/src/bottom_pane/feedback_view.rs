@@ -84,6 +84,7 @@ impl FeedbackNoteView {
             } else {
                 None
             },
+            serde_json::Value::Null,
         );
 
         match result {
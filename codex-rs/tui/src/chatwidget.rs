@@ -380,7 +380,10 @@ impl ChatWidget {
         include_logs: bool,
     ) {
         // Build a fresh snapshot at the time of opening the note overlay.
-        let snapshot = self.feedback.snapshot(self.conversation_id);
+        let snapshot = self
+            .feedback
+            .snapshot(self.conversation_id)
+            .with_offline_queue(&self.config.codex_home);
         let rollout = if include_logs {
             self.current_rollout_path.clone()
         } else {
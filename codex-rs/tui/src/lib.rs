@@ -323,6 +323,11 @@ pub async fn run_main(
             .try_init();
     };
 
+    // Best effort: deliver feedback queued by earlier offline uploads.
+    codex_feedback::CodexLogSnapshot::flush_queued_feedback_in_background(
+        config.codex_home.clone(),
+    );
+
     run_ratatui_app(
         cli,
         config,
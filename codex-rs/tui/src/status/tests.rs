@@ -91,6 +91,7 @@ fn status_snapshot_includes_reasoning_details() {
         network_access: false,
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        allow_hosts: Vec::new(),
     };
 
     config.cwd = PathBuf::from("/workspace/tests");
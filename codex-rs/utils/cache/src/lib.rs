@@ -1,10 +1,18 @@
 use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::future::Future;
 use std::hash::Hash;
 use std::num::NonZeroUsize;
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
 
 use lru::LruCache;
 use sha1::Digest;
 use sha1::Sha1;
+use sha2::Sha256;
 use tokio::sync::Mutex;
 use tokio::sync::MutexGuard;
 
@@ -12,6 +20,55 @@ use tokio::sync::MutexGuard;
 /// Calls outside a Tokio runtime are no-ops.
 pub struct BlockingLruCache<K, V> {
     inner: Mutex<LruCache<K, V>>,
+    expiry: Option<Expiry<K>>,
+    metrics: Metrics,
+}
+
+/// Snapshot of cache effectiveness returned by [`BlockingLruCache::stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: i64,
+    pub misses: i64,
+    pub len: usize,
+    pub capacity: usize,
+}
+
+/// Lock-free counters backing [`CacheStats`]. `len` and `capacity` are
+/// refreshed whenever the cache is mutated under its lock.
+struct Metrics {
+    hits: AtomicI64,
+    misses: AtomicI64,
+    len: AtomicUsize,
+    capacity: AtomicUsize,
+}
+
+impl Metrics {
+    fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            hits: AtomicI64::new(0),
+            misses: AtomicI64::new(0),
+            len: AtomicUsize::new(0),
+            capacity: AtomicUsize::new(capacity.get()),
+        }
+    }
+
+    fn record_lookup(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_shape<K: Hash + Eq, V>(&self, cache: &LruCache<K, V>) {
+        self.len.store(cache.len(), Ordering::Relaxed);
+        self.capacity.store(cache.cap().get(), Ordering::Relaxed);
+    }
+}
+
+/// Insertion timestamps for caches built with [`BlockingLruCache::with_ttl`].
+/// Only touched while the `inner` lock is held.
+struct Expiry<K> {
+    ttl: Duration,
+    inserted_at: std::sync::Mutex<HashMap<K, Instant>>,
+    now: fn() -> Instant,
 }
 
 impl<K, V> BlockingLruCache<K, V>
@@ -23,21 +80,43 @@ where
     pub fn new(capacity: NonZeroUsize) -> Self {
         Self {
             inner: Mutex::new(LruCache::new(capacity)),
+            expiry: None,
+            metrics: Metrics::new(capacity),
+        }
+    }
+
+    /// Creates a cache whose entries are treated as absent once `ttl` has
+    /// elapsed since they were inserted.
+    #[must_use]
+    pub fn with_ttl(capacity: NonZeroUsize, ttl: Duration) -> Self {
+        Self::with_ttl_and_clock(capacity, ttl, Instant::now)
+    }
+
+    fn with_ttl_and_clock(capacity: NonZeroUsize, ttl: Duration, now: fn() -> Instant) -> Self {
+        Self {
+            inner: Mutex::new(LruCache::new(capacity)),
+            expiry: Some(Expiry {
+                ttl,
+                inserted_at: std::sync::Mutex::new(HashMap::new()),
+                now,
+            }),
+            metrics: Metrics::new(capacity),
         }
     }
 
     /// Returns a clone of the cached value for `key`, or computes and inserts it.
     pub fn get_or_insert_with(&self, key: K, value: impl FnOnce() -> V) -> V
     where
+        K: Clone,
         V: Clone,
     {
         if let Some(mut guard) = lock_if_runtime(&self.inner) {
-            if let Some(v) = guard.get(&key) {
-                return v.clone();
+            if let Some(v) = self.lookup(&mut guard, &key) {
+                return v;
             }
             let v = value();
             // Insert and return a clone to keep ownership in the cache.
-            guard.put(key, v.clone());
+            self.put_stamped(&mut guard, key, v.clone());
             return v;
         }
         value()
@@ -50,19 +129,47 @@ where
         value: impl FnOnce() -> Result<V, E>,
     ) -> Result<V, E>
     where
+        K: Clone,
         V: Clone,
     {
         if let Some(mut guard) = lock_if_runtime(&self.inner) {
-            if let Some(v) = guard.get(&key) {
-                return Ok(v.clone());
+            if let Some(v) = self.lookup(&mut guard, &key) {
+                return Ok(v);
             }
             let v = value()?;
-            guard.put(key, v.clone());
+            self.put_stamped(&mut guard, key, v.clone());
             return Ok(v);
         }
         value()
     }
 
+    /// Async variant of `get_or_try_insert_with`.
+    ///
+    /// The lock is released while `value` runs, so concurrent callers may both
+    /// compute a value for the same key; the first one inserted wins and is
+    /// returned to every caller.
+    pub async fn get_or_try_insert_with_async<E, F, Fut>(&self, key: K, value: F) -> Result<V, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, E>>,
+        K: Clone,
+        V: Clone,
+    {
+        {
+            let mut guard = self.inner.lock().await;
+            if let Some(v) = self.lookup(&mut guard, &key) {
+                return Ok(v);
+            }
+        }
+        let v = value().await?;
+        let mut guard = self.inner.lock().await;
+        if let Some(existing) = self.get_live(&mut guard, &key) {
+            return Ok(existing);
+        }
+        self.put_stamped(&mut guard, key, v.clone());
+        Ok(v)
+    }
+
     /// Builds a cache if `capacity` is non-zero, returning `None` otherwise.
     #[must_use]
     pub fn try_with_capacity(capacity: usize) -> Option<Self> {
@@ -77,13 +184,33 @@ where
         V: Clone,
     {
         let mut guard = lock_if_runtime(&self.inner)?;
-        guard.get(key).cloned()
+        self.lookup(&mut guard, key)
+    }
+
+    /// Returns a clone of the cached value for `key` without marking it as
+    /// recently used.
+    pub fn peek<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        let guard = lock_if_runtime(&self.inner)?;
+        if let Some(expiry) = &self.expiry
+            && expiry.is_expired(key)
+        {
+            return None;
+        }
+        guard.peek(key).cloned()
     }
 
     /// Inserts `value` for `key`, returning the previous entry if it existed.
-    pub fn insert(&self, key: K, value: V) -> Option<V> {
+    pub fn insert(&self, key: K, value: V) -> Option<V>
+    where
+        K: Clone,
+    {
         let mut guard = lock_if_runtime(&self.inner)?;
-        guard.put(key, value)
+        self.put_stamped(&mut guard, key, value)
     }
 
     /// Removes the entry for `key` if it exists, returning it.
@@ -93,20 +220,33 @@ where
         Q: Hash + Eq + ?Sized,
     {
         let mut guard = lock_if_runtime(&self.inner)?;
-        guard.pop(key)
+        if let Some(expiry) = &self.expiry {
+            expiry.timestamps().remove(key);
+        }
+        let removed = guard.pop(key);
+        self.metrics.record_shape(&guard);
+        removed
     }
 
     /// Clears all entries from the cache.
     pub fn clear(&self) {
         if let Some(mut guard) = lock_if_runtime(&self.inner) {
+            if let Some(expiry) = &self.expiry {
+                expiry.timestamps().clear();
+            }
             guard.clear();
+            self.metrics.record_shape(&guard);
         }
     }
 
     /// Executes `callback` with a mutable reference to the underlying cache.
+    ///
+    /// Entries inserted through the callback carry no timestamp and never expire.
     pub fn with_mut<R>(&self, callback: impl FnOnce(&mut LruCache<K, V>) -> R) -> R {
         if let Some(mut guard) = lock_if_runtime(&self.inner) {
-            callback(&mut guard)
+            let result = callback(&mut guard);
+            self.metrics.record_shape(&guard);
+            result
         } else {
             let mut disabled = LruCache::unbounded();
             callback(&mut disabled)
@@ -117,6 +257,95 @@ where
     pub fn blocking_lock(&self) -> Option<MutexGuard<'_, LruCache<K, V>>> {
         lock_if_runtime(&self.inner)
     }
+
+    /// Returns hit/miss counts and the current size without taking the lock.
+    ///
+    /// Hits and misses are counted by `get` and the `get_or_*insert_with*`
+    /// helpers. Changes made through `blocking_lock` are reflected in `len` and
+    /// `capacity` after the next mutation.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.metrics.hits.load(Ordering::Relaxed),
+            misses: self.metrics.misses.load(Ordering::Relaxed),
+            len: self.metrics.len.load(Ordering::Relaxed),
+            capacity: self.metrics.capacity.load(Ordering::Relaxed),
+        }
+    }
+
+    /// `get_live`, recording the outcome as a hit or a miss.
+    fn lookup<Q>(&self, cache: &mut LruCache<K, V>, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        let value = self.get_live(cache, key);
+        self.metrics.record_lookup(value.is_some());
+        value
+    }
+
+    /// Looks up `key`, dropping the entry instead if its TTL has elapsed.
+    fn get_live<Q>(&self, cache: &mut LruCache<K, V>, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        if let Some(expiry) = &self.expiry
+            && expiry.is_expired(key)
+        {
+            expiry.timestamps().remove(key);
+            cache.pop(key);
+            self.metrics.record_shape(cache);
+            return None;
+        }
+        cache.get(key).cloned()
+    }
+
+    /// Inserts `value`, recording its insertion time when a TTL is configured.
+    fn put_stamped(&self, cache: &mut LruCache<K, V>, key: K, value: V) -> Option<V>
+    where
+        K: Clone,
+    {
+        let previous = match &self.expiry {
+            None => cache.put(key, value),
+            Some(expiry) => {
+                let mut timestamps = expiry.timestamps();
+                timestamps.insert(key.clone(), (expiry.now)());
+                match cache.push(key.clone(), value) {
+                    Some((old_key, old_value)) if old_key == key => Some(old_value),
+                    Some((evicted_key, _)) => {
+                        timestamps.remove(&evicted_key);
+                        None
+                    }
+                    None => None,
+                }
+            }
+        };
+        self.metrics.record_shape(cache);
+        previous
+    }
+}
+
+impl<K> Expiry<K>
+where
+    K: Eq + Hash,
+{
+    fn timestamps(&self) -> std::sync::MutexGuard<'_, HashMap<K, Instant>> {
+        self.inserted_at
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn is_expired<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.timestamps().get(key).is_some_and(|inserted_at| {
+            (self.now)().saturating_duration_since(*inserted_at) >= self.ttl
+        })
+    }
 }
 
 fn lock_if_runtime<K, V>(m: &Mutex<LruCache<K, V>>) -> Option<MutexGuard<'_, LruCache<K, V>>>
@@ -141,10 +370,63 @@ pub fn sha1_digest(bytes: &[u8]) -> [u8; 20] {
     out
 }
 
+/// Computes the SHA-256 digest of `bytes`.
+///
+/// Prefer this over [`sha1_digest`] when a stronger content hash is required.
+#[must_use]
+pub fn sha256_digest(bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    let result = hasher.finalize();
+    let mut out = [0; 32];
+    out.copy_from_slice(&result);
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::BlockingLruCache;
+    use super::CacheStats;
+    use super::sha256_digest;
     use std::num::NonZeroUsize;
+    use std::sync::Arc;
+    use std::sync::LazyLock;
+    use std::sync::atomic::AtomicU64;
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    static CLOCK_BASE: LazyLock<Instant> = LazyLock::new(Instant::now);
+    static CLOCK_OFFSET_SECS: AtomicU64 = AtomicU64::new(0);
+
+    fn test_clock() -> Instant {
+        *CLOCK_BASE + Duration::from_secs(CLOCK_OFFSET_SECS.load(Ordering::SeqCst))
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn expired_entries_are_recomputed() {
+        let cache = BlockingLruCache::with_ttl_and_clock(
+            NonZeroUsize::new(2).expect("capacity"),
+            Duration::from_secs(10),
+            test_clock,
+        );
+        assert_eq!(cache.get_or_insert_with("key", || 1), 1);
+
+        CLOCK_OFFSET_SECS.fetch_add(5, Ordering::SeqCst);
+        assert_eq!(cache.get_or_insert_with("key", || 2), 1);
+
+        CLOCK_OFFSET_SECS.fetch_add(6, Ordering::SeqCst);
+        assert!(cache.get(&"key").is_none());
+        assert_eq!(cache.get_or_insert_with("key", || 3), 3);
+        assert_eq!(cache.get(&"key"), Some(3));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn caches_without_ttl_never_expire() {
+        let cache = BlockingLruCache::new(NonZeroUsize::new(2).expect("capacity"));
+        cache.insert("key", 1);
+        assert_eq!(cache.get_or_insert_with("key", || 2), 1);
+    }
 
     #[tokio::test(flavor = "multi_thread")]
     async fn stores_and_retrieves_values() {
@@ -169,6 +451,98 @@ mod tests {
         assert_eq!(cache.get(&"c"), Some(3));
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn peek_does_not_update_recency() {
+        let cache = BlockingLruCache::new(NonZeroUsize::new(2).expect("capacity"));
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        assert_eq!(cache.peek(&"a"), Some(1));
+
+        cache.insert("c", 3);
+
+        assert!(cache.peek(&"a").is_none());
+        assert_eq!(cache.peek(&"b"), Some(2));
+        assert_eq!(cache.peek(&"c"), Some(3));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn async_insert_race_keeps_first_value() {
+        let cache = Arc::new(BlockingLruCache::new(
+            NonZeroUsize::new(2).expect("capacity"),
+        ));
+        let barrier = Arc::new(tokio::sync::Barrier::new(2));
+
+        let spawn_insert = |value: i32| {
+            let cache = Arc::clone(&cache);
+            let barrier = Arc::clone(&barrier);
+            tokio::spawn(async move {
+                cache
+                    .get_or_try_insert_with_async("key", || async move {
+                        // Both factories run before either result is inserted.
+                        barrier.wait().await;
+                        Ok::<_, ()>(value)
+                    })
+                    .await
+            })
+        };
+        let first = spawn_insert(1);
+        let second = spawn_insert(2);
+
+        let first = first.await.expect("task").expect("insert");
+        let second = second.await.expect("task").expect("insert");
+        assert_eq!(first, second);
+        assert_eq!(cache.get(&"key"), Some(first));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn async_insert_propagates_errors() {
+        let cache = BlockingLruCache::new(NonZeroUsize::new(2).expect("capacity"));
+
+        let result = cache
+            .get_or_try_insert_with_async("key", || async { Err::<i32, _>("boom") })
+            .await;
+        assert_eq!(result, Err("boom"));
+        assert!(cache.get(&"key").is_none());
+
+        let result = cache
+            .get_or_try_insert_with_async("key", || async { Ok::<_, &str>(7) })
+            .await;
+        assert_eq!(result, Ok(7));
+        assert_eq!(cache.get(&"key"), Some(7));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stats_track_hits_and_misses() {
+        let cache = BlockingLruCache::new(NonZeroUsize::new(4).expect("capacity"));
+
+        assert!(cache.get(&"a").is_none()); // miss
+        assert_eq!(cache.get_or_insert_with("a", || 1), 1); // miss
+        assert_eq!(cache.get_or_insert_with("a", || 2), 1); // hit
+        assert_eq!(cache.get(&"a"), Some(1)); // hit
+        assert_eq!(cache.get_or_try_insert_with("b", || Ok::<_, ()>(2)), Ok(2)); // miss
+        assert_eq!(cache.peek(&"b"), Some(2)); // not counted
+
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 2,
+                misses: 3,
+                len: 2,
+                capacity: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn sha256_digest_matches_known_vector() {
+        let expected: [u8; 32] = [
+            0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae,
+            0x22, 0x23, 0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61,
+            0xf2, 0x00, 0x15, 0xad,
+        ];
+        assert_eq!(sha256_digest(b"abc"), expected);
+    }
+
     #[test]
     fn disabled_without_runtime() {
         let cache = BlockingLruCache::new(NonZeroUsize::new(2).expect("capacity"));
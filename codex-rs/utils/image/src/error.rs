@@ -17,12 +17,25 @@ pub enum ImageProcessingError {
         #[source]
         source: image::ImageError,
     },
+    #[error("image at {path} is too large to process ({width}x{height} pixels)")]
+    TooLarge {
+        path: PathBuf,
+        width: u32,
+        height: u32,
+    },
+    #[error("invalid JPEG quality {quality}; expected a value between 1 and 100")]
+    InvalidQuality { quality: u8 },
     #[error("failed to encode image as {format:?}: {source}")]
     Encode {
         format: ImageFormat,
         #[source]
         source: image::ImageError,
     },
+    #[error("image processing task failed: {source}")]
+    Task {
+        #[source]
+        source: tokio::task::JoinError,
+    },
 }
 
 impl ImageProcessingError {
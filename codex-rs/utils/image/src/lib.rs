@@ -1,28 +1,55 @@
+use std::io::Cursor;
 use std::num::NonZeroUsize;
 use std::path::Path;
+use std::path::PathBuf;
 use std::sync::LazyLock;
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
 
 use crate::error::ImageProcessingError;
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use codex_utils_cache::BlockingLruCache;
 use codex_utils_cache::sha1_digest;
+use image::AnimationDecoder;
 use image::ColorType;
 use image::DynamicImage;
 use image::GenericImageView;
+use image::ImageDecoder;
 use image::ImageEncoder;
+use image::ImageError;
 use image::ImageFormat;
+use image::ImageReader;
+use image::codecs::gif::GifDecoder;
 use image::codecs::jpeg::JpegEncoder;
 use image::codecs::png::PngEncoder;
+use image::error::DecodingError;
 use image::imageops::FilterType;
+use image::metadata::Orientation;
 /// Maximum width used when resizing images before uploading.
 pub const MAX_WIDTH: u32 = 2048;
 /// Maximum height used when resizing images before uploading.
 pub const MAX_HEIGHT: u32 = 768;
+/// JPEG quality used when re-encoding unless a caller overrides it.
+pub const DEFAULT_JPEG_QUALITY: u8 = 85;
+/// Default upper bound on `width * height` accepted before decoding.
+pub const DEFAULT_MAX_PIXELS: i64 = 50_000_000;
+/// Placeholder path reported in errors for images passed to
+/// [`load_and_resize_bytes`].
+pub const IN_MEMORY_PATH: &str = "<in-memory image>";
+
+static MAX_PIXELS: AtomicI64 = AtomicI64::new(DEFAULT_MAX_PIXELS);
+
+/// Overrides the pixel budget checked before decoding. Images whose declared
+/// dimensions exceed it fail with [`ImageProcessingError::TooLarge`]. Zero or
+/// a negative value disables the check.
+pub fn set_max_pixels(max_pixels: i64) {
+    MAX_PIXELS.store(max_pixels, Ordering::Relaxed);
+}
 
 pub mod error;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EncodedImage {
     pub bytes: Vec<u8>,
     pub mime: String,
@@ -37,34 +64,167 @@ impl EncodedImage {
     }
 }
 
-static IMAGE_CACHE: LazyLock<BlockingLruCache<[u8; 20], EncodedImage>> =
+/// Cache key: the file contents plus every setting that affects the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ImageCacheKey {
+    digest: [u8; 20],
+    max_width: u32,
+    max_height: u32,
+    jpeg_quality: u8,
+    max_pixels: i64,
+}
+
+static IMAGE_CACHE: LazyLock<BlockingLruCache<ImageCacheKey, EncodedImage>> =
     LazyLock::new(|| BlockingLruCache::new(NonZeroUsize::new(32).unwrap_or(NonZeroUsize::MIN)));
 
+/// Drops every cached [`EncodedImage`], e.g. in response to memory pressure.
+/// Does nothing outside a Tokio runtime, where the cache is disabled.
+pub fn clear_image_cache() {
+    IMAGE_CACHE.clear();
+}
+
+/// Number of processed images currently held in the cache.
+pub fn image_cache_len() -> usize {
+    IMAGE_CACHE.stats().len
+}
+
 pub fn load_and_resize_to_fit(path: &Path) -> Result<EncodedImage, ImageProcessingError> {
+    load_and_resize_to_fit_with_bounds(path, MAX_WIDTH, MAX_HEIGHT)
+}
+
+/// Like [`load_and_resize_to_fit`], but downscales to fit within
+/// `max_width`×`max_height` instead of [`MAX_WIDTH`]×[`MAX_HEIGHT`].
+pub fn load_and_resize_to_fit_with_bounds(
+    path: &Path,
+    max_width: u32,
+    max_height: u32,
+) -> Result<EncodedImage, ImageProcessingError> {
+    load_and_resize(path, max_width, max_height, DEFAULT_JPEG_QUALITY)
+}
+
+/// Like [`load_and_resize_to_fit`], but re-encodes JPEG output at `quality`
+/// (1-100) instead of [`DEFAULT_JPEG_QUALITY`].
+pub fn load_and_resize_to_fit_with_quality(
+    path: &Path,
+    quality: u8,
+) -> Result<EncodedImage, ImageProcessingError> {
+    if !(1..=100).contains(&quality) {
+        return Err(ImageProcessingError::InvalidQuality { quality });
+    }
+    load_and_resize(path, MAX_WIDTH, MAX_HEIGHT, quality)
+}
+
+fn load_and_resize(
+    path: &Path,
+    max_width: u32,
+    max_height: u32,
+    jpeg_quality: u8,
+) -> Result<EncodedImage, ImageProcessingError> {
     let path_buf = path.to_path_buf();
 
     let file_bytes = read_file_bytes(path, &path_buf)?;
 
-    let key = sha1_digest(&file_bytes);
+    resize_bytes(file_bytes, path_buf, max_width, max_height, jpeg_quality)
+}
+
+/// Async variant of [`load_and_resize_to_fit`]. Reads the file with
+/// `tokio::fs` and decodes on the blocking pool, so it is safe to call from a
+/// current-thread runtime.
+pub async fn load_and_resize_to_fit_async(
+    path: &Path,
+) -> Result<EncodedImage, ImageProcessingError> {
+    let path_buf = path.to_path_buf();
+    let file_bytes = tokio::fs::read(path)
+        .await
+        .map_err(|source| ImageProcessingError::Read {
+            path: path_buf.clone(),
+            source,
+        })?;
+
+    tokio::task::spawn_blocking(move || {
+        resize_bytes(
+            file_bytes,
+            path_buf,
+            MAX_WIDTH,
+            MAX_HEIGHT,
+            DEFAULT_JPEG_QUALITY,
+        )
+    })
+    .await
+    .map_err(|source| ImageProcessingError::Task { source })?
+}
+
+/// Like [`load_and_resize_to_fit`], but processes image bytes that are
+/// already in memory (e.g. pasted from the clipboard) instead of reading a
+/// file. Errors report the path as [`IN_MEMORY_PATH`].
+pub fn load_and_resize_bytes(bytes: &[u8]) -> Result<EncodedImage, ImageProcessingError> {
+    resize_bytes(
+        bytes.to_vec(),
+        PathBuf::from(IN_MEMORY_PATH),
+        MAX_WIDTH,
+        MAX_HEIGHT,
+        DEFAULT_JPEG_QUALITY,
+    )
+}
+
+/// Decodes, resizes and re-encodes `file_bytes`, caching the result by
+/// content hash. `path_buf` is only used in error messages.
+fn resize_bytes(
+    file_bytes: Vec<u8>,
+    path_buf: PathBuf,
+    max_width: u32,
+    max_height: u32,
+    jpeg_quality: u8,
+) -> Result<EncodedImage, ImageProcessingError> {
+    let max_pixels = MAX_PIXELS.load(Ordering::Relaxed);
+    let key = ImageCacheKey {
+        digest: sha1_digest(&file_bytes),
+        max_width,
+        max_height,
+        jpeg_quality,
+        max_pixels,
+    };
 
     IMAGE_CACHE.get_or_try_insert_with(key, move || {
-        let format = match image::guess_format(&file_bytes) {
-            Ok(ImageFormat::Png) => Some(ImageFormat::Png),
-            Ok(ImageFormat::Jpeg) => Some(ImageFormat::Jpeg),
+        let guessed = image::guess_format(&file_bytes).ok();
+        // GIFs are never passed through: only their first frame is kept and
+        // it is re-encoded as PNG.
+        let format = match guessed {
+            Some(ImageFormat::Png) => Some(ImageFormat::Png),
+            Some(ImageFormat::Jpeg) => Some(ImageFormat::Jpeg),
+            Some(ImageFormat::WebP) => Some(ImageFormat::WebP),
             _ => None,
         };
 
-        let dynamic = image::load_from_memory(&file_bytes).map_err(|source| {
-            ImageProcessingError::Decode {
-                path: path_buf.clone(),
-                source,
-            }
+        if guessed == Some(ImageFormat::Avif) && !cfg!(feature = "avif") {
+            return Err(ImageProcessingError::Decode {
+                path: path_buf,
+                source: ImageError::Decoding(DecodingError::new(
+                    ImageFormat::Avif.into(),
+                    "AVIF support is not enabled; build codex-utils-image with the `avif` feature",
+                )),
+            });
+        }
+
+        ensure_within_pixel_budget(&file_bytes, &path_buf, max_pixels)?;
+
+        let decoded = if guessed == Some(ImageFormat::Gif) {
+            decode_first_gif_frame(&file_bytes).map(|image| (image, false))
+        } else {
+            decode_with_orientation(&file_bytes)
+        };
+        let (dynamic, reoriented) = decoded.map_err(|source| ImageProcessingError::Decode {
+            path: path_buf.clone(),
+            source,
         })?;
 
         let (width, height) = dynamic.dimensions();
 
-        let encoded = if width <= MAX_WIDTH && height <= MAX_HEIGHT {
-            if let Some(format) = format {
+        let encoded = if width <= max_width && height <= max_height {
+            // Re-encode rotated images so the bytes match the reported dimensions.
+            if let Some(format) = format
+                && !reoriented
+            {
                 let mime = format_to_mime(format);
                 EncodedImage {
                     bytes: file_bytes,
@@ -73,7 +233,8 @@ pub fn load_and_resize_to_fit(path: &Path) -> Result<EncodedImage, ImageProcessi
                     height,
                 }
             } else {
-                let (bytes, output_format) = encode_image(&dynamic, ImageFormat::Png)?;
+                let (bytes, output_format) =
+                    encode_image(&dynamic, format.unwrap_or(ImageFormat::Png), jpeg_quality)?;
                 let mime = format_to_mime(output_format);
                 EncodedImage {
                     bytes,
@@ -83,9 +244,9 @@ pub fn load_and_resize_to_fit(path: &Path) -> Result<EncodedImage, ImageProcessi
                 }
             }
         } else {
-            let resized = dynamic.resize(MAX_WIDTH, MAX_HEIGHT, FilterType::Triangle);
+            let resized = dynamic.resize(max_width, max_height, FilterType::Triangle);
             let target_format = format.unwrap_or(ImageFormat::Png);
-            let (bytes, output_format) = encode_image(&resized, target_format)?;
+            let (bytes, output_format) = encode_image(&resized, target_format, jpeg_quality)?;
             let mime = format_to_mime(output_format);
             EncodedImage {
                 bytes,
@@ -99,6 +260,59 @@ pub fn load_and_resize_to_fit(path: &Path) -> Result<EncodedImage, ImageProcessi
     })
 }
 
+/// Reads only the image header and rejects images whose declared size exceeds
+/// `max_pixels`, so huge files fail before any pixel data is allocated. A
+/// budget of zero or less accepts every size.
+fn ensure_within_pixel_budget(
+    bytes: &[u8],
+    path: &Path,
+    max_pixels: i64,
+) -> Result<(), ImageProcessingError> {
+    if max_pixels <= 0 {
+        return Ok(());
+    }
+    let (width, height) = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(ImageError::IoError)
+        .and_then(ImageReader::into_dimensions)
+        .map_err(|source| ImageProcessingError::Decode {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    if i64::from(width) * i64::from(height) > max_pixels {
+        return Err(ImageProcessingError::TooLarge {
+            path: path.to_path_buf(),
+            width,
+            height,
+        });
+    }
+    Ok(())
+}
+
+/// Decodes `bytes` and applies any EXIF orientation. Returns whether the
+/// image had to be rotated or flipped.
+fn decode_with_orientation(bytes: &[u8]) -> Result<(DynamicImage, bool), ImageError> {
+    let mut decoder = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()?
+        .into_decoder()?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+    Ok((image, orientation != Orientation::NoTransforms))
+}
+
+/// Decodes only the first frame of a (possibly animated) GIF.
+fn decode_first_gif_frame(bytes: &[u8]) -> Result<DynamicImage, ImageError> {
+    let decoder = GifDecoder::new(Cursor::new(bytes))?;
+    match decoder.into_frames().next() {
+        Some(frame) => Ok(DynamicImage::ImageRgba8(frame?.into_buffer())),
+        None => Err(ImageError::Decoding(DecodingError::new(
+            ImageFormat::Gif.into(),
+            "GIF has no frames",
+        ))),
+    }
+}
+
 fn read_file_bytes(path: &Path, path_for_error: &Path) -> Result<Vec<u8>, ImageProcessingError> {
     match tokio::runtime::Handle::try_current() {
         // If we're inside a Tokio runtime, avoid block_on (it panics on worker threads).
@@ -120,9 +334,14 @@ fn read_file_bytes(path: &Path, path_for_error: &Path) -> Result<Vec<u8>, ImageP
 fn encode_image(
     image: &DynamicImage,
     preferred_format: ImageFormat,
+    jpeg_quality: u8,
 ) -> Result<(Vec<u8>, ImageFormat), ImageProcessingError> {
     let target_format = match preferred_format {
         ImageFormat::Jpeg => ImageFormat::Jpeg,
+        // Only a lossless WebP encoder is available, which bloats photos, so
+        // WebPs that must be re-encoded become JPEG, or PNG to keep alpha.
+        ImageFormat::WebP if image.color().has_alpha() => ImageFormat::Png,
+        ImageFormat::WebP => ImageFormat::Jpeg,
         _ => ImageFormat::Png,
     };
 
@@ -145,7 +364,7 @@ fn encode_image(
                 })?;
         }
         ImageFormat::Jpeg => {
-            let mut encoder = JpegEncoder::new_with_quality(&mut buffer, 85);
+            let mut encoder = JpegEncoder::new_with_quality(&mut buffer, jpeg_quality);
             encoder
                 .encode_image(image)
                 .map_err(|source| ImageProcessingError::Encode {
@@ -162,6 +381,7 @@ fn encode_image(
 fn format_to_mime(format: ImageFormat) -> String {
     match format {
         ImageFormat::Jpeg => "image/jpeg".to_string(),
+        ImageFormat::WebP => "image/webp".to_string(),
         _ => "image/png".to_string(),
     }
 }
@@ -171,6 +391,7 @@ mod tests {
     use super::*;
     use image::GenericImageView;
     use image::ImageBuffer;
+    use image::Rgb;
     use image::Rgba;
     use tempfile::NamedTempFile;
 
@@ -210,6 +431,361 @@ mod tests {
         assert_eq!(loaded.dimensions(), (processed.width, processed.height));
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn returns_original_webp_when_within_bounds() {
+        let temp_file = NamedTempFile::new().expect("temp file");
+        let image = ImageBuffer::from_pixel(64, 32, Rgba([10u8, 20, 30, 255]));
+        image
+            .save_with_format(temp_file.path(), ImageFormat::WebP)
+            .expect("write webp to temp file");
+
+        let original_bytes = std::fs::read(temp_file.path()).expect("read written image");
+
+        let encoded = load_and_resize_to_fit(temp_file.path()).expect("process image");
+
+        assert_eq!(encoded.width, 64);
+        assert_eq!(encoded.height, 32);
+        assert_eq!(encoded.mime, "image/webp");
+        assert_eq!(encoded.bytes, original_bytes);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn downscales_opaque_webp_as_jpeg() {
+        let temp_file = NamedTempFile::new().expect("temp file");
+        let image = ImageBuffer::from_pixel(4096, 1024, Rgb([200u8, 10, 10]));
+        image
+            .save_with_format(temp_file.path(), ImageFormat::WebP)
+            .expect("write webp to temp file");
+
+        let processed = load_and_resize_to_fit(temp_file.path()).expect("process image");
+
+        assert_eq!(
+            (
+                processed.mime.as_str(),
+                processed.width,
+                processed.height,
+                image::guess_format(&processed.bytes).expect("guess format"),
+            ),
+            ("image/jpeg", MAX_WIDTH, 512, ImageFormat::Jpeg)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn downscales_transparent_webp_as_png() {
+        let temp_file = NamedTempFile::new().expect("temp file");
+        let image = ImageBuffer::from_pixel(4096, 1024, Rgba([200u8, 10, 10, 128]));
+        image
+            .save_with_format(temp_file.path(), ImageFormat::WebP)
+            .expect("write webp to temp file");
+
+        let processed = load_and_resize_to_fit(temp_file.path()).expect("process image");
+
+        assert_eq!(
+            (
+                processed.mime.as_str(),
+                processed.width,
+                processed.height,
+                image::guess_format(&processed.bytes).expect("guess format"),
+            ),
+            ("image/png", MAX_WIDTH, 512, ImageFormat::Png)
+        );
+    }
+
+    /// Minimal little-endian EXIF block holding only the orientation tag.
+    fn exif_with_orientation(orientation: u8) -> Vec<u8> {
+        vec![
+            0x49,
+            0x49,
+            0x2A,
+            0x00,
+            0x08,
+            0x00,
+            0x00,
+            0x00, // TIFF header, IFD at offset 8
+            0x01,
+            0x00, // one entry
+            0x12,
+            0x01,
+            0x03,
+            0x00,
+            0x01,
+            0x00,
+            0x00,
+            0x00, // Orientation, SHORT, count 1
+            orientation,
+            0x00,
+            0x00,
+            0x00, // value
+            0x00,
+            0x00,
+            0x00,
+            0x00, // no next IFD
+        ]
+    }
+
+    fn write_jpeg_with_orientation(path: &Path, width: u32, height: u32, orientation: u8) {
+        let image = ImageBuffer::from_pixel(width, height, image::Rgb([10u8, 20, 30]));
+        let mut bytes = Vec::new();
+        let mut encoder = JpegEncoder::new(&mut bytes);
+        encoder
+            .set_exif_metadata(exif_with_orientation(orientation))
+            .expect("set exif");
+        encoder
+            .write_image(image.as_raw(), width, height, ColorType::Rgb8.into())
+            .expect("encode jpeg");
+        std::fs::write(path, bytes).expect("write jpeg");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn applies_exif_orientation_before_sizing() {
+        for (orientation, expected) in [(1, (64, 32)), (6, (32, 64)), (8, (32, 64))] {
+            let temp_file = NamedTempFile::new().expect("temp file");
+            write_jpeg_with_orientation(temp_file.path(), 64, 32, orientation);
+
+            let encoded = load_and_resize_to_fit(temp_file.path()).expect("process image");
+            let loaded = image::load_from_memory(&encoded.bytes).expect("decode output");
+
+            assert_eq!(
+                (encoded.width, encoded.height),
+                expected,
+                "orientation {orientation}"
+            );
+            assert_eq!(loaded.dimensions(), expected, "orientation {orientation}");
+            assert_eq!(encoded.mime, "image/jpeg");
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn bounds_are_part_of_the_cache_key() {
+        let temp_file = NamedTempFile::new().expect("temp file");
+        let image = ImageBuffer::from_pixel(1024, 1024, Rgba([200u8, 10, 10, 255]));
+        image
+            .save_with_format(temp_file.path(), ImageFormat::Png)
+            .expect("write png to temp file");
+
+        let small =
+            load_and_resize_to_fit_with_bounds(temp_file.path(), 128, 128).expect("process small");
+        let large =
+            load_and_resize_to_fit_with_bounds(temp_file.path(), 512, 512).expect("process large");
+        let small_again = load_and_resize_to_fit_with_bounds(temp_file.path(), 128, 128)
+            .expect("process small again");
+
+        assert_eq!((small.width, small.height), (128, 128));
+        assert_eq!((large.width, large.height), (512, 512));
+        assert_ne!(small.bytes, large.bytes);
+        assert_eq!(small_again.bytes, small.bytes);
+    }
+
+    fn crc32(bytes: &[u8]) -> u32 {
+        let mut crc = 0xFFFF_FFFFu32;
+        for byte in bytes {
+            crc ^= u32::from(*byte);
+            for _ in 0..8 {
+                let mask = (crc & 1).wrapping_neg();
+                crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+            }
+        }
+        !crc
+    }
+
+    fn push_png_chunk(png: &mut Vec<u8>, kind: [u8; 4], data: &[u8]) {
+        let mut chunk = kind.to_vec();
+        chunk.extend_from_slice(data);
+        let len = u32::try_from(data.len()).expect("chunk length");
+        png.extend_from_slice(&len.to_be_bytes());
+        png.extend_from_slice(&chunk);
+        png.extend_from_slice(&crc32(&chunk).to_be_bytes());
+    }
+
+    /// A PNG declaring `width`×`height` in its header but carrying no pixel
+    /// data at all.
+    fn png_header_only(width: u32, height: u32) -> Vec<u8> {
+        let mut ihdr = Vec::new();
+        ihdr.extend_from_slice(&width.to_be_bytes());
+        ihdr.extend_from_slice(&height.to_be_bytes());
+        ihdr.extend_from_slice(&[8, 6, 0, 0, 0]); // 8-bit RGBA, no interlace
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        push_png_chunk(&mut png, *b"IHDR", &ihdr);
+        push_png_chunk(&mut png, *b"IDAT", &[]);
+        push_png_chunk(&mut png, *b"IEND", &[]);
+        png
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rejects_images_over_pixel_budget_before_decoding() {
+        let temp_file = NamedTempFile::new().expect("temp file");
+        std::fs::write(temp_file.path(), png_header_only(100_000, 100_000)).expect("write png");
+
+        let err = load_and_resize_to_fit(temp_file.path()).expect_err("huge image should fail");
+        match err {
+            ImageProcessingError::TooLarge { width, height, .. } => {
+                assert_eq!((width, height), (100_000, 100_000));
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn jpeg_quality_affects_resized_output() {
+        let temp_file = NamedTempFile::new().expect("temp file");
+        let image = ImageBuffer::from_fn(4096, 2048, |x, y| {
+            image::Rgb([(x % 251) as u8, (y % 241) as u8, ((x * y) % 239) as u8])
+        });
+        image
+            .save_with_format(temp_file.path(), ImageFormat::Jpeg)
+            .expect("write jpeg to temp file");
+
+        let low = load_and_resize_to_fit_with_quality(temp_file.path(), 50).expect("quality 50");
+        let high = load_and_resize_to_fit_with_quality(temp_file.path(), 95).expect("quality 95");
+
+        assert_eq!(low.mime, "image/jpeg");
+        assert_eq!((low.width, low.height), (high.width, high.height));
+        assert!(
+            high.bytes.len() > low.bytes.len() * 3 / 2,
+            "expected quality 95 ({}) to be much larger than quality 50 ({})",
+            high.bytes.len(),
+            low.bytes.len()
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range_jpeg_quality() {
+        for quality in [0, 101] {
+            let err = load_and_resize_to_fit_with_quality(Path::new("unused.jpg"), quality)
+                .expect_err("quality should be rejected");
+            assert!(
+                matches!(err, ImageProcessingError::InvalidQuality { quality: q } if q == quality)
+            );
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn animated_gif_is_reduced_to_first_frame_png() {
+        let temp_file = NamedTempFile::new().expect("temp file");
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = image::codecs::gif::GifEncoder::new(&mut bytes);
+            encoder
+                .encode_frames([
+                    image::Frame::new(ImageBuffer::from_pixel(32, 16, Rgba([255u8, 0, 0, 255]))),
+                    image::Frame::new(ImageBuffer::from_pixel(32, 16, Rgba([0u8, 0, 255, 255]))),
+                ])
+                .expect("encode gif");
+        }
+        std::fs::write(temp_file.path(), &bytes).expect("write gif");
+
+        let encoded = load_and_resize_to_fit(temp_file.path()).expect("process gif");
+
+        assert_eq!(encoded.mime, "image/png");
+        assert_eq!((encoded.width, encoded.height), (32, 16));
+        assert_eq!(
+            image::guess_format(&encoded.bytes).expect("guess format"),
+            ImageFormat::Png
+        );
+        let decoder = image::codecs::png::PngDecoder::new(Cursor::new(encoded.bytes.as_slice()))
+            .expect("decode png header");
+        assert!(!decoder.is_apng().expect("check apng"));
+        let loaded = image::load_from_memory(&encoded.bytes).expect("decode output");
+        let Rgba([red, green, blue, _]) = loaded.get_pixel(0, 0);
+        assert!(
+            red > 200 && green < 50 && blue < 50,
+            "unexpected first pixel"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn bytes_and_path_produce_identical_output() {
+        let temp_file = NamedTempFile::new().expect("temp file");
+        let image = ImageBuffer::from_pixel(4096, 1024, Rgba([30u8, 60, 90, 255]));
+        image
+            .save_with_format(temp_file.path(), ImageFormat::Png)
+            .expect("write png to temp file");
+        let bytes = std::fs::read(temp_file.path()).expect("read written image");
+
+        let from_path = load_and_resize_to_fit(temp_file.path()).expect("process path");
+        clear_image_cache();
+        let from_bytes = load_and_resize_bytes(&bytes).expect("process bytes");
+
+        assert_eq!(from_bytes, from_path);
+    }
+
+    #[test]
+    fn invalid_bytes_report_in_memory_path() {
+        let err = load_and_resize_bytes(b"not an image").expect_err("invalid image should fail");
+        match err {
+            ImageProcessingError::Decode { path, .. } => {
+                assert_eq!(path, PathBuf::from(IN_MEMORY_PATH));
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn async_api_works_on_current_thread_runtime() {
+        let temp_file = NamedTempFile::new().expect("temp file");
+        let image = ImageBuffer::from_pixel(4096, 1024, Rgba([90u8, 60, 30, 255]));
+        image
+            .save_with_format(temp_file.path(), ImageFormat::Png)
+            .expect("write png to temp file");
+
+        let encoded = load_and_resize_to_fit_async(temp_file.path())
+            .await
+            .expect("process image");
+
+        assert_eq!(encoded.mime, "image/png");
+        assert_eq!((encoded.width, encoded.height), (MAX_WIDTH, 512));
+        let loaded = image::load_from_memory(&encoded.bytes).expect("decode output");
+        assert_eq!(loaded.dimensions(), (MAX_WIDTH, 512));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn async_api_reports_missing_files() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = dir.path().join("missing.png");
+
+        let err = load_and_resize_to_fit_async(&path)
+            .await
+            .expect_err("missing file should fail");
+        match err {
+            ImageProcessingError::Read { path: err_path, .. } => assert_eq!(err_path, path),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[cfg(feature = "avif")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn decodes_avif_and_reencodes_as_png() {
+        // A solid 4096x1024 image, checked in so tests do not need an AVIF encoder.
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/red_4096x1024.avif");
+
+        let encoded = load_and_resize_to_fit(&path).expect("process avif");
+
+        assert_eq!(encoded.mime, "image/png");
+        assert_eq!((encoded.width, encoded.height), (MAX_WIDTH, 512));
+        assert_eq!(
+            image::guess_format(&encoded.bytes).expect("guess format"),
+            ImageFormat::Png
+        );
+    }
+
+    #[cfg(not(feature = "avif"))]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn avif_without_feature_is_a_decode_error() {
+        let temp_file = NamedTempFile::new().expect("temp file");
+        // Just the `ftyp` box, which is all format detection looks at.
+        let mut bytes = vec![0, 0, 0, 0x14];
+        bytes.extend_from_slice(b"ftypavif\0\0\0\0avif");
+        std::fs::write(temp_file.path(), bytes).expect("write avif");
+
+        let err = load_and_resize_to_fit(temp_file.path()).expect_err("avif should fail");
+        assert!(err.is_invalid_image(), "unexpected error: {err:?}");
+        assert!(
+            err.to_string().contains("`avif` feature"),
+            "unexpected message: {err}"
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn fails_cleanly_for_invalid_images() {
         let temp_file = NamedTempFile::new().expect("temp file");
@@ -224,9 +800,7 @@ mod tests {
 
     #[tokio::test(flavor = "multi_thread")]
     async fn reprocesses_updated_file_contents() {
-        {
-            IMAGE_CACHE.clear();
-        }
+        clear_image_cache();
 
         let temp_file = NamedTempFile::new().expect("temp file");
         let first_image = ImageBuffer::from_pixel(32, 16, Rgba([20u8, 120, 220, 255]));
@@ -0,0 +1,26 @@
+//! Lives in its own test binary so no other test touches the global image
+//! cache while the length is being asserted.
+
+use codex_utils_image::clear_image_cache;
+use codex_utils_image::image_cache_len;
+use codex_utils_image::load_and_resize_to_fit;
+use image::ImageBuffer;
+use image::ImageFormat;
+use image::Rgba;
+use tempfile::NamedTempFile;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn clear_image_cache_empties_the_cache() {
+    let temp_file = NamedTempFile::new().expect("temp file");
+    let image = ImageBuffer::from_pixel(32, 16, Rgba([10u8, 20, 30, 255]));
+    image
+        .save_with_format(temp_file.path(), ImageFormat::Png)
+        .expect("write png to temp file");
+
+    clear_image_cache();
+    load_and_resize_to_fit(temp_file.path()).expect("process image");
+    assert_eq!(image_cache_len(), 1);
+
+    clear_image_cache();
+    assert_eq!(image_cache_len(), 0);
+}
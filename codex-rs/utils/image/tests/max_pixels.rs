@@ -0,0 +1,52 @@
+//! Lives in its own test binary because `set_max_pixels` changes a global
+//! that the unit tests rely on.
+
+use codex_utils_image::DEFAULT_MAX_PIXELS;
+use codex_utils_image::error::ImageProcessingError;
+use codex_utils_image::load_and_resize_to_fit;
+use codex_utils_image::set_max_pixels;
+use image::ImageBuffer;
+use image::ImageFormat;
+use image::Rgba;
+use tempfile::NamedTempFile;
+
+fn dimensions(path: &std::path::Path) -> Result<(u32, u32), String> {
+    load_and_resize_to_fit(path)
+        .map(|image| (image.width, image.height))
+        .map_err(|err| match err {
+            ImageProcessingError::TooLarge { width, height, .. } => {
+                format!("too large: {width}x{height}")
+            }
+            other => format!("unexpected error: {other}"),
+        })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pixel_budget_changes_apply_to_cached_images() {
+    let temp_file = NamedTempFile::new().expect("temp file");
+    let image = ImageBuffer::from_pixel(10, 10, Rgba([10u8, 20, 30, 255]));
+    image
+        .save_with_format(temp_file.path(), ImageFormat::Png)
+        .expect("write png to temp file");
+
+    // The first load caches the image under the default budget; a tighter
+    // budget must still reject it rather than serve the cached copy.
+    let default_budget = dimensions(temp_file.path());
+    set_max_pixels(99);
+    let tight_budget = dimensions(temp_file.path());
+    set_max_pixels(0);
+    let zero_budget = dimensions(temp_file.path());
+    set_max_pixels(-1);
+    let negative_budget = dimensions(temp_file.path());
+    set_max_pixels(DEFAULT_MAX_PIXELS);
+
+    assert_eq!(
+        (default_budget, tight_budget, zero_budget, negative_budget),
+        (
+            Ok((10, 10)),
+            Err("too large: 10x10".to_string()),
+            Ok((10, 10)),
+            Ok((10, 10)),
+        )
+    );
+}
@@ -9,23 +9,71 @@ use std::time::Duration;
 use anyhow::Result;
 use portable_pty::native_pty_system;
 use portable_pty::CommandBuilder;
+use portable_pty::MasterPty;
 use portable_pty::PtySize;
 use tokio::sync::broadcast;
 use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::SendError;
 use tokio::sync::oneshot;
 use tokio::sync::Mutex as TokioMutex;
+use tokio::sync::Notify;
 use tokio::task::JoinHandle;
 
+/// Initial dimensions of the pseudo-terminal handed to the child.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalSize {
+    pub rows: u16,
+    pub cols: u16,
+}
+
+impl Default for TerminalSize {
+    fn default() -> Self {
+        Self { rows: 24, cols: 80 }
+    }
+}
+
+impl From<TerminalSize> for PtySize {
+    fn from(size: TerminalSize) -> Self {
+        PtySize {
+            rows: size.rows,
+            cols: size.cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        }
+    }
+}
+
+/// Signals that can be delivered to a running PTY child without tearing
+/// down the session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    Interrupt,
+    Terminate,
+}
+
+/// The PTY master side, kept so the session can resize the terminal.
+struct PtyMaster(Box<dyn MasterPty + Send>);
+
+impl std::fmt::Debug for PtyMaster {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PtyMaster").finish_non_exhaustive()
+    }
+}
+
 #[derive(Debug)]
 pub struct ExecCommandSession {
     writer_tx: mpsc::Sender<Vec<u8>>,
     output_tx: broadcast::Sender<Vec<u8>>,
+    master: StdMutex<PtyMaster>,
+    #[cfg_attr(not(unix), allow(dead_code))]
+    process_id: Option<i32>,
     killer: StdMutex<Option<Box<dyn portable_pty::ChildKiller + Send + Sync>>>,
     reader_handle: StdMutex<Option<JoinHandle<()>>>,
     writer_handle: StdMutex<Option<JoinHandle<()>>>,
     wait_handle: StdMutex<Option<JoinHandle<()>>>,
     exit_status: Arc<AtomicBool>,
     exit_code: Arc<StdMutex<Option<i32>>>,
+    exit_notify: Arc<Notify>,
 }
 
 impl ExecCommandSession {
@@ -33,24 +81,30 @@ impl ExecCommandSession {
     pub fn new(
         writer_tx: mpsc::Sender<Vec<u8>>,
         output_tx: broadcast::Sender<Vec<u8>>,
+        master: Box<dyn MasterPty + Send>,
+        process_id: Option<i32>,
         killer: Box<dyn portable_pty::ChildKiller + Send + Sync>,
         reader_handle: JoinHandle<()>,
         writer_handle: JoinHandle<()>,
         wait_handle: JoinHandle<()>,
         exit_status: Arc<AtomicBool>,
         exit_code: Arc<StdMutex<Option<i32>>>,
+        exit_notify: Arc<Notify>,
     ) -> (Self, broadcast::Receiver<Vec<u8>>) {
         let initial_output_rx = output_tx.subscribe();
         (
             Self {
                 writer_tx,
                 output_tx,
+                master: StdMutex::new(PtyMaster(master)),
+                process_id,
                 killer: StdMutex::new(Some(killer)),
                 reader_handle: StdMutex::new(Some(reader_handle)),
                 writer_handle: StdMutex::new(Some(writer_handle)),
                 wait_handle: StdMutex::new(Some(wait_handle)),
                 exit_status,
                 exit_code,
+                exit_notify,
             },
             initial_output_rx,
         )
@@ -64,6 +118,16 @@ impl ExecCommandSession {
         self.output_tx.subscribe()
     }
 
+    /// Send `s` to the child's stdin.
+    pub async fn write_str(&self, s: &str) -> Result<(), SendError<Vec<u8>>> {
+        self.writer_tx.send(s.as_bytes().to_vec()).await
+    }
+
+    /// Send `s` followed by a newline to the child's stdin.
+    pub async fn write_line(&self, s: &str) -> Result<(), SendError<Vec<u8>>> {
+        self.writer_tx.send(format!("{s}\n").into_bytes()).await
+    }
+
     pub fn has_exited(&self) -> bool {
         self.exit_status.load(std::sync::atomic::Ordering::SeqCst)
     }
@@ -71,6 +135,84 @@ impl ExecCommandSession {
     pub fn exit_code(&self) -> Option<i32> {
         self.exit_code.lock().ok().and_then(|guard| *guard)
     }
+
+    /// Wait up to `dur` for the child to exit, returning its exit code or
+    /// `None` on timeout. Unlike `exit_rx`, this can be called repeatedly.
+    pub async fn wait_with_timeout(&self, dur: Duration) -> Option<i32> {
+        let deadline = tokio::time::Instant::now() + dur;
+        loop {
+            let notified = self.exit_notify.notified();
+            tokio::pin!(notified);
+            // Register before checking so an exit in between is not missed.
+            notified.as_mut().enable();
+            if self.has_exited() {
+                return self.exit_code();
+            }
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                return None;
+            }
+        }
+    }
+
+    /// Resize the terminal; the child is notified via `SIGWINCH`.
+    pub fn resize(&self, rows: u16, cols: u16) -> Result<()> {
+        let master = self
+            .master
+            .lock()
+            .map_err(|_| anyhow::anyhow!("pty master lock poisoned"))?;
+        master.0.resize(TerminalSize { rows, cols }.into())
+    }
+
+    /// Deliver `sig` to the child while keeping the session open.
+    ///
+    /// On Unix the signal goes to the child's process group. On Windows,
+    /// `Interrupt` is sent as a Ctrl-C console event and `Terminate` falls
+    /// back to a hard kill. Fails once the child has exited.
+    pub fn signal(&self, sig: Signal) -> Result<()> {
+        // After the child is reaped its pid may be reused by another process.
+        if self.has_exited() {
+            anyhow::bail!("pty child has already exited");
+        }
+
+        #[cfg(unix)]
+        {
+            let Some(pid) = self.process_id else {
+                anyhow::bail!("pty child has no process id");
+            };
+            let signo = match sig {
+                Signal::Interrupt => libc::SIGINT,
+                Signal::Terminate => libc::SIGTERM,
+            };
+            // The child is a session leader, so its pid is also its pgid.
+            // SAFETY: `kill` takes plain integers and touches no memory. The
+            // exit check above keeps us from signalling a pid that was already
+            // reaped and possibly reused.
+            if unsafe { libc::kill(-pid, signo) } == -1 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+            Ok(())
+        }
+
+        #[cfg(windows)]
+        {
+            match sig {
+                Signal::Interrupt => self
+                    .writer_tx
+                    .try_send(vec![0x03])
+                    .map_err(|err| anyhow::anyhow!("failed to send ctrl-c: {err}")),
+                Signal::Terminate => {
+                    let killer = self
+                        .killer
+                        .lock()
+                        .map_err(|_| anyhow::anyhow!("pty killer lock poisoned"))?;
+                    match killer.as_ref() {
+                        Some(killer) => Ok(killer.clone_killer().kill()?),
+                        None => anyhow::bail!("pty child already killed"),
+                    }
+                }
+            }
+        }
+    }
 }
 
 impl Drop for ExecCommandSession {
@@ -112,18 +254,14 @@ pub async fn spawn_pty_process(
     cwd: &Path,
     env: &HashMap<String, String>,
     arg0: &Option<String>,
+    size: TerminalSize,
 ) -> Result<SpawnedPty> {
     if program.is_empty() {
         anyhow::bail!("missing program for PTY spawn");
     }
 
     let pty_system = native_pty_system();
-    let pair = pty_system.openpty(PtySize {
-        rows: 24,
-        cols: 80,
-        pixel_width: 0,
-        pixel_height: 0,
-    })?;
+    let pair = pty_system.openpty(size.into())?;
 
     let mut command_builder = CommandBuilder::new(arg0.as_ref().unwrap_or(&program.to_string()));
     command_builder.cwd(cwd);
@@ -136,6 +274,7 @@ pub async fn spawn_pty_process(
     }
 
     let mut child = pair.slave.spawn_command(command_builder)?;
+    let process_id = child.process_id().and_then(|pid| i32::try_from(pid).ok());
     let killer = child.clone_killer();
 
     let (writer_tx, mut writer_rx) = mpsc::channel::<Vec<u8>>(128);
@@ -180,27 +319,33 @@ pub async fn spawn_pty_process(
     let wait_exit_status = Arc::clone(&exit_status);
     let exit_code = Arc::new(StdMutex::new(None));
     let wait_exit_code = Arc::clone(&exit_code);
+    let exit_notify = Arc::new(Notify::new());
+    let wait_exit_notify = Arc::clone(&exit_notify);
     let wait_handle: JoinHandle<()> = tokio::task::spawn_blocking(move || {
         let code = match child.wait() {
             Ok(status) => status.exit_code() as i32,
             Err(_) => -1,
         };
-        wait_exit_status.store(true, std::sync::atomic::Ordering::SeqCst);
         if let Ok(mut guard) = wait_exit_code.lock() {
             *guard = Some(code);
         }
+        wait_exit_status.store(true, std::sync::atomic::Ordering::SeqCst);
+        wait_exit_notify.notify_waiters();
         let _ = exit_tx.send(code);
     });
 
     let (session, output_rx) = ExecCommandSession::new(
         writer_tx,
         output_tx,
+        pair.master,
+        process_id,
         killer,
         reader_handle,
         writer_handle,
         wait_handle,
         exit_status,
         exit_code,
+        exit_notify,
     );
 
     Ok(SpawnedPty {
@@ -209,3 +354,121 @@ pub async fn spawn_pty_process(
         exit_rx,
     })
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn resize_is_visible_to_child() -> Result<()> {
+        let env: HashMap<String, String> = std::env::vars().collect();
+        let cwd = std::env::current_dir()?;
+        // Wait for a line on stdin so the resize lands before `stty` runs.
+        let args = vec!["-c".to_string(), "read _; stty size".to_string()];
+        let SpawnedPty {
+            session,
+            mut output_rx,
+            exit_rx,
+        } = spawn_pty_process("/bin/sh", &args, &cwd, &env, &None, TerminalSize::default()).await?;
+
+        session.resize(40, 120)?;
+        session.writer_sender().send(b"\n".to_vec()).await?;
+
+        let code = tokio::time::timeout(Duration::from_secs(10), exit_rx).await??;
+        let mut output = Vec::new();
+        // The reader may still be draining the PTY after the child exits.
+        while let Ok(Ok(chunk)) =
+            tokio::time::timeout(Duration::from_millis(200), output_rx.recv()).await
+        {
+            output.extend(chunk);
+        }
+        let output = String::from_utf8_lossy(&output);
+        let reported = output
+            .lines()
+            .map(str::trim)
+            .find(|line| line.contains(' '));
+
+        assert_eq!((code, reported), (0, Some("40 120")));
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn terminate_signal_ends_child() -> Result<()> {
+        let env: HashMap<String, String> = std::env::vars().collect();
+        let cwd = std::env::current_dir()?;
+        let SpawnedPty {
+            session, exit_rx, ..
+        } = spawn_pty_process(
+            "sleep",
+            &["10".to_string()],
+            &cwd,
+            &env,
+            &None,
+            TerminalSize::default(),
+        )
+        .await?;
+
+        session.signal(Signal::Terminate)?;
+
+        // portable-pty reports signal deaths as exit code 1.
+        let code = tokio::time::timeout(Duration::from_secs(5), exit_rx).await??;
+        let signal_after_exit = session.signal(Signal::Terminate).map_err(|e| e.to_string());
+        assert_eq!(
+            (code, session.has_exited(), signal_after_exit),
+            (1, true, Err("pty child has already exited".to_string()))
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn wait_with_timeout_reports_exit_or_timeout() -> Result<()> {
+        let env: HashMap<String, String> = std::env::vars().collect();
+        let cwd = std::env::current_dir()?;
+        let spawn = |args: &'static str| {
+            let args = vec!["-c".to_string(), args.to_string()];
+            let env = env.clone();
+            let cwd = cwd.clone();
+            async move {
+                spawn_pty_process("/bin/sh", &args, &cwd, &env, &None, TerminalSize::default())
+                    .await
+            }
+        };
+
+        let fast = spawn("exit 3").await?;
+        let slow = spawn("sleep 10").await?;
+
+        let fast_code = fast.session.wait_with_timeout(Duration::from_secs(5)).await;
+        let slow_code = slow
+            .session
+            .wait_with_timeout(Duration::from_millis(200))
+            .await;
+        // The oneshot receiver is still usable after waiting.
+        let fast_rx_code = fast.exit_rx.await?;
+
+        assert_eq!((fast_code, slow_code, fast_rx_code), (Some(3), None, 3));
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn write_line_round_trips_through_cat() -> Result<()> {
+        let env: HashMap<String, String> = std::env::vars().collect();
+        let cwd = std::env::current_dir()?;
+        let SpawnedPty { session, .. } =
+            spawn_pty_process("cat", &[], &cwd, &env, &None, TerminalSize::default()).await?;
+        let mut output_rx = session.output_receiver();
+
+        session.write_line("hello").await?;
+
+        // The terminal echoes the input, then `cat` prints it back.
+        let expected = "hello\r\nhello\r\n";
+        let mut output = String::new();
+        while output.len() < expected.len() {
+            let chunk = tokio::time::timeout(Duration::from_secs(5), output_rx.recv()).await??;
+            output.push_str(&String::from_utf8_lossy(&chunk));
+        }
+
+        assert_eq!(output, expected);
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
 //! Readiness flag with token-based authorization and async waiting (Tokio).
 
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicI32;
 use std::sync::atomic::Ordering;
@@ -10,8 +11,16 @@ use std::time::Duration;
 use tokio::sync::Mutex;
 use tokio::sync::watch;
 use tokio::time;
+use tokio::time::Instant;
 
-/// Opaque subscription token returned by `subscribe()`.
+#[cfg(any(test, feature = "test-util"))]
+mod manual;
+
+pub use errors::ReadinessError;
+#[cfg(any(test, feature = "test-util"))]
+pub use manual::ManualReadiness;
+
+/// Opaque subscription token returned by `subscribe()` and `subscribe_with_ttl()`.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct Token(i32);
 
@@ -22,13 +31,28 @@ pub trait Readiness: Send + Sync + 'static {
     /// Returns true if the flag is currently marked ready. At least one token needs to be marked
     /// as ready before.
     /// `true` is not reversible.
+    ///
+    /// Note: when there are no active subscriptions this marks the flag ready as a side effect.
+    /// Use `peek_ready()` to observe the state without mutating it.
     fn is_ready(&self) -> bool;
 
+    /// Returns true if the flag is currently marked ready, without side effects.
+    ///
+    /// Unlike `is_ready()`, this never marks the flag ready and never takes the token lock.
+    fn peek_ready(&self) -> bool;
+
     /// Subscribe to readiness and receive an authorization token.
     ///
     /// If the flag is already ready, returns `FlagAlreadyReady`.
     async fn subscribe(&self) -> Result<Token, errors::ReadinessError>;
 
+    /// Like `subscribe()`, but the token expires after `ttl`.
+    ///
+    /// Expired tokens are dropped from the subscription set, so a subscriber that never calls
+    /// `mark_ready` cannot block readiness forever. `mark_ready` on an expired token returns
+    /// `Ok(false)`.
+    async fn subscribe_with_ttl(&self, ttl: Duration) -> Result<Token, errors::ReadinessError>;
+
     /// Attempt to mark the flag ready, validated by the provided token.
     ///
     /// Returns `true` iff:
@@ -36,8 +60,22 @@ pub trait Readiness: Send + Sync + 'static {
     /// - the flag was not already ready.
     async fn mark_ready(&self, token: Token) -> Result<bool, errors::ReadinessError>;
 
+    /// Withdraw a subscription without marking the flag ready.
+    ///
+    /// Returns `true` iff `token` was currently subscribed. Once the last token is withdrawn,
+    /// `is_ready()` may mark the flag ready.
+    async fn unsubscribe(&self, token: Token) -> Result<bool, errors::ReadinessError>;
+
     /// Asynchronously wait until the flag becomes ready.
     async fn wait_ready(&self);
+
+    /// Return the flag to its initial, not-ready state so it can be reused.
+    ///
+    /// All outstanding tokens are invalidated. Implementations that cannot be reset return
+    /// `Unsupported`.
+    async fn reset(&self) -> Result<(), errors::ReadinessError> {
+        Err(errors::ReadinessError::Unsupported)
+    }
 }
 
 pub struct ReadinessFlag {
@@ -45,34 +83,103 @@ pub struct ReadinessFlag {
     ready: AtomicBool,
     /// Used to generate the next i32 token.
     next_id: AtomicI32,
-    /// Set of active subscriptions.
-    tokens: Mutex<HashSet<Token>>,
+    /// Active subscriptions, each with an optional expiry deadline.
+    tokens: Mutex<HashMap<Token, Option<Instant>>>,
     /// Broadcasts readiness to async waiters.
     tx: watch::Sender<bool>,
+    /// Maximum time to wait for the `tokens` lock.
+    lock_timeout: Duration,
+    /// When the flag was constructed; `on_ready` reports the time since.
+    created_at: Instant,
+    /// Called once, the first time the flag becomes ready.
+    on_ready: Option<Box<dyn Fn(Duration) + Send + Sync>>,
+    /// Set once `on_ready` has been claimed, so it never runs twice.
+    on_ready_fired: AtomicBool,
 }
 
 impl ReadinessFlag {
     /// Create a new, not-yet-ready flag.
     pub fn new() -> Self {
+        Self::with_lock_timeout(LOCK_TIMEOUT)
+    }
+
+    /// Create a new, not-yet-ready flag whose mutators give up acquiring the token lock after
+    /// `lock_timeout`.
+    pub fn with_lock_timeout(lock_timeout: Duration) -> Self {
         let (tx, _rx) = watch::channel(false);
         Self {
             ready: AtomicBool::new(false),
             next_id: AtomicI32::new(1), // Reserve 0.
-            tokens: Mutex::new(HashSet::new()),
+            tokens: Mutex::new(HashMap::new()),
             tx,
+            lock_timeout,
+            created_at: Instant::now(),
+            on_ready: None,
+            on_ready_fired: AtomicBool::new(false),
+        }
+    }
+
+    /// Install a hook that runs exactly once, the first time the flag becomes ready (through
+    /// `mark_ready` or the `is_ready` auto-path), with the time elapsed since construction.
+    /// Useful for emitting readiness metrics. The hook runs on the caller's thread, so keep it
+    /// cheap.
+    pub fn with_on_ready(mut self, on_ready: impl Fn(Duration) + Send + Sync + 'static) -> Self {
+        self.on_ready = Some(Box::new(on_ready));
+        self
+    }
+
+    /// Broadcast readiness and run `on_ready` if it has not run yet.
+    fn notify_ready(&self) {
+        // Best-effort broadcast; ignore error if there are no receivers.
+        let _ = self.tx.send(true);
+        if let Some(on_ready) = &self.on_ready
+            && !self.on_ready_fired.swap(true, Ordering::AcqRel)
+        {
+            on_ready(self.created_at.elapsed());
         }
     }
 
     async fn with_tokens<R>(
         &self,
-        f: impl FnOnce(&mut HashSet<Token>) -> R,
+        f: impl FnOnce(&mut HashMap<Token, Option<Instant>>) -> R,
     ) -> Result<R, errors::ReadinessError> {
-        let mut guard = time::timeout(LOCK_TIMEOUT, self.tokens.lock())
+        let mut guard = time::timeout(self.lock_timeout, self.tokens.lock())
             .await
             .map_err(|_| errors::ReadinessError::TokenLockFailed)?;
+        sweep_expired(&mut guard);
         Ok(f(&mut guard))
     }
 
+    async fn subscribe_until(
+        &self,
+        deadline: Option<Instant>,
+    ) -> Result<Token, errors::ReadinessError> {
+        if self.load_ready() {
+            return Err(errors::ReadinessError::FlagAlreadyReady);
+        }
+
+        // Generate a token; ensure it's not 0.
+        let token = Token(self.next_id.fetch_add(1, Ordering::Relaxed));
+
+        // Recheck readiness while holding the lock so mark_ready can't flip the flag between the
+        // check above and inserting the token.
+        let inserted = self
+            .with_tokens(|tokens| {
+                if self.load_ready() {
+                    return false;
+                }
+                tokens.insert(token, deadline);
+                true
+            })
+            .await?;
+
+        if !inserted {
+            return Err(errors::ReadinessError::FlagAlreadyReady);
+        }
+
+        Ok(token)
+    }
+
     fn load_ready(&self) -> bool {
         self.ready.load(Ordering::Acquire)
     }
@@ -99,45 +206,31 @@ impl Readiness for ReadinessFlag {
             return true;
         }
 
-        if let Ok(tokens) = self.tokens.try_lock()
-            && tokens.is_empty()
-        {
-            let was_ready = self.ready.swap(true, Ordering::AcqRel);
-            drop(tokens);
-            if !was_ready {
-                let _ = self.tx.send(true);
+        if let Ok(mut tokens) = self.tokens.try_lock() {
+            sweep_expired(&mut tokens);
+            if tokens.is_empty() {
+                let was_ready = self.ready.swap(true, Ordering::AcqRel);
+                drop(tokens);
+                if !was_ready {
+                    self.notify_ready();
+                }
+                return true;
             }
-            return true;
         }
 
         self.load_ready()
     }
 
-    async fn subscribe(&self) -> Result<Token, errors::ReadinessError> {
-        if self.load_ready() {
-            return Err(errors::ReadinessError::FlagAlreadyReady);
-        }
-
-        // Generate a token; ensure it's not 0.
-        let token = Token(self.next_id.fetch_add(1, Ordering::Relaxed));
-
-        // Recheck readiness while holding the lock so mark_ready can't flip the flag between the
-        // check above and inserting the token.
-        let inserted = self
-            .with_tokens(|tokens| {
-                if self.load_ready() {
-                    return false;
-                }
-                tokens.insert(token);
-                true
-            })
-            .await?;
+    fn peek_ready(&self) -> bool {
+        self.load_ready()
+    }
 
-        if !inserted {
-            return Err(errors::ReadinessError::FlagAlreadyReady);
-        }
+    async fn subscribe(&self) -> Result<Token, errors::ReadinessError> {
+        self.subscribe_until(None).await
+    }
 
-        Ok(token)
+    async fn subscribe_with_ttl(&self, ttl: Duration) -> Result<Token, errors::ReadinessError> {
+        self.subscribe_until(Some(Instant::now() + ttl)).await
     }
 
     async fn mark_ready(&self, token: Token) -> Result<bool, errors::ReadinessError> {
@@ -150,7 +243,7 @@ impl Readiness for ReadinessFlag {
 
         let marked = self
             .with_tokens(|set| {
-                if !set.remove(&token) {
+                if set.remove(&token).is_none() {
                     return false; // invalid or already used
                 }
                 self.ready.store(true, Ordering::Release);
@@ -161,11 +254,14 @@ impl Readiness for ReadinessFlag {
         if !marked {
             return Ok(false);
         }
-        // Best-effort broadcast; ignore error if there are no receivers.
-        let _ = self.tx.send(true);
+        self.notify_ready();
         Ok(true)
     }
 
+    async fn unsubscribe(&self, token: Token) -> Result<bool, errors::ReadinessError> {
+        self.with_tokens(|set| set.remove(&token).is_some()).await
+    }
+
     async fn wait_ready(&self) {
         if self.is_ready() {
             return;
@@ -182,26 +278,102 @@ impl Readiness for ReadinessFlag {
             }
         }
     }
+
+    async fn reset(&self) -> Result<(), errors::ReadinessError> {
+        self.with_tokens(|set| {
+            set.clear();
+            self.ready.store(false, Ordering::Release);
+        })
+        .await?;
+        // Best-effort broadcast; ignore error if there are no receivers.
+        let _ = self.tx.send(false);
+        Ok(())
+    }
+}
+
+/// A derived readiness signal that is ready once all of its children are.
+///
+/// The group has no tokens of its own: `subscribe`, `subscribe_with_ttl`, `mark_ready`,
+/// `unsubscribe` and `reset` return `Unsupported`; drive the children instead. An empty group is
+/// always ready.
+pub struct ReadinessGroup {
+    children: Vec<Arc<dyn Readiness>>,
+}
+
+impl ReadinessGroup {
+    pub fn new(children: Vec<Arc<dyn Readiness>>) -> Self {
+        Self { children }
+    }
+}
+
+impl fmt::Debug for ReadinessGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadinessGroup")
+            .field("children", &self.children.len())
+            .field("ready", &self.peek_ready())
+            .finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl Readiness for ReadinessGroup {
+    fn is_ready(&self) -> bool {
+        self.children.iter().all(|child| child.is_ready())
+    }
+
+    fn peek_ready(&self) -> bool {
+        self.children.iter().all(|child| child.peek_ready())
+    }
+
+    async fn subscribe(&self) -> Result<Token, errors::ReadinessError> {
+        Err(errors::ReadinessError::Unsupported)
+    }
+
+    async fn subscribe_with_ttl(&self, _ttl: Duration) -> Result<Token, errors::ReadinessError> {
+        Err(errors::ReadinessError::Unsupported)
+    }
+
+    async fn mark_ready(&self, _token: Token) -> Result<bool, errors::ReadinessError> {
+        Err(errors::ReadinessError::Unsupported)
+    }
+
+    async fn unsubscribe(&self, _token: Token) -> Result<bool, errors::ReadinessError> {
+        Err(errors::ReadinessError::Unsupported)
+    }
+
+    async fn wait_ready(&self) {
+        futures::future::join_all(self.children.iter().map(|child| child.wait_ready())).await;
+    }
+}
+
+/// Drop subscriptions whose TTL has elapsed.
+fn sweep_expired(tokens: &mut HashMap<Token, Option<Instant>>) {
+    let now = Instant::now();
+    tokens.retain(|_, deadline| deadline.is_none_or(|deadline| deadline > now));
 }
 
 mod errors {
     use thiserror::Error;
 
-    #[derive(Debug, Error)]
+    #[derive(Clone, Debug, Error)]
     pub enum ReadinessError {
         #[error("Failed to acquire readiness token lock")]
         TokenLockFailed,
         #[error("Flag is already ready. Impossible to subscribe")]
         FlagAlreadyReady,
+        #[error("Operation is not supported by this readiness implementation")]
+        Unsupported,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
+    use std::time::Duration;
 
     use super::Readiness;
     use super::ReadinessFlag;
+    use super::ReadinessGroup;
     use super::Token;
     use super::errors::ReadinessError;
     use assert_matches::assert_matches;
@@ -275,6 +447,19 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn peek_ready_does_not_mark_flag_ready() -> Result<(), ReadinessError> {
+        let flag = ReadinessFlag::new();
+
+        assert!(!flag.peek_ready());
+        let token = flag.subscribe().await?;
+        assert!(!flag.peek_ready());
+
+        assert!(flag.mark_ready(token).await?);
+        assert!(flag.peek_ready());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn subscribe_returns_error_when_lock_is_held() {
         let flag = ReadinessFlag::new();
@@ -289,4 +474,255 @@ mod tests {
             .expect_err("contended subscribe should report a lock failure");
         assert_matches!(err, ReadinessError::TokenLockFailed);
     }
+
+    #[tokio::test]
+    async fn custom_lock_timeout_reports_lock_failure() {
+        let flag = Arc::new(ReadinessFlag::with_lock_timeout(Duration::from_millis(1)));
+        let (locked_tx, locked_rx) = tokio::sync::oneshot::channel();
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+
+        let holder = {
+            let flag = Arc::clone(&flag);
+            tokio::spawn(async move {
+                let _guard = flag.tokens.lock().await;
+                let _ = locked_tx.send(());
+                let _ = release_rx.await;
+            })
+        };
+        locked_rx
+            .await
+            .expect("holder task should acquire the lock");
+
+        let err = flag
+            .subscribe()
+            .await
+            .expect_err("contended subscribe should report a lock failure");
+        assert_matches!(err, ReadinessError::TokenLockFailed);
+
+        let _ = release_tx.send(());
+        holder.await.expect("holder task should not panic");
+    }
+
+    #[tokio::test]
+    async fn unsubscribe_removes_known_token() -> Result<(), ReadinessError> {
+        let flag = ReadinessFlag::new();
+        let token = flag.subscribe().await?;
+
+        assert!(flag.unsubscribe(token).await?);
+        assert!(!flag.unsubscribe(token).await?);
+        assert!(!flag.mark_ready(token).await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unsubscribe_rejects_unknown_token() -> Result<(), ReadinessError> {
+        let flag = ReadinessFlag::new();
+        let _token = flag.subscribe().await?;
+
+        assert!(!flag.unsubscribe(Token(42)).await?);
+        assert!(!flag.is_ready());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unsubscribing_last_token_allows_auto_ready() -> Result<(), ReadinessError> {
+        let flag = ReadinessFlag::new();
+        let first = flag.subscribe().await?;
+        let second = flag.subscribe().await?;
+
+        assert!(flag.unsubscribe(first).await?);
+        assert!(!flag.is_ready());
+
+        assert!(flag.unsubscribe(second).await?);
+        assert!(flag.is_ready());
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn expired_token_is_swept_and_allows_auto_ready() -> Result<(), ReadinessError> {
+        let flag = ReadinessFlag::new();
+        let _token = flag.subscribe_with_ttl(Duration::from_secs(5)).await?;
+        assert!(!flag.is_ready());
+
+        tokio::time::advance(Duration::from_secs(6)).await;
+
+        assert!(flag.is_ready());
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn mark_ready_rejects_expired_token() -> Result<(), ReadinessError> {
+        let flag = ReadinessFlag::new();
+        let expiring = flag.subscribe_with_ttl(Duration::from_secs(5)).await?;
+        let _other = flag.subscribe().await?;
+
+        tokio::time::advance(Duration::from_secs(6)).await;
+
+        assert!(!flag.mark_ready(expiring).await?);
+        assert!(!flag.is_ready());
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn token_within_ttl_can_mark_ready() -> Result<(), ReadinessError> {
+        let flag = ReadinessFlag::new();
+        let token = flag.subscribe_with_ttl(Duration::from_secs(5)).await?;
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+
+        assert!(flag.mark_ready(token).await?);
+        assert!(flag.is_ready());
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn on_ready_fires_once_under_concurrent_mark_ready() -> Result<(), ReadinessError> {
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let flag = Arc::new(ReadinessFlag::new().with_on_ready({
+            let calls = Arc::clone(&calls);
+            move |elapsed| calls.lock().expect("calls lock").push(elapsed)
+        }));
+        let mut tokens = Vec::new();
+        for _ in 0..8 {
+            tokens.push(flag.subscribe().await?);
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let markers: Vec<_> = tokens
+            .into_iter()
+            .map(|token| {
+                let flag = Arc::clone(&flag);
+                tokio::spawn(async move { flag.mark_ready(token).await })
+            })
+            .collect();
+        let mut marked = 0;
+        for marker in markers {
+            if marker.await.expect("marker task should not panic")? {
+                marked += 1;
+            }
+        }
+        assert!(flag.is_ready());
+
+        let calls = calls.lock().expect("calls lock").clone();
+        assert_eq!((marked, calls.len()), (1, 1));
+        assert!(
+            calls[0] >= Duration::from_millis(20) && calls[0] < Duration::from_secs(10),
+            "implausible readiness duration {:?}",
+            calls[0]
+        );
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn on_ready_fires_from_auto_ready_only_once() -> Result<(), ReadinessError> {
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let flag = ReadinessFlag::new().with_on_ready({
+            let calls = Arc::clone(&calls);
+            move |elapsed| calls.lock().expect("calls lock").push(elapsed)
+        });
+
+        tokio::time::advance(Duration::from_secs(3)).await;
+        assert!(flag.is_ready());
+        assert!(flag.is_ready());
+
+        flag.reset().await?;
+        let token = flag.subscribe().await?;
+        assert!(flag.mark_ready(token).await?);
+
+        assert_eq!(
+            *calls.lock().expect("calls lock"),
+            vec![Duration::from_secs(3)]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn group_is_ready_only_after_all_children() -> Result<(), ReadinessError> {
+        let first = Arc::new(ReadinessFlag::new());
+        let second = Arc::new(ReadinessFlag::new());
+        let first_token = first.subscribe().await?;
+        let second_token = second.subscribe().await?;
+        let group = Arc::new(ReadinessGroup::new(vec![
+            Arc::clone(&first) as Arc<dyn Readiness>,
+            Arc::clone(&second) as Arc<dyn Readiness>,
+        ]));
+
+        let waiter = {
+            let group = Arc::clone(&group);
+            tokio::spawn(async move { group.wait_ready().await })
+        };
+        assert!(!group.is_ready());
+
+        assert!(first.mark_ready(first_token).await?);
+        assert!(!group.is_ready());
+        assert!(!waiter.is_finished());
+
+        assert!(second.mark_ready(second_token).await?);
+        waiter.await.expect("waiting task should not panic");
+        assert!(group.is_ready());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn group_rejects_token_operations() {
+        let group = ReadinessGroup::new(vec![Arc::new(ReadinessFlag::new())]);
+
+        assert_matches!(group.subscribe().await, Err(ReadinessError::Unsupported));
+        assert_matches!(
+            group.subscribe_with_ttl(Duration::from_secs(1)).await,
+            Err(ReadinessError::Unsupported)
+        );
+        assert_matches!(
+            group.mark_ready(Token(1)).await,
+            Err(ReadinessError::Unsupported)
+        );
+        assert_matches!(
+            group.unsubscribe(Token(1)).await,
+            Err(ReadinessError::Unsupported)
+        );
+        assert_matches!(group.reset().await, Err(ReadinessError::Unsupported));
+    }
+
+    #[tokio::test]
+    async fn reset_allows_subscribing_again() -> Result<(), ReadinessError> {
+        let flag = ReadinessFlag::new();
+        let token = flag.subscribe().await?;
+        assert!(flag.mark_ready(token).await?);
+
+        flag.reset().await?;
+        assert!(!flag.load_ready());
+
+        let token = flag.subscribe().await?;
+        assert!(flag.mark_ready(token).await?);
+        assert!(flag.is_ready());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reset_invalidates_outstanding_tokens() -> Result<(), ReadinessError> {
+        let flag = ReadinessFlag::new();
+        let stale = flag.subscribe().await?;
+
+        flag.reset().await?;
+        let _fresh = flag.subscribe().await?;
+
+        assert!(!flag.mark_ready(stale).await?);
+        assert!(!flag.load_ready());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reset_returns_error_when_lock_is_held() {
+        let flag = ReadinessFlag::new();
+        let _guard = flag
+            .tokens
+            .try_lock()
+            .expect("initial lock acquisition should succeed");
+
+        let err = flag
+            .reset()
+            .await
+            .expect_err("contended reset should report a lock failure");
+        assert_matches!(err, ReadinessError::TokenLockFailed);
+    }
 }
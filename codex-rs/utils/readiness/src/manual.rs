@@ -0,0 +1,164 @@
+//! In-memory [`Readiness`] for tests of code that depends on the trait.
+
+use std::sync::Mutex;
+use std::sync::atomic::AtomicI32;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use tokio::sync::watch;
+
+use crate::Readiness;
+use crate::Token;
+use crate::errors::ReadinessError;
+
+/// A [`Readiness`] whose state is flipped directly with [`ManualReadiness::set_ready`] instead of
+/// the token dance. `subscribe` and `mark_ready` behave like a fresh [`crate::ReadinessFlag`]
+/// unless overridden with [`ManualReadiness::set_subscribe_result`] or
+/// [`ManualReadiness::set_mark_ready_result`].
+#[derive(Debug)]
+pub struct ManualReadiness {
+    ready: watch::Sender<bool>,
+    next_id: AtomicI32,
+    subscribe_result: Mutex<Option<Result<Token, ReadinessError>>>,
+    mark_ready_result: Mutex<Option<Result<bool, ReadinessError>>>,
+}
+
+impl ManualReadiness {
+    /// Create a double that is not ready yet.
+    pub fn new() -> Self {
+        let (ready, _rx) = watch::channel(false);
+        Self {
+            ready,
+            next_id: AtomicI32::new(1),
+            subscribe_result: Mutex::new(None),
+            mark_ready_result: Mutex::new(None),
+        }
+    }
+
+    /// Mark the double ready and wake every `wait_ready()` caller.
+    pub fn set_ready(&self) {
+        self.ready.send_replace(true);
+    }
+
+    /// Make every later `subscribe`/`subscribe_with_ttl` call return `result`.
+    pub fn set_subscribe_result(&self, result: Result<Token, ReadinessError>) {
+        *lock(&self.subscribe_result) = Some(result);
+    }
+
+    /// Make every later `mark_ready` call return `result`. `Ok(true)` also marks the double
+    /// ready.
+    pub fn set_mark_ready_result(&self, result: Result<bool, ReadinessError>) {
+        *lock(&self.mark_ready_result) = Some(result);
+    }
+
+    fn load_ready(&self) -> bool {
+        *self.ready.borrow()
+    }
+}
+
+impl Default for ManualReadiness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[async_trait::async_trait]
+impl Readiness for ManualReadiness {
+    fn is_ready(&self) -> bool {
+        self.load_ready()
+    }
+
+    fn peek_ready(&self) -> bool {
+        self.load_ready()
+    }
+
+    async fn subscribe(&self) -> Result<Token, ReadinessError> {
+        if let Some(result) = lock(&self.subscribe_result).clone() {
+            return result;
+        }
+        if self.load_ready() {
+            return Err(ReadinessError::FlagAlreadyReady);
+        }
+        Ok(Token(self.next_id.fetch_add(1, Ordering::Relaxed)))
+    }
+
+    async fn subscribe_with_ttl(&self, _ttl: Duration) -> Result<Token, ReadinessError> {
+        self.subscribe().await
+    }
+
+    async fn mark_ready(&self, _token: Token) -> Result<bool, ReadinessError> {
+        let result = lock(&self.mark_ready_result)
+            .clone()
+            .unwrap_or_else(|| Ok(!self.load_ready()));
+        if matches!(result, Ok(true)) {
+            self.set_ready();
+        }
+        result
+    }
+
+    async fn unsubscribe(&self, _token: Token) -> Result<bool, ReadinessError> {
+        Ok(true)
+    }
+
+    async fn wait_ready(&self) {
+        let mut rx = self.ready.subscribe();
+        let _ = rx.wait_for(|ready| *ready).await;
+    }
+
+    async fn reset(&self) -> Result<(), ReadinessError> {
+        self.ready.send_replace(false);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use assert_matches::assert_matches;
+
+    use super::ManualReadiness;
+    use crate::Readiness;
+    use crate::Token;
+    use crate::errors::ReadinessError;
+
+    #[tokio::test]
+    async fn wait_ready_unblocks_after_set_ready() {
+        let readiness = Arc::new(ManualReadiness::new());
+        let waiter = {
+            let readiness = Arc::clone(&readiness);
+            tokio::spawn(async move { readiness.wait_ready().await })
+        };
+        assert!(!readiness.is_ready());
+
+        readiness.set_ready();
+
+        waiter.await.expect("waiting task should not panic");
+        assert!(readiness.is_ready());
+    }
+
+    #[tokio::test]
+    async fn configured_results_are_returned() -> Result<(), ReadinessError> {
+        let readiness = ManualReadiness::new();
+        readiness.set_subscribe_result(Ok(Token(7)));
+        readiness.set_mark_ready_result(Err(ReadinessError::TokenLockFailed));
+
+        assert_eq!(readiness.subscribe().await?, Token(7));
+        assert_matches!(
+            readiness.mark_ready(Token(7)).await,
+            Err(ReadinessError::TokenLockFailed)
+        );
+        assert!(!readiness.is_ready());
+
+        readiness.set_mark_ready_result(Ok(true));
+        assert!(readiness.mark_ready(Token(7)).await?);
+        assert!(readiness.is_ready());
+        Ok(())
+    }
+}
@@ -1,8 +1,15 @@
+use std::collections::HashMap;
 use std::fmt;
 use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::OnceLock;
 
+use anyhow::Context;
 use anyhow::Error as AnyhowError;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use codex_utils_cache::BlockingLruCache;
 use thiserror::Error;
 use tiktoken_rs::CoreBPE;
@@ -32,6 +39,12 @@ pub enum TokenizerError {
         #[source]
         source: AnyhowError,
     },
+    #[error("failed to load encoding from {}", path.display())]
+    LoadFile {
+        path: PathBuf,
+        #[source]
+        source: AnyhowError,
+    },
     #[error("failed to decode tokens")]
     Decode {
         #[source]
@@ -39,10 +52,15 @@ pub enum TokenizerError {
     },
 }
 
+const DEFAULT_MODEL_CACHE_CAPACITY: usize = 64;
+
 fn model_cache() -> &'static BlockingLruCache<String, CoreBPE> {
     static MODEL_CACHE: OnceLock<BlockingLruCache<String, CoreBPE>> = OnceLock::new();
-    MODEL_CACHE
-        .get_or_init(|| BlockingLruCache::new(NonZeroUsize::new(64).unwrap_or(NonZeroUsize::MIN)))
+    MODEL_CACHE.get_or_init(|| {
+        BlockingLruCache::new(
+            NonZeroUsize::new(DEFAULT_MODEL_CACHE_CAPACITY).unwrap_or(NonZeroUsize::MIN),
+        )
+    })
 }
 
 /// Fire-and-forget function used to pre-warm model tokenizer loading. This is done
@@ -77,6 +95,46 @@ impl Tokenizer {
         Ok(Self { inner })
     }
 
+    /// Build a tokenizer from a `.tiktoken` BPE file (one `<base64 token> <rank>`
+    /// pair per line) plus a map of special tokens to their ranks. Text is split
+    /// with the `o200k_base` pattern.
+    pub fn from_bpe_file(
+        path: &Path,
+        special_tokens: HashMap<String, u32>,
+    ) -> Result<Self, TokenizerError> {
+        let load = || -> anyhow::Result<CoreBPE> {
+            let contents = std::fs::read_to_string(path)?;
+            let mut encoder = HashMap::new();
+            for (idx, line) in contents.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let (raw, rank) = line
+                    .split_once(' ')
+                    .with_context(|| format!("line {}: expected `<token> <rank>`", idx + 1))?;
+                let token = BASE64_STANDARD
+                    .decode(raw)
+                    .with_context(|| format!("line {}: invalid base64 token", idx + 1))?;
+                let rank: u32 = rank
+                    .trim()
+                    .parse()
+                    .with_context(|| format!("line {}: invalid rank", idx + 1))?;
+                encoder.insert(token, rank);
+            }
+            CoreBPE::new(
+                encoder.into_iter().collect(),
+                special_tokens.into_iter().collect(),
+                tiktoken_rs::O200K_BASE_PAT_STR,
+            )
+        };
+
+        let inner = load().map_err(|source| TokenizerError::LoadFile {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Ok(Self { inner })
+    }
+
     /// Default to `O200kBase`
     pub fn try_default() -> Result<Self, TokenizerError> {
         Self::new(EncodingKind::O200kBase)
@@ -85,13 +143,28 @@ impl Tokenizer {
     /// Build a tokenizer using an `OpenAI` model name (maps to an encoding).
     /// Falls back to the `O200kBase` encoding when the model is unknown.
     pub fn for_model(model: &str) -> Result<Self, TokenizerError> {
+        Self::for_model_checked(model).map(|(tokenizer, _fell_back)| tokenizer)
+    }
+
+    /// Like [`Tokenizer::for_model`], but also returns `true` when `model` was
+    /// not recognized and the `O200kBase` fallback was used, so callers can
+    /// warn about likely typos.
+    pub fn for_model_checked(model: &str) -> Result<(Self, bool), TokenizerError> {
+        let fell_back = tiktoken_rs::tokenizer::get_tokenizer(model).is_none();
         let inner = model_cache().get_or_try_insert_with(model.to_owned(), || {
             match tiktoken_rs::get_bpe_from_model(model) {
                 Ok(inner) => Ok(inner),
                 Err(_model_error) => Tokenizer::new(EncodingKind::O200kBase).map(|e| e.inner),
             }
         })?;
-        Ok(Self { inner })
+        Ok((Self { inner }, fell_back))
+    }
+
+    /// Resize the process-wide cache used by [`Tokenizer::for_model`]. When
+    /// shrinking, the least recently used models are evicted. Defaults to 64.
+    /// Only effective inside a Tokio runtime, like the cache itself.
+    pub fn set_model_cache_capacity(capacity: NonZeroUsize) {
+        model_cache().with_mut(|cache| cache.resize(capacity));
     }
 
     /// Encode text to token IDs. If `with_special_tokens` is true, special
@@ -106,6 +179,52 @@ impl Tokenizer {
         raw.into_iter().map(|t| t as i32).collect()
     }
 
+    /// Encode each of `texts` to token IDs. `result[i]` holds the tokens for
+    /// `texts[i]`.
+    #[must_use]
+    pub fn encode_batch(&self, texts: &[&str], with_special_tokens: bool) -> Vec<Vec<i32>> {
+        texts
+            .iter()
+            .map(|text| self.encode(text, with_special_tokens))
+            .collect()
+    }
+
+    /// Parallel variant of [`Tokenizer::encode_batch`]. The input is split into
+    /// chunks that are encoded on the rayon thread pool; ordering is preserved.
+    #[cfg(feature = "rayon")]
+    #[must_use]
+    pub fn encode_batch_par(&self, texts: &[&str], with_special_tokens: bool) -> Vec<Vec<i32>> {
+        use rayon::prelude::*;
+
+        const CHUNK_SIZE: usize = 64;
+        texts
+            .par_chunks(CHUNK_SIZE)
+            .flat_map_iter(|chunk| {
+                chunk
+                    .iter()
+                    .map(move |text| self.encode(text, with_special_tokens))
+            })
+            .collect()
+    }
+
+    /// Encode `text` to ordinary token IDs, pairing each with the byte range it
+    /// covers in `text`. A token may cover part of a multi-byte character, so
+    /// ranges are not guaranteed to fall on `char` boundaries.
+    #[must_use]
+    pub fn encode_with_offsets(&self, text: &str) -> Vec<(i32, Range<usize>)> {
+        let mut start = 0;
+        self.encode(text, false)
+            .into_iter()
+            .map(|token| {
+                // Tokens produced by this tokenizer are always in its vocabulary.
+                let end = start + self.token_bytes(token).map_or(0, |bytes| bytes.len());
+                let range = start..end;
+                start = end;
+                (token, range)
+            })
+            .collect()
+    }
+
     /// Count tokens in `text` as a signed integer.
     #[must_use]
     pub fn count(&self, text: &str) -> i64 {
@@ -113,6 +232,101 @@ impl Tokenizer {
         i64::try_from(self.inner.encode_ordinary(text).len()).unwrap_or(i64::MAX)
     }
 
+    /// Approximate [`Tokenizer::count`] for large inputs by encoding `text` in
+    /// windows of at most `chunk_bytes` bytes and summing the counts, which
+    /// bounds peak memory to one window's tokens. `tiktoken_rs` has no
+    /// length-only encoder, so this is the cheaper path for big documents.
+    ///
+    /// Windows are cut just before whitespace when possible, where tokens
+    /// rarely span, so the result is usually exact. A cut inside a word (or a
+    /// run of whitespace) splits a token and can change the count slightly,
+    /// typically by at most one token per window.
+    #[must_use]
+    pub fn count_chunked(&self, text: &str, chunk_bytes: usize) -> i64 {
+        let mut total: i64 = 0;
+        let mut rest = text;
+        while !rest.is_empty() {
+            let chunk = next_chunk(rest, chunk_bytes.max(1));
+            total = total.saturating_add(self.count(chunk));
+            rest = &rest[chunk.len()..];
+        }
+        total
+    }
+
+    /// Update `prefix_tokens`, the ordinary tokens of `prefix`, to cover
+    /// `prefix` followed by `suffix`, and return the new token count. Meant for
+    /// live counts while text is typed: only the last few prefix tokens are
+    /// re-encoded together with `suffix`, instead of the whole buffer.
+    ///
+    /// Re-encoding that trailing window lets tokens merge across the splice,
+    /// so the result matches a full [`Tokenizer::encode`] for ordinary text. It
+    /// can drift slightly when a single pre-token reaches further back than the
+    /// window (for example a very long run of whitespace or a long word), and
+    /// that drift accumulates across appends; re-encode the whole buffer
+    /// occasionally when exact counts matter.
+    pub fn encode_incremental(
+        &self,
+        prefix: &str,
+        prefix_tokens: &mut Vec<i32>,
+        suffix: &str,
+    ) -> i64 {
+        const WINDOW_TOKENS: usize = 8;
+
+        // `None` for IDs outside the vocabulary, which means `prefix_tokens`
+        // cannot belong to `prefix`.
+        let token_len = |token: i32| self.token_bytes(token).map(|bytes| bytes.len());
+        let mut keep = prefix_tokens.len().saturating_sub(WINDOW_TOKENS);
+        let window_bytes: Option<usize> = prefix_tokens[keep..]
+            .iter()
+            .map(|&token| token_len(token))
+            .sum();
+        match window_bytes.and_then(|window_bytes| prefix.len().checked_sub(window_bytes)) {
+            Some(mut start) => {
+                // A token can end inside a multi-byte character; widen the
+                // window until it starts on a character boundary.
+                while keep > 0 && !prefix.is_char_boundary(start) {
+                    keep -= 1;
+                    match token_len(prefix_tokens[keep]) {
+                        Some(len) => start = start.saturating_sub(len),
+                        None => {
+                            keep = 0;
+                            start = 0;
+                        }
+                    }
+                }
+                if !prefix.is_char_boundary(start) {
+                    start = 0;
+                }
+                prefix_tokens.truncate(keep);
+                prefix_tokens.extend(self.encode(&format!("{}{suffix}", &prefix[start..]), false));
+            }
+            // `prefix_tokens` does not belong to `prefix`; start over.
+            None => *prefix_tokens = self.encode(&format!("{prefix}{suffix}"), false),
+        }
+        i64::try_from(prefix_tokens.len()).unwrap_or(i64::MAX)
+    }
+
+    /// Return the longest prefix of `text` that encodes to at most `max_tokens`
+    /// ordinary tokens.
+    ///
+    /// Cutting the token stream can split a multi-byte character; when that
+    /// happens, tokens are dropped one at a time until the prefix decodes.
+    #[must_use]
+    pub fn truncate_to_token_limit(&self, text: &str, max_tokens: usize) -> String {
+        let mut tokens = self.inner.encode_ordinary(text);
+        if tokens.len() <= max_tokens {
+            return text.to_string();
+        }
+        tokens.truncate(max_tokens);
+        while !tokens.is_empty() {
+            if let Ok(prefix) = self.inner.decode(tokens.clone()) {
+                return prefix;
+            }
+            tokens.pop();
+        }
+        String::new()
+    }
+
     /// Decode token IDs back to text.
     pub fn decode(&self, tokens: &[i32]) -> Result<String, TokenizerError> {
         let raw: Vec<u32> = tokens.iter().map(|t| *t as u32).collect();
@@ -120,6 +334,68 @@ impl Tokenizer {
             .decode(raw)
             .map_err(|source| TokenizerError::Decode { source })
     }
+
+    /// Like [`Tokenizer::decode`], but never fails. Meant for streamed or
+    /// truncated token windows that can start or end inside a multi-byte
+    /// character: each token is decoded to its bytes and the concatenation is
+    /// converted with [`String::from_utf8_lossy`], so only the split character
+    /// becomes U+FFFD. IDs outside the vocabulary also become U+FFFD.
+    #[must_use]
+    pub fn decode_lossy(&self, tokens: &[i32]) -> String {
+        let mut bytes = Vec::with_capacity(tokens.len() * 4);
+        for &token in tokens {
+            match self.token_bytes(token) {
+                Some(token_bytes) => bytes.extend(token_bytes),
+                None => {
+                    let mut buf = [0; 4];
+                    bytes.extend_from_slice(
+                        char::REPLACEMENT_CHARACTER.encode_utf8(&mut buf).as_bytes(),
+                    );
+                }
+            }
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    /// The raw bytes of `token`, or `None` when it is not in the vocabulary.
+    /// The bytes need not be valid UTF-8 on their own.
+    fn token_bytes(&self, token: i32) -> Option<Vec<u8>> {
+        let rank = u32::try_from(token).ok()?;
+        match self.inner.decode(vec![rank]) {
+            Ok(text) => Some(text.into_bytes()),
+            Err(err) if is_unknown_token_error(&err) => None,
+            // A known token holding part of a multi-byte character. tiktoken-rs
+            // only exposes its byte-level decoder through this function, which
+            // is safe here because the ID was just checked.
+            Err(_) => self.inner._decode_native_and_split(vec![rank]).next(),
+        }
+    }
+}
+
+// tiktoken-rs keeps its `DecodeKeyError` private, so an ID missing from the
+// vocabulary can only be told apart from invalid UTF-8 by its message.
+fn is_unknown_token_error(err: &AnyhowError) -> bool {
+    err.to_string().starts_with("Invalid token for decoding")
+}
+
+// Longest prefix of `text` within `max_bytes` that ends on a `char` boundary,
+// preferably right before whitespace. Always makes progress, even when the
+// first character is wider than `max_bytes`.
+fn next_chunk(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    if end == 0 {
+        end = text.chars().next().map_or(text.len(), char::len_utf8);
+    }
+    match text[..end].rfind(char::is_whitespace) {
+        Some(idx) if idx > 0 => &text[..idx],
+        _ => &text[..end],
+    }
 }
 
 impl fmt::Debug for Tokenizer {
@@ -160,6 +436,229 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn encode_batch_matches_individual_encodes() -> Result<(), TokenizerError> {
+        let tok = Tokenizer::new(EncodingKind::Cl100kBase)?;
+        let texts = ["hello world", "", "emoji 🚀 and 漢字", "<|endoftext|>"];
+
+        for with_special_tokens in [false, true] {
+            let expected: Vec<Vec<i32>> = texts
+                .iter()
+                .map(|text| tok.encode(text, with_special_tokens))
+                .collect();
+            assert_eq!(tok.encode_batch(&texts, with_special_tokens), expected);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn encode_batch_par_preserves_order() -> Result<(), TokenizerError> {
+        let tok = Tokenizer::new(EncodingKind::Cl100kBase)?;
+        let owned: Vec<String> = (0..500).map(|i| format!("line number {i}")).collect();
+        let texts: Vec<&str> = owned.iter().map(String::as_str).collect();
+
+        assert_eq!(
+            tok.encode_batch_par(&texts, false),
+            tok.encode_batch(&texts, false)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn count_chunked_matches_count_at_whitespace_cuts() -> Result<(), TokenizerError> {
+        let tok = Tokenizer::new(EncodingKind::Cl100kBase)?;
+        let text = "The quick brown fox jumps over the lazy dog. ".repeat(200);
+
+        assert_eq!(
+            [16, 64, 1024, text.len()].map(|chunk_bytes| tok.count_chunked(&text, chunk_bytes)),
+            [tok.count(&text); 4]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn count_chunked_stays_close_when_cutting_tokens() -> Result<(), TokenizerError> {
+        let tok = Tokenizer::new(EncodingKind::Cl100kBase)?;
+        let text = "abcdefghij漢字🚀".repeat(100);
+        let chunk_bytes = 7;
+        let windows = i64::try_from(text.len().div_ceil(chunk_bytes)).unwrap_or(i64::MAX);
+
+        let delta = (tok.count_chunked(&text, chunk_bytes) - tok.count(&text)).abs();
+        assert!(delta <= windows, "delta {delta} exceeds {windows} windows");
+        assert_eq!(tok.count_chunked("", chunk_bytes), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn encode_incremental_tracks_full_encodes() -> Result<(), TokenizerError> {
+        let tok = Tokenizer::new(EncodingKind::Cl100kBase)?;
+        let appends = [
+            "Hello",
+            ",",
+            " wor",
+            "ld",
+            "!\n",
+            "Let's",
+            " refactor",
+            " the",
+            " token",
+            "izer",
+            " crate",
+            " to",
+            " count",
+            " 漢",
+            "字",
+            " and",
+            " 🚀",
+            "s.",
+        ];
+
+        let mut text = String::new();
+        let mut tokens = Vec::new();
+        for suffix in appends {
+            let count = tok.encode_incremental(&text, &mut tokens, suffix);
+            text.push_str(suffix);
+            assert_eq!(count, tok.count(&text), "after appending {suffix:?}");
+        }
+        assert_eq!(tokens, tok.encode(&text, false));
+        Ok(())
+    }
+
+    #[test]
+    fn encode_incremental_recovers_from_mismatched_tokens() -> Result<(), TokenizerError> {
+        let tok = Tokenizer::new(EncodingKind::Cl100kBase)?;
+        let mut tokens = tok.encode("a much longer prefix than the text", false);
+
+        let count = tok.encode_incremental("short", &mut tokens, " text");
+        assert_eq!(count, tok.count("short text"));
+        assert_eq!(tokens, tok.encode("short text", false));
+        Ok(())
+    }
+
+    #[test]
+    fn encode_incremental_recovers_from_unknown_token_ids() -> Result<(), TokenizerError> {
+        let tok = Tokenizer::new(EncodingKind::Cl100kBase)?;
+        let mut tokens = tok.encode("short", false);
+        tokens.push(i32::MAX);
+
+        let count = tok.encode_incremental("short", &mut tokens, " text");
+        assert_eq!(count, tok.count("short text"));
+        assert_eq!(tokens, tok.encode("short text", false));
+        Ok(())
+    }
+
+    #[test]
+    fn decode_lossy_replaces_truncated_character() -> Result<(), TokenizerError> {
+        let tok = Tokenizer::new(EncodingKind::Cl100kBase)?;
+        let text = "ok 🦀";
+        let mut tokens = tok.encode(text, false);
+        // The crab spans several byte tokens; dropping the last one splits it.
+        tokens.pop();
+        assert!(tok.decode(&tokens).is_err());
+
+        assert_eq!(tok.decode_lossy(&tokens), "ok \u{FFFD}");
+        assert_eq!(tok.decode_lossy(&tok.encode(text, false)), text);
+        Ok(())
+    }
+
+    #[test]
+    fn decode_lossy_keeps_text_after_window_starting_mid_character() -> Result<(), TokenizerError> {
+        let tok = Tokenizer::new(EncodingKind::Cl100kBase)?;
+        let tokens = tok.encode("🦀 and the rest", false);
+        assert!(tok.decode(&tokens[1..]).is_err());
+
+        // Each orphaned continuation byte becomes its own U+FFFD.
+        let lossy = tok.decode_lossy(&tokens[1..]);
+        assert!(lossy.starts_with(char::REPLACEMENT_CHARACTER), "{lossy:?}");
+        assert_eq!(
+            lossy.trim_start_matches(char::REPLACEMENT_CHARACTER),
+            " and the rest"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decode_lossy_replaces_unknown_ids_in_the_middle() -> Result<(), TokenizerError> {
+        let tok = Tokenizer::new(EncodingKind::Cl100kBase)?;
+        let mut tokens = tok.encode("hello", false);
+        tokens.extend([i32::MAX, -1]);
+        tokens.extend(tok.encode(" world", false));
+
+        assert_eq!(tok.decode_lossy(&tokens), "hello\u{FFFD}\u{FFFD} world");
+        Ok(())
+    }
+
+    #[test]
+    fn truncate_to_token_limit_ascii() -> Result<(), TokenizerError> {
+        let tok = Tokenizer::new(EncodingKind::Cl100kBase)?;
+        let s = "hello world";
+        assert_eq!(tok.truncate_to_token_limit(s, 3), s);
+        assert_eq!(tok.truncate_to_token_limit(s, 2), s);
+        assert_eq!(tok.truncate_to_token_limit(s, 1), "hello");
+        assert_eq!(tok.truncate_to_token_limit(s, 0), "");
+        Ok(())
+    }
+
+    #[test]
+    fn truncate_to_token_limit_backs_off_multibyte() -> Result<(), TokenizerError> {
+        let tok = Tokenizer::new(EncodingKind::Cl100kBase)?;
+        let s = "ok 🚀 漢字";
+        let total = tok.encode(s, false).len();
+        assert_eq!(tok.truncate_to_token_limit(s, total), s);
+
+        for limit in 0..total {
+            let prefix = tok.truncate_to_token_limit(s, limit);
+            assert!(s.starts_with(&prefix), "{prefix:?} is not a prefix");
+            assert!(tok.encode(&prefix, false).len() <= limit);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn encode_with_offsets_covers_input_contiguously() -> Result<(), TokenizerError> {
+        let tok = Tokenizer::new(EncodingKind::Cl100kBase)?;
+        let s = "hello wörld 🚀 漢字!";
+        let with_offsets = tok.encode_with_offsets(s);
+
+        let ids: Vec<i32> = with_offsets.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, tok.encode(s, false));
+
+        let mut expected_start = 0;
+        for (_, range) in &with_offsets {
+            assert_eq!(range.start, expected_start);
+            assert!(range.end > range.start);
+            expected_start = range.end;
+        }
+        assert_eq!(expected_start, s.len());
+        Ok(())
+    }
+
+    #[test]
+    fn from_bpe_file_roundtrips_custom_vocabulary() -> Result<(), TokenizerError> {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("tiny.tiktoken");
+        // "a" = 0, "b" = 1, " " = 2, "ab" = 3
+        std::fs::write(&path, "YQ== 0\nYg== 1\nIA== 2\nYWI= 3\n").expect("write vocabulary");
+        let special_tokens = HashMap::from([("<|end|>".to_string(), 4)]);
+
+        let tok = Tokenizer::from_bpe_file(&path, special_tokens)?;
+        let ids = tok.encode("ab ab", false);
+        assert_eq!(ids, vec![3, 2, 3]);
+        assert_eq!(tok.decode(&ids)?, "ab ab");
+        assert_eq!(tok.encode("ab<|end|>", true), vec![3, 4]);
+        Ok(())
+    }
+
+    #[test]
+    fn from_bpe_file_reports_missing_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("missing.tiktoken");
+
+        let err = Tokenizer::from_bpe_file(&path, HashMap::new()).expect_err("missing file");
+        assert!(matches!(err, TokenizerError::LoadFile { path: p, .. } if p == path));
+    }
+
     #[test]
     fn model_mapping_builds_tokenizer() -> Result<(), TokenizerError> {
         // Choose a long-standing model alias that maps to cl100k_base.
@@ -179,6 +678,30 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn for_model_checked_reports_fallback() -> Result<(), TokenizerError> {
+        let (_, known_fell_back) = Tokenizer::for_model_checked("gpt-4o")?;
+        let (_, bogus_fell_back) = Tokenizer::for_model_checked("gtp-5.1-typo")?;
+        assert_eq!((known_fell_back, bogus_fell_back), (false, true));
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn model_cache_capacity_evicts_least_recent_model() -> Result<(), TokenizerError> {
+        Tokenizer::set_model_cache_capacity(NonZeroUsize::MIN);
+
+        Tokenizer::for_model("gpt-4o")?;
+        Tokenizer::for_model("gpt-3.5-turbo")?;
+        let cached: Vec<String> =
+            model_cache().with_mut(|cache| cache.iter().map(|(model, _)| model.clone()).collect());
+
+        Tokenizer::set_model_cache_capacity(
+            NonZeroUsize::new(DEFAULT_MODEL_CACHE_CAPACITY).unwrap_or(NonZeroUsize::MIN),
+        );
+        assert_eq!(cached, vec!["gpt-3.5-turbo".to_string()]);
+        Ok(())
+    }
+
     #[test]
     fn warm_model_cache_without_runtime_is_noop() {
         warm_model_cache("gpt-5");
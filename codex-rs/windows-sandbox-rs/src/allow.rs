@@ -67,6 +67,7 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            allow_hosts: Vec::new(),
         };
 
         let allow = compute_allow_paths(&policy, &command_cwd, &command_cwd, &HashMap::new());
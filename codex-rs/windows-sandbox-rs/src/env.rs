@@ -107,7 +107,11 @@ fn ensure_denybin(tools: &[&str], denybin_dir: Option<&Path>) -> Result<PathBuf>
     Ok(base)
 }
 
-pub fn apply_no_network_to_env(env_map: &mut HashMap<String, String>) -> Result<()> {
+/// Points every proxy variable at a dead local port so that network access
+/// fails, except for `allow_hosts`, which are added to `NO_PROXY` so they are
+/// reached directly. The package-manager offline switches are only applied
+/// when nothing is allowed, since an allowed host is typically a mirror.
+fn apply_no_network_proxy_env(env_map: &mut HashMap<String, String>, allow_hosts: &[String]) {
     env_map.insert("SBX_NONET_ACTIVE".into(), "1".into());
     env_map
         .entry("HTTP_PROXY".into())
@@ -118,21 +122,36 @@ pub fn apply_no_network_to_env(env_map: &mut HashMap<String, String>) -> Result<
     env_map
         .entry("ALL_PROXY".into())
         .or_insert_with(|| "http://127.0.0.1:9".into());
-    env_map
+    let no_proxy = env_map
         .entry("NO_PROXY".into())
         .or_insert_with(|| "localhost,127.0.0.1,::1".into());
-    env_map
-        .entry("PIP_NO_INDEX".into())
-        .or_insert_with(|| "1".into());
+    for host in allow_hosts {
+        if !no_proxy
+            .split(',')
+            .any(|existing| existing.trim().eq_ignore_ascii_case(host))
+        {
+            if !no_proxy.is_empty() {
+                no_proxy.push(',');
+            }
+            no_proxy.push_str(host);
+        }
+    }
+    if allow_hosts.is_empty() {
+        env_map
+            .entry("PIP_NO_INDEX".into())
+            .or_insert_with(|| "1".into());
+        env_map
+            .entry("NPM_CONFIG_OFFLINE".into())
+            .or_insert_with(|| "true".into());
+        env_map
+            .entry("CARGO_NET_OFFLINE".into())
+            .or_insert_with(|| "true".into());
+    } else {
+        env_map.insert("SBX_NONET_ALLOW_HOSTS".into(), allow_hosts.join(","));
+    }
     env_map
         .entry("PIP_DISABLE_PIP_VERSION_CHECK".into())
         .or_insert_with(|| "1".into());
-    env_map
-        .entry("NPM_CONFIG_OFFLINE".into())
-        .or_insert_with(|| "true".into());
-    env_map
-        .entry("CARGO_NET_OFFLINE".into())
-        .or_insert_with(|| "true".into());
     env_map
         .entry("GIT_HTTP_PROXY".into())
         .or_insert_with(|| "http://127.0.0.1:9".into());
@@ -145,6 +164,13 @@ pub fn apply_no_network_to_env(env_map: &mut HashMap<String, String>) -> Result<
     env_map
         .entry("GIT_ALLOW_PROTOCOLS".into())
         .or_insert_with(|| "".into());
+}
+
+pub fn apply_no_network_to_env(
+    env_map: &mut HashMap<String, String>,
+    allow_hosts: &[String],
+) -> Result<()> {
+    apply_no_network_proxy_env(env_map, allow_hosts);
 
     // Block interactive network tools that bypass HTTP(S) proxy settings, but
     // allow curl/wget to run so commands like `curl --version` still succeed.
@@ -163,3 +189,56 @@ pub fn apply_no_network_to_env(env_map: &mut HashMap<String, String>) -> Result<
     reorder_pathext_for_stubs(env_map);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::apply_no_network_proxy_env;
+    use std::collections::HashMap;
+
+    #[test]
+    fn allow_hosts_bypass_the_blocking_proxy() {
+        let mut env_map = HashMap::new();
+        apply_no_network_proxy_env(
+            &mut env_map,
+            &["mirror.internal".to_string(), "localhost".to_string()],
+        );
+
+        assert_eq!(
+            (
+                env_map.get("NO_PROXY").map(String::as_str),
+                env_map.get("SBX_NONET_ALLOW_HOSTS").map(String::as_str),
+                env_map.get("HTTPS_PROXY").map(String::as_str),
+                env_map.contains_key("PIP_NO_INDEX"),
+                env_map.contains_key("CARGO_NET_OFFLINE"),
+            ),
+            (
+                Some("localhost,127.0.0.1,::1,mirror.internal"),
+                Some("mirror.internal,localhost"),
+                Some("http://127.0.0.1:9"),
+                false,
+                false,
+            )
+        );
+    }
+
+    #[test]
+    fn empty_allow_list_keeps_everything_offline() {
+        let mut env_map = HashMap::new();
+        apply_no_network_proxy_env(&mut env_map, &[]);
+
+        assert_eq!(
+            (
+                env_map.get("NO_PROXY").map(String::as_str),
+                env_map.contains_key("SBX_NONET_ALLOW_HOSTS"),
+                env_map.get("PIP_NO_INDEX").map(String::as_str),
+                env_map.get("CARGO_NET_OFFLINE").map(String::as_str),
+            ),
+            (
+                Some("localhost,127.0.0.1,::1"),
+                false,
+                Some("1"),
+                Some("true")
+            )
+        );
+    }
+}
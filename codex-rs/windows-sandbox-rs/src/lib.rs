@@ -13,26 +13,56 @@ pub use windows_impl::preflight_audit_everyone_writable;
 #[cfg(target_os = "windows")]
 pub use windows_impl::run_windows_sandbox_capture;
 #[cfg(target_os = "windows")]
+pub use windows_impl::run_windows_sandbox_stream;
+#[cfg(target_os = "windows")]
+pub use windows_impl::AceChange;
+#[cfg(target_os = "windows")]
+pub use windows_impl::AceTrustee;
+#[cfg(target_os = "windows")]
 pub use windows_impl::CaptureResult;
+#[cfg(target_os = "windows")]
+pub use windows_impl::PlannedAce;
+#[cfg(target_os = "windows")]
+pub use windows_impl::SandboxRunOptions;
+#[cfg(target_os = "windows")]
+pub use windows_impl::Stream;
+#[cfg(target_os = "windows")]
+pub use windows_impl::StreamResult;
 
 #[cfg(not(target_os = "windows"))]
 pub use stub::preflight_audit_everyone_writable;
 #[cfg(not(target_os = "windows"))]
 pub use stub::run_windows_sandbox_capture;
 #[cfg(not(target_os = "windows"))]
+pub use stub::run_windows_sandbox_stream;
+#[cfg(not(target_os = "windows"))]
 pub use stub::world_writable_warning_details;
 #[cfg(not(target_os = "windows"))]
+pub use stub::AceChange;
+#[cfg(not(target_os = "windows"))]
+pub use stub::AceTrustee;
+#[cfg(not(target_os = "windows"))]
 pub use stub::CaptureResult;
+#[cfg(not(target_os = "windows"))]
+pub use stub::PlannedAce;
+#[cfg(not(target_os = "windows"))]
+pub use stub::SandboxRunOptions;
+#[cfg(not(target_os = "windows"))]
+pub use stub::Stream;
+#[cfg(not(target_os = "windows"))]
+pub use stub::StreamResult;
 
 #[cfg(target_os = "windows")]
 mod windows_impl {
     use super::acl::add_allow_ace;
     use super::acl::allow_null_device;
+    use super::acl::path_has_write_allow_for_sid;
     use super::acl::revoke_ace;
     use super::allow::compute_allow_paths;
     use super::audit;
     use super::cap::cap_sid_file;
     use super::cap::load_or_create_cap_sids;
+    use super::cap::CapSids;
     use super::env::apply_no_network_to_env;
     use super::env::ensure_non_interactive_pager;
     use super::env::normalize_null_device_env;
@@ -53,11 +83,13 @@ mod windows_impl {
     use std::path::Path;
     use std::path::PathBuf;
     use std::ptr;
+    use std::sync::mpsc::Sender;
     use windows_sys::Win32::Foundation::CloseHandle;
     use windows_sys::Win32::Foundation::GetLastError;
     use windows_sys::Win32::Foundation::SetHandleInformation;
     use windows_sys::Win32::Foundation::HANDLE;
     use windows_sys::Win32::Foundation::HANDLE_FLAG_INHERIT;
+    use windows_sys::Win32::Foundation::WAIT_TIMEOUT;
     use windows_sys::Win32::System::Pipes::CreatePipe;
     use windows_sys::Win32::System::Threading::CreateProcessAsUserW;
     use windows_sys::Win32::System::Threading::GetExitCodeProcess;
@@ -70,6 +102,10 @@ mod windows_impl {
 
     type PipeHandles = ((HANDLE, HANDLE), (HANDLE, HANDLE), (HANDLE, HANDLE));
 
+    /// Exit code reported for a timed-out command when the caller does not
+    /// choose one. Kept at the historical value for compatibility.
+    const DEFAULT_TIMEOUT_EXIT_CODE: i32 = 128 + 64;
+
     fn ensure_dir(p: &Path) -> Result<()> {
         if let Some(d) = p.parent() {
             std::fs::create_dir_all(d)?;
@@ -170,11 +206,200 @@ mod windows_impl {
         Ok(((in_r, in_w), (out_r, out_w), (err_r, err_w)))
     }
 
+    /// Optional settings for a sandboxed run. `Default` resolves the policy
+    /// against the command's `cwd`, applies no timeout, closes stdin right
+    /// away and really spawns the command.
+    #[derive(Debug, Clone, Default)]
+    pub struct SandboxRunOptions {
+        /// Directory the policy's writable roots are resolved against, when it
+        /// differs from the command's `cwd`.
+        pub sandbox_policy_cwd: Option<PathBuf>,
+        pub timeout_ms: Option<u64>,
+        /// Exit code reported for a timed-out command.
+        pub timeout_exit_code: Option<i32>,
+        /// Bytes fed to the child's stdin before it is closed.
+        pub stdin: Option<Vec<u8>>,
+        /// Only report the ACL changes; supported by
+        /// [`run_windows_sandbox_capture`] alone.
+        pub dry_run: bool,
+    }
+
     pub struct CaptureResult {
         pub exit_code: i32,
         pub stdout: Vec<u8>,
         pub stderr: Vec<u8>,
         pub timed_out: bool,
+        /// ACL changes the run made, in order; for a dry run, the changes a
+        /// real run would make.
+        pub ace_changes: Vec<PlannedAce>,
+    }
+
+    impl CaptureResult {
+        /// Whether the command was killed for exceeding its timeout.
+        pub fn is_timeout(&self) -> bool {
+            self.timed_out
+        }
+    }
+
+    /// How the sandbox would touch a path's ACL.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum AceChange {
+        /// Grant the trustee write access.
+        Add,
+        /// Remove that grant again once the command exits.
+        Revoke,
+    }
+
+    /// Whose access an ACL change is about.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum AceTrustee {
+        /// The capability SID of the sandbox token.
+        Capability,
+        /// The logon session SID, granted access to the NUL device for
+        /// workspace-write runs.
+        LogonSession,
+    }
+
+    /// One ACL change made by a run, or planned by a dry run, of
+    /// [`run_windows_sandbox_capture`].
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PlannedAce {
+        pub path: PathBuf,
+        pub trustee: AceTrustee,
+        pub change: AceChange,
+    }
+
+    /// Path the NUL device grants are reported under.
+    const NULL_DEVICE_PATH: &str = r"\\.\NUL";
+
+    /// The capability SID that sandbox tokens for `policy` carry.
+    fn cap_sid_for_policy<'a>(policy: &SandboxPolicy, caps: &'a CapSids) -> Result<&'a str> {
+        match policy {
+            SandboxPolicy::ReadOnly => Ok(&caps.readonly),
+            SandboxPolicy::WorkspaceWrite { .. } => Ok(&caps.workspace),
+            SandboxPolicy::DangerFullAccess => {
+                anyhow::bail!("DangerFullAccess is not supported for sandboxing")
+            }
+        }
+    }
+
+    /// SID of the current logon session, if it can be read from our token.
+    unsafe fn current_logon_sid_bytes() -> Option<Vec<u8>> {
+        let base = super::token::get_current_token_for_restriction().ok()?;
+        let bytes = super::token::get_logon_sid_bytes(base).ok();
+        CloseHandle(base);
+        bytes
+    }
+
+    fn null_device_grant(trustee: AceTrustee) -> PlannedAce {
+        PlannedAce {
+            path: PathBuf::from(NULL_DEVICE_PATH),
+            trustee,
+            change: AceChange::Add,
+        }
+    }
+
+    /// ACL changes a run under `policy` would make, in the order
+    /// [`run_windows_sandbox_stream`] makes them: NUL device access for the
+    /// logon session (workspace-write only), write access on every allowed
+    /// path whose DACL lacks it, NUL device access for the capability SID, and,
+    /// unless the policy persists its ACEs (workspace-write), revoking the path
+    /// grants after the command exits.
+    fn plan_ace_changes(
+        policy: &SandboxPolicy,
+        codex_home: &Path,
+        sandbox_policy_cwd: &Path,
+        cwd: &Path,
+        env_map: &HashMap<String, String>,
+    ) -> Result<Vec<PlannedAce>> {
+        let caps = load_or_create_cap_sids(codex_home);
+        let cap_sid = cap_sid_for_policy(policy, &caps)?;
+        let psid = unsafe { convert_string_sid_to_sid(cap_sid) }
+            .ok_or_else(|| anyhow::anyhow!("invalid capability SID {cap_sid}"))?;
+        let is_workspace_write = matches!(policy, SandboxPolicy::WorkspaceWrite { .. });
+        let persist_aces = is_workspace_write;
+
+        let mut planned = Vec::new();
+        if is_workspace_write && unsafe { current_logon_sid_bytes() }.is_some() {
+            planned.push(null_device_grant(AceTrustee::LogonSession));
+        }
+        let added: Vec<PathBuf> = compute_allow_paths(policy, sandbox_policy_cwd, cwd, env_map)
+            .into_iter()
+            .filter(|path| {
+                matches!(
+                    unsafe { path_has_write_allow_for_sid(path, psid) },
+                    Ok(false)
+                )
+            })
+            .collect();
+        planned.extend(added.iter().map(|path| PlannedAce {
+            path: path.clone(),
+            trustee: AceTrustee::Capability,
+            change: AceChange::Add,
+        }));
+        planned.push(null_device_grant(AceTrustee::Capability));
+        if !persist_aces {
+            planned.extend(added.into_iter().map(|path| PlannedAce {
+                path,
+                trustee: AceTrustee::Capability,
+                change: AceChange::Revoke,
+            }));
+        }
+        Ok(planned)
+    }
+
+    /// Which of the child's output pipes a streamed chunk came from.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Stream {
+        Stdout,
+        Stderr,
+    }
+
+    /// Outcome of [`run_windows_sandbox_stream`]; the output itself has
+    /// already been delivered through the channel.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct StreamResult {
+        pub exit_code: i32,
+        pub timed_out: bool,
+        /// ACL changes the run made, in order.
+        pub ace_changes: Vec<PlannedAce>,
+    }
+
+    impl StreamResult {
+        /// Whether the command was killed for exceeding its timeout.
+        pub fn is_timeout(&self) -> bool {
+            self.timed_out
+        }
+    }
+
+    /// Forward everything read from `handle` to `output` as it arrives.
+    fn spawn_pipe_reader(
+        handle: HANDLE,
+        stream: Stream,
+        output: Sender<(Stream, Vec<u8>)>,
+    ) -> std::thread::JoinHandle<()> {
+        std::thread::spawn(move || {
+            let mut tmp = [0u8; 8192];
+            loop {
+                let mut read_bytes: u32 = 0;
+                let ok = unsafe {
+                    windows_sys::Win32::Storage::FileSystem::ReadFile(
+                        handle,
+                        tmp.as_mut_ptr(),
+                        tmp.len() as u32,
+                        &mut read_bytes,
+                        std::ptr::null_mut(),
+                    )
+                };
+                if ok == 0 || read_bytes == 0 {
+                    break;
+                }
+                let _ = output.send((stream, tmp[..read_bytes as usize].to_vec()));
+            }
+            unsafe {
+                CloseHandle(handle);
+            }
+        })
     }
 
     pub fn preflight_audit_everyone_writable(
@@ -187,17 +412,91 @@ mod windows_impl {
 
     pub fn run_windows_sandbox_capture(
         policy_json_or_preset: &str,
-        sandbox_policy_cwd: &Path,
         codex_home: &Path,
         command: Vec<String>,
         cwd: &Path,
-        mut env_map: HashMap<String, String>,
-        timeout_ms: Option<u64>,
+        env_map: HashMap<String, String>,
+        options: SandboxRunOptions,
     ) -> Result<CaptureResult> {
+        if options.dry_run {
+            // Only report the ACL changes; no token is created and no process
+            // is spawned.
+            let policy = parse_policy(policy_json_or_preset)?;
+            let sandbox_policy_cwd = options.sandbox_policy_cwd.as_deref().unwrap_or(cwd);
+            return Ok(CaptureResult {
+                exit_code: 0,
+                stdout: Vec::new(),
+                stderr: Vec::new(),
+                timed_out: false,
+                ace_changes: plan_ace_changes(
+                    &policy,
+                    codex_home,
+                    sandbox_policy_cwd,
+                    cwd,
+                    &env_map,
+                )?,
+            });
+        }
+        let (tx, rx) = std::sync::mpsc::channel();
+        let result = run_windows_sandbox_stream(
+            policy_json_or_preset,
+            codex_home,
+            command,
+            cwd,
+            env_map,
+            options,
+            tx,
+        )?;
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        for (stream, chunk) in rx {
+            match stream {
+                Stream::Stdout => stdout.extend_from_slice(&chunk),
+                Stream::Stderr => stderr.extend_from_slice(&chunk),
+            }
+        }
+        Ok(CaptureResult {
+            exit_code: result.exit_code,
+            stdout,
+            stderr,
+            timed_out: result.timed_out,
+            ace_changes: result.ace_changes,
+        })
+    }
+
+    /// Like [`run_windows_sandbox_capture`], but sends stdout/stderr chunks to
+    /// `output` as they are read instead of buffering them. Returns once the
+    /// child has exited and both pipes have been drained.
+    pub fn run_windows_sandbox_stream(
+        policy_json_or_preset: &str,
+        codex_home: &Path,
+        command: Vec<String>,
+        cwd: &Path,
+        mut env_map: HashMap<String, String>,
+        options: SandboxRunOptions,
+        output: Sender<(Stream, Vec<u8>)>,
+    ) -> Result<StreamResult> {
+        let SandboxRunOptions {
+            sandbox_policy_cwd,
+            timeout_ms,
+            timeout_exit_code,
+            stdin,
+            dry_run,
+        } = options;
+        if dry_run {
+            anyhow::bail!("dry_run is only supported by run_windows_sandbox_capture");
+        }
+        let sandbox_policy_cwd = sandbox_policy_cwd.unwrap_or_else(|| cwd.to_path_buf());
         let policy = parse_policy(policy_json_or_preset)?;
+        let allow_hosts: Vec<String> = policy
+            .network_allow_hosts()
+            .iter()
+            .map(|host| host.trim().to_string())
+            .filter(|host| !host.is_empty())
+            .collect();
         normalize_null_device_env(&mut env_map);
         ensure_non_interactive_pager(&mut env_map);
-        apply_no_network_to_env(&mut env_map)?;
+        apply_no_network_to_env(&mut env_map, &allow_hosts)?;
         ensure_codex_home_exists(codex_home)?;
 
         let current_dir = cwd.to_path_buf();
@@ -208,59 +507,48 @@ mod windows_impl {
         let cap_sid_path = cap_sid_file(codex_home);
         let is_workspace_write = matches!(&policy, SandboxPolicy::WorkspaceWrite { .. });
 
+        let caps = load_or_create_cap_sids(codex_home);
+        let cap_sid = cap_sid_for_policy(&policy, &caps)?;
+        ensure_dir(&cap_sid_path)?;
+        fs::write(&cap_sid_path, serde_json::to_string(&caps)?)?;
         let (h_token, psid_to_use): (HANDLE, *mut c_void) = unsafe {
-            match &policy {
-                SandboxPolicy::ReadOnly => {
-                    let caps = load_or_create_cap_sids(codex_home);
-                    ensure_dir(&cap_sid_path)?;
-                    fs::write(&cap_sid_path, serde_json::to_string(&caps)?)?;
-                    let psid = convert_string_sid_to_sid(&caps.readonly).unwrap();
-                    super::token::create_readonly_token_with_cap(psid)?
-                }
-                SandboxPolicy::WorkspaceWrite { .. } => {
-                    let caps = load_or_create_cap_sids(codex_home);
-                    ensure_dir(&cap_sid_path)?;
-                    fs::write(&cap_sid_path, serde_json::to_string(&caps)?)?;
-                    let psid = convert_string_sid_to_sid(&caps.workspace).unwrap();
-                    super::token::create_workspace_write_token_with_cap(psid)?
-                }
-                SandboxPolicy::DangerFullAccess => {
-                    anyhow::bail!("DangerFullAccess is not supported for sandboxing")
-                }
+            let psid = convert_string_sid_to_sid(cap_sid).unwrap();
+            if is_workspace_write {
+                super::token::create_workspace_write_token_with_cap(psid)?
+            } else {
+                super::token::create_readonly_token_with_cap(psid)?
             }
         };
 
+        // Keep this sequence in step with `plan_ace_changes`.
+        let mut ace_changes = Vec::new();
         unsafe {
             if is_workspace_write {
-                if let Ok(base) = super::token::get_current_token_for_restriction() {
-                    if let Ok(bytes) = super::token::get_logon_sid_bytes(base) {
-                        let mut tmp = bytes.clone();
-                        let psid2 = tmp.as_mut_ptr() as *mut c_void;
-                        allow_null_device(psid2);
-                    }
-                    windows_sys::Win32::Foundation::CloseHandle(base);
+                if let Some(mut bytes) = current_logon_sid_bytes() {
+                    allow_null_device(bytes.as_mut_ptr() as *mut c_void);
+                    ace_changes.push(null_device_grant(AceTrustee::LogonSession));
                 }
             }
         }
 
         let persist_aces = is_workspace_write;
-        let allow = compute_allow_paths(&policy, sandbox_policy_cwd, &current_dir, &env_map);
+        let allow = compute_allow_paths(&policy, &sandbox_policy_cwd, &current_dir, &env_map);
         let mut guards: Vec<(PathBuf, *mut c_void)> = Vec::new();
         unsafe {
             for p in &allow {
-                if let Ok(added) = add_allow_ace(p, psid_to_use) {
-                    if added {
-                        if persist_aces {
-                            if p.is_dir() {
-                                // best-effort seeding omitted intentionally
-                            }
-                        } else {
-                            guards.push((p.clone(), psid_to_use));
-                        }
+                if let Ok(true) = add_allow_ace(p, psid_to_use) {
+                    ace_changes.push(PlannedAce {
+                        path: p.clone(),
+                        trustee: AceTrustee::Capability,
+                        change: AceChange::Add,
+                    });
+                    if !persist_aces {
+                        guards.push((p.clone(), psid_to_use));
                     }
                 }
             }
             allow_null_device(psid_to_use);
+            ace_changes.push(null_device_grant(AceTrustee::Capability));
         }
 
         let (stdin_pair, stdout_pair, stderr_pair) = unsafe { setup_stdio_pipes()? };
@@ -323,60 +611,54 @@ mod windows_impl {
 
         unsafe {
             CloseHandle(in_r);
-            // Close the parent's stdin write end so the child sees EOF immediately.
-            CloseHandle(in_w);
             CloseHandle(out_w);
             CloseHandle(err_w);
         }
 
-        let (tx_out, rx_out) = std::sync::mpsc::channel::<Vec<u8>>();
-        let (tx_err, rx_err) = std::sync::mpsc::channel::<Vec<u8>>();
-        let t_out = std::thread::spawn(move || {
-            let mut buf = Vec::new();
-            let mut tmp = [0u8; 8192];
-            loop {
-                let mut read_bytes: u32 = 0;
-                let ok = unsafe {
-                    windows_sys::Win32::Storage::FileSystem::ReadFile(
-                        out_r,
-                        tmp.as_mut_ptr(),
-                        tmp.len() as u32,
-                        &mut read_bytes,
-                        std::ptr::null_mut(),
-                    )
-                };
-                if ok == 0 || read_bytes == 0 {
-                    break;
+        // Feed stdin from a separate thread so a child that does not drain it
+        // cannot deadlock us. Without input, close the write end right away so
+        // the child sees EOF immediately.
+        let t_in = match stdin {
+            Some(input) => Some(std::thread::spawn(move || {
+                let mut remaining = input.as_slice();
+                while !remaining.is_empty() {
+                    let mut written: u32 = 0;
+                    let ok = unsafe {
+                        windows_sys::Win32::Storage::FileSystem::WriteFile(
+                            in_w,
+                            remaining.as_ptr(),
+                            remaining.len().min(u32::MAX as usize) as u32,
+                            &mut written,
+                            std::ptr::null_mut(),
+                        )
+                    };
+                    if ok == 0 || written == 0 {
+                        break;
+                    }
+                    remaining = &remaining[written as usize..];
                 }
-                buf.extend_from_slice(&tmp[..read_bytes as usize]);
-            }
-            let _ = tx_out.send(buf);
-        });
-        let t_err = std::thread::spawn(move || {
-            let mut buf = Vec::new();
-            let mut tmp = [0u8; 8192];
-            loop {
-                let mut read_bytes: u32 = 0;
-                let ok = unsafe {
-                    windows_sys::Win32::Storage::FileSystem::ReadFile(
-                        err_r,
-                        tmp.as_mut_ptr(),
-                        tmp.len() as u32,
-                        &mut read_bytes,
-                        std::ptr::null_mut(),
-                    )
-                };
-                if ok == 0 || read_bytes == 0 {
-                    break;
+                unsafe {
+                    CloseHandle(in_w);
+                }
+            })),
+            None => {
+                unsafe {
+                    CloseHandle(in_w);
                 }
-                buf.extend_from_slice(&tmp[..read_bytes as usize]);
+                None
             }
-            let _ = tx_err.send(buf);
-        });
+        };
+
+        let t_out = spawn_pipe_reader(out_r, Stream::Stdout, output.clone());
+        let t_err = spawn_pipe_reader(err_r, Stream::Stderr, output);
 
-        let timeout = timeout_ms.map(|ms| ms as u32).unwrap_or(INFINITE);
+        // Clamp so that a huge timeout never wraps around to INFINITE or a
+        // tiny value.
+        let timeout = timeout_ms
+            .map(|ms| ms.min(u64::from(INFINITE - 1)) as u32)
+            .unwrap_or(INFINITE);
         let res = unsafe { WaitForSingleObject(pi.hProcess, timeout) };
-        let timed_out = res == 0x0000_0102;
+        let timed_out = res == WAIT_TIMEOUT;
         let mut exit_code_u32: u32 = 1;
         if !timed_out {
             unsafe {
@@ -397,12 +679,13 @@ mod windows_impl {
             }
             CloseHandle(h_token);
         }
+        if let Some(t_in) = t_in {
+            let _ = t_in.join();
+        }
         let _ = t_out.join();
         let _ = t_err.join();
-        let stdout = rx_out.recv().unwrap_or_default();
-        let stderr = rx_err.recv().unwrap_or_default();
         let exit_code = if timed_out {
-            128 + 64
+            timeout_exit_code.unwrap_or(DEFAULT_TIMEOUT_EXIT_CODE)
         } else {
             exit_code_u32 as i32
         };
@@ -417,17 +700,227 @@ mod windows_impl {
             unsafe {
                 for (p, sid) in guards {
                     revoke_ace(&p, sid);
+                    ace_changes.push(PlannedAce {
+                        path: p,
+                        trustee: AceTrustee::Capability,
+                        change: AceChange::Revoke,
+                    });
                 }
             }
         }
 
-        Ok(CaptureResult {
+        Ok(StreamResult {
             exit_code,
-            stdout,
-            stderr,
             timed_out,
+            ace_changes,
         })
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::null_device_grant;
+        use super::run_windows_sandbox_capture;
+        use super::run_windows_sandbox_stream;
+        use super::AceChange;
+        use super::AceTrustee;
+        use super::SandboxRunOptions;
+        use super::Stream;
+        use super::StreamResult;
+        use std::collections::HashMap;
+
+        #[test]
+        fn passes_stdin_to_child() {
+            let root = std::env::temp_dir().join(format!(
+                "codex-windows-sandbox-stdin-{}",
+                std::process::id()
+            ));
+            let codex_home = root.join("codex-home");
+            std::fs::create_dir_all(&codex_home).unwrap();
+            let env_map: HashMap<String, String> = std::env::vars().collect();
+
+            let capture = run_windows_sandbox_capture(
+                "read-only",
+                &codex_home,
+                vec!["findstr".to_string(), "^".to_string()],
+                &root,
+                env_map,
+                SandboxRunOptions {
+                    timeout_ms: Some(30_000),
+                    stdin: Some(b"hello from stdin\r\n".to_vec()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            let _ = std::fs::remove_dir_all(&root);
+
+            assert_eq!(
+                (
+                    capture.exit_code,
+                    String::from_utf8_lossy(&capture.stdout).into_owned()
+                ),
+                (0, "hello from stdin\r\n".to_string())
+            );
+        }
+
+        #[test]
+        fn streams_output_in_chunks() {
+            let root = std::env::temp_dir().join(format!(
+                "codex-windows-sandbox-stream-{}",
+                std::process::id()
+            ));
+            let codex_home = root.join("codex-home");
+            std::fs::create_dir_all(&codex_home).unwrap();
+            let env_map: HashMap<String, String> = std::env::vars().collect();
+            let (tx, rx) = std::sync::mpsc::channel();
+
+            let result = run_windows_sandbox_stream(
+                "read-only",
+                &codex_home,
+                vec![
+                    "cmd".to_string(),
+                    "/c".to_string(),
+                    "echo first& ping -n 2 127.0.0.1 >NUL& echo second".to_string(),
+                ],
+                &root,
+                env_map,
+                SandboxRunOptions {
+                    timeout_ms: Some(30_000),
+                    ..Default::default()
+                },
+                tx,
+            )
+            .unwrap();
+            let _ = std::fs::remove_dir_all(&root);
+
+            let stdout_chunks: Vec<Vec<u8>> = rx
+                .into_iter()
+                .filter(|(stream, _)| *stream == Stream::Stdout)
+                .map(|(_, chunk)| chunk)
+                .collect();
+            assert_eq!(
+                (result, stdout_chunks.len() >= 2, stdout_chunks.concat()),
+                (
+                    StreamResult {
+                        exit_code: 0,
+                        timed_out: false,
+                        ace_changes: vec![null_device_grant(AceTrustee::Capability)],
+                    },
+                    true,
+                    b"first\r\nsecond\r\n".to_vec()
+                )
+            );
+        }
+
+        #[test]
+        fn timeout_reports_chosen_exit_code() {
+            let root = std::env::temp_dir().join(format!(
+                "codex-windows-sandbox-timeout-{}",
+                std::process::id()
+            ));
+            let codex_home = root.join("codex-home");
+            std::fs::create_dir_all(&codex_home).unwrap();
+            let env_map: HashMap<String, String> = std::env::vars().collect();
+
+            let capture = run_windows_sandbox_capture(
+                "read-only",
+                &codex_home,
+                vec![
+                    "ping".to_string(),
+                    "-n".to_string(),
+                    "30".to_string(),
+                    "127.0.0.1".to_string(),
+                ],
+                &root,
+                env_map,
+                SandboxRunOptions {
+                    timeout_ms: Some(200),
+                    timeout_exit_code: Some(124),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            let _ = std::fs::remove_dir_all(&root);
+
+            assert_eq!(
+                (capture.is_timeout(), capture.timed_out, capture.exit_code),
+                (true, true, 124)
+            );
+        }
+
+        #[test]
+        fn dry_run_reports_planned_aces_without_spawning() {
+            let root = std::env::temp_dir().join(format!(
+                "codex-windows-sandbox-dry-run-{}",
+                std::process::id()
+            ));
+            let codex_home = root.join("codex-home");
+            std::fs::create_dir_all(&codex_home).unwrap();
+            let marker = root.join("spawned.txt");
+            let env_map: HashMap<String, String> = std::env::vars().collect();
+
+            let capture = run_windows_sandbox_capture(
+                "workspace-write",
+                &codex_home,
+                vec![
+                    "cmd".to_string(),
+                    "/c".to_string(),
+                    format!("echo spawned> \"{}\"", marker.display()),
+                ],
+                &root,
+                env_map,
+                SandboxRunOptions {
+                    timeout_ms: Some(30_000),
+                    dry_run: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            let spawned = marker.exists();
+            let _ = std::fs::remove_dir_all(&root);
+
+            assert!(capture
+                .ace_changes
+                .iter()
+                .any(|ace| ace.path == root && ace.change == AceChange::Add));
+            assert_eq!(
+                (capture.exit_code, capture.stdout, capture.stderr, spawned,),
+                (0, Vec::new(), Vec::new(), false)
+            );
+        }
+
+        #[test]
+        fn dry_run_plan_matches_real_run() {
+            let root = std::env::temp_dir()
+                .join(format!("codex-windows-sandbox-plan-{}", std::process::id()));
+            let codex_home = root.join("codex-home");
+            std::fs::create_dir_all(&codex_home).unwrap();
+            let env_map: HashMap<String, String> = std::env::vars().collect();
+            let run = |dry_run| {
+                run_windows_sandbox_capture(
+                    "workspace-write",
+                    &codex_home,
+                    vec!["cmd".to_string(), "/c".to_string(), "exit 0".to_string()],
+                    &root,
+                    env_map.clone(),
+                    SandboxRunOptions {
+                        timeout_ms: Some(30_000),
+                        dry_run,
+                        ..Default::default()
+                    },
+                )
+                .unwrap()
+            };
+
+            let plan = run(true).ace_changes;
+            let real = run(false);
+            // Workspace-write grants persist, so the cwd is no longer planned.
+            let replan = run(true).ace_changes;
+            let _ = std::fs::remove_dir_all(&root);
+
+            assert_eq!((real.exit_code, real.ace_changes), (0, plan));
+            assert!(!replan.iter().any(|ace| ace.path == root));
+        }
+    }
 }
 
 #[cfg(not(target_os = "windows"))]
@@ -436,6 +929,17 @@ mod stub {
     use anyhow::Result;
     use std::collections::HashMap;
     use std::path::Path;
+    use std::path::PathBuf;
+    use std::sync::mpsc::Sender;
+
+    #[derive(Debug, Clone, Default)]
+    pub struct SandboxRunOptions {
+        pub sandbox_policy_cwd: Option<PathBuf>,
+        pub timeout_ms: Option<u64>,
+        pub timeout_exit_code: Option<i32>,
+        pub stdin: Option<Vec<u8>>,
+        pub dry_run: bool,
+    }
 
     #[derive(Debug, Default)]
     pub struct CaptureResult {
@@ -443,28 +947,86 @@ mod stub {
         pub stdout: Vec<u8>,
         pub stderr: Vec<u8>,
         pub timed_out: bool,
+        pub ace_changes: Vec<PlannedAce>,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum AceChange {
+        Add,
+        Revoke,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum AceTrustee {
+        Capability,
+        LogonSession,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PlannedAce {
+        pub path: PathBuf,
+        pub trustee: AceTrustee,
+        pub change: AceChange,
+    }
+
+    impl CaptureResult {
+        /// Whether the command was killed for exceeding its timeout.
+        pub fn is_timeout(&self) -> bool {
+            self.timed_out
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Stream {
+        Stdout,
+        Stderr,
+    }
+
+    #[derive(Debug, Default, Clone, PartialEq, Eq)]
+    pub struct StreamResult {
+        pub exit_code: i32,
+        pub timed_out: bool,
+        pub ace_changes: Vec<PlannedAce>,
+    }
+
+    impl StreamResult {
+        /// Whether the command was killed for exceeding its timeout.
+        pub fn is_timeout(&self) -> bool {
+            self.timed_out
+        }
     }
 
     pub fn preflight_audit_everyone_writable(
         _cwd: &Path,
         _env_map: &HashMap<String, String>,
         _logs_base_dir: Option<&Path>,
-    ) -> Result<Vec<std::path::PathBuf>> {
+    ) -> Result<Vec<PathBuf>> {
         bail!("Windows sandbox is only available on Windows")
     }
 
     pub fn run_windows_sandbox_capture(
         _policy_json_or_preset: &str,
-        _sandbox_policy_cwd: &Path,
         _codex_home: &Path,
         _command: Vec<String>,
         _cwd: &Path,
         _env_map: HashMap<String, String>,
-        _timeout_ms: Option<u64>,
+        _options: SandboxRunOptions,
     ) -> Result<CaptureResult> {
         bail!("Windows sandbox is only available on Windows")
     }
 
+    pub fn run_windows_sandbox_stream(
+        _policy_json_or_preset: &str,
+        _codex_home: &Path,
+        _command: Vec<String>,
+        _cwd: &Path,
+        _env_map: HashMap<String, String>,
+        _options: SandboxRunOptions,
+        _output: Sender<(Stream, Vec<u8>)>,
+    ) -> Result<StreamResult> {
+        bail!("Windows sandbox is only available on Windows")
+    }
+
     pub fn world_writable_warning_details(
         _codex_home: impl AsRef<Path>,
         _cwd: impl AsRef<Path>,
@@ -15,3 +15,25 @@ pub fn parse_policy(value: &str) -> Result<SandboxPolicy> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::parse_policy;
+    use super::SandboxPolicy;
+
+    #[test]
+    fn workspace_write_json_carries_allow_hosts() {
+        let json = r#"{"type":"workspace-write","network_access":false,"allow_hosts":["mirror.internal"]}"#;
+
+        assert_eq!(
+            parse_policy(json).unwrap(),
+            SandboxPolicy::WorkspaceWrite {
+                writable_roots: Vec::new(),
+                network_access: false,
+                exclude_tmpdir_env_var: false,
+                exclude_slash_tmp: false,
+                allow_hosts: vec!["mirror.internal".to_string()],
+            }
+        );
+    }
+}